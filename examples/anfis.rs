@@ -0,0 +1,57 @@
+use fuzzy_logic_rs::{
+    anfis::AnfisTrainer,
+    defuzzifications::TSKDefuzzifiers,
+    fuzzy_inference_systems::TSKFIS,
+    membership_functions::{Gaussian, MFKind, MembershipFunction},
+    rules::Rule,
+    s_norms::SNorms,
+    t_norms::TNorms,
+    variables::{InputVariable, TSKOutputVariable},
+};
+
+fn main() {
+    let original_function = |x: f64| x * (1.0 - x);
+    let data: Vec<Vec<f64>> = (0..=100)
+        .map(|i| {
+            let x = i as f64 / 100.0;
+            vec![x, original_function(x)]
+        })
+        .collect();
+
+    let mut fis = TSKFIS::new(SNorms::Max, TNorms::Product, TSKDefuzzifiers::Mean);
+
+    let mut x: InputVariable = InputVariable::new("X".to_string(), (0.0, 1.0));
+    for (i, mean) in [0.0, 0.25, 0.5, 0.75, 1.0].into_iter().enumerate() {
+        x.add_membership(MembershipFunction::new(
+            format!("x{}", i + 1),
+            MFKind::Gaussian(Gaussian::new(mean, 0.09)),
+        ));
+    }
+    fis.add_input(x);
+
+    // the coefficients are found by the trainer
+    let mut y: TSKOutputVariable = TSKOutputVariable::new("Y".to_string());
+    for _ in 0..5 {
+        y.add_linear_membership(vec![0.0]);
+    }
+    fis.add_output(y);
+
+    for i in 0..5 {
+        fis.add_rule(Rule::new_and(vec![i, i], 1.0));
+    }
+
+    // every 5th row is kept for validation
+    let (validation, training): (Vec<_>, Vec<_>) =
+        data.into_iter().enumerate().partition(|(i, _)| i % 5 == 0);
+    let mut rows: Vec<Vec<f64>> = training.into_iter().map(|(_, row)| row).collect();
+    let validation_size = validation.len();
+    rows.extend(validation.into_iter().map(|(_, row)| row));
+
+    let split = validation_size as f64 / rows.len() as f64;
+    let trainer = AnfisTrainer::new(100, 0.01, split, 10);
+    let (trained, history) = trainer.train(&fis, &rows).unwrap();
+
+    println!("training error: {:?}", history.get_training_errors().last());
+    println!("validation error: {:?}", history.get_validation_errors().last());
+    println!("{:?} {}", trained.compute_outputs(vec![0.6]), original_function(0.6));
+}
@@ -1,15 +1,33 @@
+use crate::s_norms::SNorms;
+
 #[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Aggregations {
     Max,
+    Min,
     Sum,
+    BoundedSum,
+    ProbabilisticOr,
+    NormalizedSum,
+    SNorm(SNorms),
+    #[cfg_attr(feature = "serde", serde(skip))]
     Custom(fn(&Vec<Vec<f64>>) -> Vec<f64>),
 }
 
 impl Aggregations {
+    // no sets (no rule uses the output) aggregate to an empty set
     pub fn aggregation(&self, implication_vec: &Vec<Vec<f64>>) -> Vec<f64> {
+        if implication_vec.is_empty() {
+            return Vec::new();
+        }
         match self {
             Self::Max => max_aggregation(implication_vec),
+            Self::Min => min_aggregation(implication_vec),
             Self::Sum => sum_aggregation(implication_vec),
+            Self::BoundedSum => bounded_sum_aggregation(implication_vec),
+            Self::ProbabilisticOr => probabilistic_or_aggregation(implication_vec),
+            Self::NormalizedSum => normalized_sum_aggregation(implication_vec),
+            Self::SNorm(s_norm) => pointwise(implication_vec, |mu| s_norm.s_norm(mu)),
             Self::Custom(f) => f(implication_vec),
         }
     }
@@ -18,7 +36,7 @@ impl Aggregations {
 pub fn max_aggregation(implication_vec: &Vec<Vec<f64>>) -> Vec<f64> {
     let mut mu = Vec::new();
     let size_implication = implication_vec.len();
-    let size_vec = implication_vec[0].len();
+    let size_vec = implication_vec.first().map_or(0, Vec::len);
     for i in 0..size_vec {
         let mut max = implication_vec[0][i];
         for ii in 1..size_implication {
@@ -31,16 +49,47 @@ pub fn max_aggregation(implication_vec: &Vec<Vec<f64>>) -> Vec<f64> {
     mu
 }
 
-pub fn sum_aggregation(implication_vec: &Vec<Vec<f64>>) -> Vec<f64> {
-    let mut mu = Vec::new();
-    let size_implication = implication_vec.len();
-    let size_vec = implication_vec[0].len();
-    for i in 0..size_implication {
-        let mut sum = 0.0;
-        for ii in 1..size_vec {
-            sum += implication_vec[i][ii]
+// the intersection used with implicative reasoning
+pub fn min_aggregation(implication_vec: &[Vec<f64>]) -> Vec<f64> {
+    let Some((first, rest)) = implication_vec.split_first() else {
+        return Vec::new();
+    };
+    let mut mu = first.clone();
+    for implied in rest {
+        for (m, e) in mu.iter_mut().zip(implied) {
+            *m = m.min(*e);
         }
-        mu.push(sum)
     }
     mu
 }
+
+// applies `f` to the degrees of all rules at each point of the universe
+fn pointwise<F: Fn(&[f64]) -> f64>(implication_vec: &[Vec<f64>], f: F) -> Vec<f64> {
+    (0..implication_vec.first().map_or(0, Vec::len))
+        .map(|i| {
+            let column: Vec<f64> = implication_vec.iter().map(|vec| vec[i]).collect();
+            f(&column)
+        })
+        .collect()
+}
+
+pub fn sum_aggregation(implication_vec: &[Vec<f64>]) -> Vec<f64> {
+    pointwise(implication_vec, |mu| mu.iter().sum())
+}
+
+pub fn bounded_sum_aggregation(implication_vec: &[Vec<f64>]) -> Vec<f64> {
+    pointwise(implication_vec, |mu| mu.iter().sum::<f64>().min(1.0))
+}
+
+pub fn probabilistic_or_aggregation(implication_vec: &[Vec<f64>]) -> Vec<f64> {
+    pointwise(implication_vec, |mu| {
+        mu.iter().fold(0.0, |acc, e| acc + e - acc * e)
+    })
+}
+
+// the sum is scaled down only when it goes above 1.0
+pub fn normalized_sum_aggregation(implication_vec: &[Vec<f64>]) -> Vec<f64> {
+    let sum = sum_aggregation(implication_vec);
+    let max = sum.iter().cloned().fold(1.0, f64::max);
+    sum.into_iter().map(|mu| mu / max).collect()
+}
@@ -0,0 +1,687 @@
+use crate::complements::Complements;
+use crate::defuzzifications::TSKDefuzzifiers;
+use crate::error::{check, FuzzyError};
+use crate::fuzzy_inference_systems::TSKFIS;
+use crate::membership_functions::{
+    affine_membership, Bell, Gaussian, GetDegree, Kind, MembershipFunction, Polynomial, Quadratic,
+    TSKMembershipFunction, Triangle,
+};
+use crate::rules::{self, OutputRelation, Relation, TSKRule};
+use crate::s_norms::SNorms;
+use crate::t_norms::TNorms;
+use crate::validation::{self, CheckedRule, Consequents};
+use crate::variables::{InputVariable, TSKOutputVariable};
+
+// keeps the normal equations solvable when a consequent is never used
+const RIDGE: f64 = 1e-10;
+const MIN_WIDTH: f64 = 1e-6;
+
+#[derive(Debug, Clone)]
+pub struct AnfisTrainer {
+    epochs: usize,
+    learning_rate: f64,
+    validation_split: f64,
+    patience: usize,
+}
+
+#[derive(Debug, Clone)]
+pub struct TrainingHistory {
+    training_errors: Vec<f64>,
+    validation_errors: Vec<f64>,
+    best_epoch: usize,
+}
+
+impl TrainingHistory {
+    pub fn get_training_errors(&self) -> &Vec<f64> {
+        &self.training_errors
+    }
+
+    pub fn get_validation_errors(&self) -> &Vec<f64> {
+        &self.validation_errors
+    }
+
+    pub fn get_best_epoch(&self) -> usize {
+        self.best_epoch
+    }
+}
+
+impl AnfisTrainer {
+    pub fn new(epochs: usize, learning_rate: f64, validation_split: f64, patience: usize) -> Self {
+        Self::try_new(epochs, learning_rate, validation_split, patience)
+            .unwrap_or_else(|err| panic!("{}", err))
+    }
+
+    pub fn try_new(
+        epochs: usize,
+        learning_rate: f64,
+        validation_split: f64,
+        patience: usize,
+    ) -> Result<Self, FuzzyError> {
+        check(epochs > 0, "epochs must be greater than 0")?;
+        check(learning_rate >= 0.0, "learning_rate must not be negative")?;
+        check(
+            (0.0..1.0).contains(&validation_split),
+            "validation_split must be between 0.0 and 1.0",
+        )?;
+        check(patience > 0, "patience must be greater than 0")?;
+        Ok(Self {
+            epochs,
+            learning_rate,
+            validation_split,
+            patience,
+        })
+    }
+
+    pub fn get_epochs(&self) -> usize {
+        self.epochs
+    }
+
+    pub fn get_learning_rate(&self) -> f64 {
+        self.learning_rate
+    }
+
+    pub fn get_validation_split(&self) -> f64 {
+        self.validation_split
+    }
+
+    pub fn get_patience(&self) -> usize {
+        self.patience
+    }
+
+    // Every row is the inputs followed by the outputs. The last rows are used for validation.
+    pub fn train(
+        &self,
+        fis: &TSKFIS,
+        data: &[Vec<f64>],
+    ) -> Result<(TSKFIS, TrainingHistory), FuzzyError> {
+        let mut network = Network::from_fis(fis)?;
+        let width = network.input_size + network.output_size;
+        if let Some(row) = data.iter().find(|row| row.len() != width) {
+            return Err(FuzzyError::InvalidParameters(format!(
+                "Each row must have {} values, found {}",
+                width,
+                row.len()
+            )));
+        }
+        let validation_size = (data.len() as f64 * self.validation_split).round() as usize;
+        let (training, validation) = data.split_at(data.len() - validation_size);
+        check(!training.is_empty(), "There is no data left for training")?;
+
+        let mut history = TrainingHistory {
+            training_errors: Vec::new(),
+            validation_errors: Vec::new(),
+            best_epoch: 0,
+        };
+        let mut best_error = f64::INFINITY;
+        let mut best = network.clone();
+        let mut since_best = 0;
+        for epoch in 0..self.epochs {
+            // forward pass: consequents by least squares
+            network.fit_consequents(training);
+            let training_error = network.rmse(training);
+            history.training_errors.push(training_error);
+            let monitored = if validation.is_empty() {
+                training_error
+            } else {
+                let validation_error = network.rmse(validation);
+                history.validation_errors.push(validation_error);
+                validation_error
+            };
+            if monitored < best_error {
+                best_error = monitored;
+                best = network.clone();
+                history.best_epoch = epoch;
+                since_best = 0;
+            } else {
+                since_best += 1;
+                if since_best >= self.patience {
+                    break;
+                }
+            }
+            // backward pass: premises by gradient descent
+            network.step_premises(training, self.learning_rate);
+        }
+        Ok((best.to_fis(fis), history))
+    }
+}
+
+#[derive(Debug, Clone)]
+enum Premise {
+    // mean, variance
+    Gaussian([f64; 2]),
+    // width, shape, center
+    Bell([f64; 3]),
+    Triangle([f64; 3]),
+    Fixed(Kind),
+}
+
+impl Premise {
+    fn from_kind(kind: &Kind) -> Self {
+        let p = kind.get_parameters();
+        match kind {
+            Kind::Gaussian(_) => Self::Gaussian([p[0], p[1]]),
+            Kind::Bell(_) => Self::Bell([p[0], p[1], p[2]]),
+            Kind::Triangle(_) => Self::Triangle([p[0], p[1], p[2]]),
+            _ => Self::Fixed(kind.clone()),
+        }
+    }
+
+    fn to_kind(&self) -> Kind {
+        match self {
+            Self::Gaussian([m, s]) => Kind::Gaussian(Gaussian::new(*m, *s)),
+            Self::Bell([a, b, c]) => Kind::Bell(Bell::new(*a, *b, *c)),
+            Self::Triangle([a, b, c]) => Kind::Triangle(Triangle::new(*a, *b, *c)),
+            Self::Fixed(kind) => kind.clone(),
+        }
+    }
+
+    fn parameters_mut(&mut self) -> &mut [f64] {
+        match self {
+            Self::Gaussian(p) => p,
+            Self::Bell(p) => p,
+            Self::Triangle(p) => p,
+            Self::Fixed(_) => &mut [],
+        }
+    }
+
+    fn degree(&self, x: f64) -> f64 {
+        match self {
+            Self::Gaussian([m, s]) => f64::exp(-0.5 * f64::powi((x - m) / s, 2)),
+            Self::Bell([a, b, c]) => 1.0 / (1.0 + f64::powf(f64::abs((x - c) / a), 2.0 * b)),
+            Self::Triangle([a, b, c]) => Triangle::new(*a, *b, *c).get_degree(x),
+            Self::Fixed(kind) => kind.get_degree(x),
+        }
+    }
+
+    // derivative of the degree for every parameter
+    fn gradient(&self, x: f64) -> Vec<f64> {
+        match self {
+            Self::Gaussian([m, s]) => {
+                let mu = self.degree(x);
+                vec![mu * (x - m) / (s * s), mu * (x - m) * (x - m) / (s * s * s)]
+            }
+            Self::Bell([a, b, c]) => {
+                let mu = self.degree(x);
+                let t = f64::abs((x - c) / a);
+                if t == 0.0 {
+                    return vec![0.0, 0.0, 0.0];
+                }
+                let common = mu * (1.0 - mu);
+                vec![
+                    2.0 * b * common / a,
+                    -2.0 * t.ln() * common,
+                    2.0 * b * common / (x - c),
+                ]
+            }
+            Self::Triangle([a, b, c]) => {
+                if *a <= x && x < *b {
+                    let d = (b - a) * (b - a);
+                    vec![(x - b) / d, -(x - a) / d, 0.0]
+                } else if *b <= x && x < *c {
+                    let d = (c - b) * (c - b);
+                    vec![0.0, (c - x) / d, (x - b) / d]
+                } else {
+                    vec![0.0, 0.0, 0.0]
+                }
+            }
+            Self::Fixed(_) => Vec::new(),
+        }
+    }
+
+    // keep the parameters valid after a gradient step
+    fn repair(&mut self) {
+        match self {
+            Self::Gaussian(p) => p[1] = p[1].abs().max(MIN_WIDTH),
+            Self::Bell(p) => {
+                p[0] = p[0].abs().max(MIN_WIDTH);
+                p[1] = p[1].max(MIN_WIDTH);
+            }
+            Self::Triangle(p) => p.sort_by(|a, b| a.partial_cmp(b).unwrap()),
+            Self::Fixed(_) => (),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+enum Consequent {
+    Constant(f64),
+    Linear(Vec<f64>),
+    Affine(Vec<f64>),
+    Quadratic(Quadratic),
+    Polynomial(Polynomial),
+    Fixed(fn(&Vec<f64>) -> f64),
+}
+
+impl Consequent {
+    fn from_membership(mf: &TSKMembershipFunction) -> Self {
+        match mf {
+            TSKMembershipFunction::Constant(c) => Self::Constant(*c),
+            TSKMembershipFunction::Linear(coefficients) => Self::Linear(coefficients.clone()),
+            TSKMembershipFunction::Affine(coefficients) => Self::Affine(coefficients.clone()),
+            TSKMembershipFunction::Quadratic(mf) => Self::Quadratic(mf.clone()),
+            TSKMembershipFunction::Polynomial(mf) => Self::Polynomial(mf.clone()),
+            TSKMembershipFunction::Custom(f) => Self::Fixed(*f),
+        }
+    }
+
+    fn to_membership(&self) -> TSKMembershipFunction {
+        match self {
+            Self::Constant(c) => TSKMembershipFunction::Constant(*c),
+            Self::Linear(coefficients) => TSKMembershipFunction::Linear(coefficients.clone()),
+            Self::Affine(coefficients) => TSKMembershipFunction::Affine(coefficients.clone()),
+            Self::Quadratic(mf) => TSKMembershipFunction::Quadratic(mf.clone()),
+            Self::Polynomial(mf) => TSKMembershipFunction::Polynomial(mf.clone()),
+            Self::Fixed(f) => TSKMembershipFunction::Custom(*f),
+        }
+    }
+
+    fn size(&self) -> usize {
+        match self {
+            Self::Constant(_) => 1,
+            Self::Linear(coefficients) | Self::Affine(coefficients) => coefficients.len(),
+            Self::Quadratic(mf) => mf.get_parameters().len(),
+            Self::Polynomial(mf) => mf.get_parameters().len(),
+            Self::Fixed(_) => 0,
+        }
+    }
+
+    // the value is linear in the parameters, these are the factors
+    fn basis(&self, x: &[f64]) -> Vec<f64> {
+        match self {
+            Self::Constant(_) => vec![1.0],
+            Self::Linear(coefficients) => (0..coefficients.len())
+                .map(|i| x.get(i).copied().unwrap_or(0.0))
+                .collect(),
+            Self::Affine(coefficients) => (0..coefficients.len())
+                .map(|i| x.get(i).copied().unwrap_or(0.0))
+                .take(coefficients.len().saturating_sub(1))
+                .chain([1.0])
+                .collect(),
+            Self::Quadratic(mf) => mf.get_basis(x),
+            Self::Polynomial(mf) => mf.get_basis(x),
+            Self::Fixed(_) => Vec::new(),
+        }
+    }
+
+    fn parameters_mut(&mut self) -> &mut [f64] {
+        match self {
+            Self::Constant(c) => std::slice::from_mut(c),
+            Self::Linear(coefficients) | Self::Affine(coefficients) => coefficients,
+            Self::Quadratic(mf) => mf.parameters_mut(),
+            Self::Polynomial(mf) => mf.parameters_mut(),
+            Self::Fixed(_) => &mut [],
+        }
+    }
+
+    fn value(&self, x: &[f64]) -> f64 {
+        match self {
+            Self::Constant(c) => *c,
+            Self::Linear(coefficients) => coefficients.iter().zip(x).map(|(c, x)| c * x).sum(),
+            Self::Affine(coefficients) => affine_membership(coefficients, x),
+            Self::Quadratic(mf) => mf.get_mu(x),
+            Self::Polynomial(mf) => mf.get_mu(x),
+            Self::Fixed(f) => f(&x.to_vec()),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+struct Network {
+    input_size: usize,
+    output_size: usize,
+    t_norm: TNorms,
+    s_norm: SNorms,
+    rules: Vec<TSKRule>,
+    premises: Vec<Vec<Premise>>,
+    consequents: Vec<Vec<Consequent>>,
+}
+
+struct Forward {
+    degrees: Vec<Vec<f64>>,
+    firing: Vec<f64>,
+    total: f64,
+}
+
+impl Network {
+    fn from_fis(fis: &TSKFIS) -> Result<Self, FuzzyError> {
+        let inputs = fis.get_inputs();
+        let outputs = fis.get_outputs();
+        let rules = fis.get_rule_base();
+        check(!inputs.is_empty(), "You must add at least one INPUT")?;
+        check(!outputs.is_empty(), "You must add at least one OUTPUT")?;
+        check(!rules.is_empty(), "You must add at least one RULE")?;
+        validation::check_consequent_inputs(inputs.len(), outputs)?;
+        for (i, rule) in rules.iter().enumerate() {
+            rule.check_rule(i, inputs, outputs)?;
+            rule.check_consequents(i, inputs.len(), outputs, Consequents::Functions)?;
+        }
+        check(
+            rules.iter().all(|rule| {
+                rule.get_output_rules()
+                    .iter()
+                    .all(|relation| matches!(relation, OutputRelation::Term(Relation::Term(_))))
+            }),
+            "ANFIS only trains consequents that are terms of the outputs",
+        )?;
+        check(
+            rules.iter().all(|rule| rule.get_antecedent().is_none()),
+            "ANFIS does not support rules with an expression",
+        )?;
+        check(
+            rules.iter().all(|rule| {
+                rule.get_input_rules()
+                    .iter()
+                    .all(|relation| !matches!(relation, Relation::Hedged(..)))
+            }),
+            "ANFIS does not support hedges",
+        )?;
+        check(
+            matches!(fis.get_defuzzification_method(), TSKDefuzzifiers::Mean)
+                && outputs.iter().all(|output| {
+                    matches!(
+                        output.get_defuzzification_method(),
+                        None | Some(TSKDefuzzifiers::Mean)
+                    )
+                }),
+            "ANFIS needs the Mean defuzzification",
+        )?;
+        check(
+            matches!(fis.get_t_norm_method(), TNorms::Min | TNorms::Product),
+            "ANFIS needs the Min or Product t-norm",
+        )?;
+        check(
+            matches!(fis.get_complement_method(), Complements::Standard),
+            "ANFIS needs the Standard complement",
+        )?;
+        check(
+            rules
+                .iter()
+                .all(|rule| *rule.get_kind() == rules::Kind::AND)
+                || matches!(fis.get_s_norm_method(), SNorms::Max),
+            "ANFIS needs the Max s-norm for OR rules",
+        )?;
+        Ok(Self {
+            input_size: inputs.len(),
+            output_size: outputs.len(),
+            t_norm: fis.get_t_norm_method().clone(),
+            s_norm: fis.get_s_norm_method().clone(),
+            rules: rules.clone(),
+            premises: inputs
+                .iter()
+                .map(|input| {
+                    input
+                        .get_memberships()
+                        .iter()
+                        .map(|mf| Premise::from_kind(mf.get_kind()))
+                        .collect()
+                })
+                .collect(),
+            consequents: outputs
+                .iter()
+                .map(|output| {
+                    output
+                        .get_memberships()
+                        .iter()
+                        .map(Consequent::from_membership)
+                        .collect()
+                })
+                .collect(),
+        })
+    }
+
+    fn to_fis(&self, original: &TSKFIS) -> TSKFIS {
+        let mut fis = TSKFIS::new(
+            self.s_norm.clone(),
+            self.t_norm.clone(),
+            original.get_defuzzification_method().clone(),
+        );
+        for (input, premises) in original.get_inputs().iter().zip(&self.premises) {
+            let mut variable = InputVariable::new(input.get_name().clone(), *input.get_range());
+            for (mf, premise) in input.get_memberships().iter().zip(premises) {
+                variable.add_membership(MembershipFunction::new(
+                    mf.get_name().clone(),
+                    premise.to_kind(),
+                ));
+            }
+            fis.add_input(variable);
+        }
+        for (output, consequents) in original.get_outputs().iter().zip(&self.consequents) {
+            let mut variable = TSKOutputVariable::new(output.get_name().clone());
+            for consequent in consequents {
+                variable.add_membership(consequent.to_membership());
+            }
+            fis.add_output(variable);
+        }
+        for rule in &self.rules {
+            fis.add_tsk_rule(rule.clone());
+        }
+        fis
+    }
+
+    // the term and whether it is complemented, None for don't care inputs
+    fn relation(&self, rule: &TSKRule, input: usize) -> Option<(usize, bool)> {
+        let relation = &rule.get_input_rules()[input];
+        let complement = matches!(relation, Relation::Not(_));
+        relation.get_term().map(|index| (index, complement))
+    }
+
+    fn consequent(&self, rule: &TSKRule, output: usize) -> &Consequent {
+        let index = rule.get_output_rules()[output].get_term();
+        &self.consequents[output][index.unwrap()]
+    }
+
+    fn forward(&self, x: &[f64]) -> Forward {
+        let mut degrees = Vec::new();
+        let mut firing = Vec::new();
+        for rule in &self.rules {
+            let mu: Vec<f64> = (0..self.input_size)
+                .filter_map(|j| {
+                    let (index, complement) = self.relation(rule, j)?;
+                    let degree = self.premises[j][index].degree(x[j]);
+                    Some(if complement { 1.0 - degree } else { degree })
+                })
+                .collect();
+            let connected = rule.get_kind().connect(&self.s_norm, &self.t_norm, &mu);
+            firing.push(rule.get_weight() * connected);
+            degrees.push(mu);
+        }
+        let total = firing.iter().sum();
+        Forward {
+            degrees,
+            firing,
+            total,
+        }
+    }
+
+    fn predict(&self, x: &[f64], forward: &Forward) -> Vec<f64> {
+        (0..self.output_size)
+            .map(|o| {
+                self.rules
+                    .iter()
+                    .zip(&forward.firing)
+                    .map(|(rule, w)| w * self.consequent(rule, o).value(x))
+                    .sum::<f64>()
+                    / forward.total
+            })
+            .collect()
+    }
+
+    fn rmse(&self, data: &[Vec<f64>]) -> f64 {
+        let mut sum = 0.0;
+        let mut count = 0;
+        for row in data {
+            let (x, y) = row.split_at(self.input_size);
+            let forward = self.forward(x);
+            if forward.total <= 0.0 {
+                continue;
+            }
+            for (prediction, target) in self.predict(x, &forward).iter().zip(y) {
+                sum += (prediction - target).powi(2);
+                count += 1;
+            }
+        }
+        (sum / count as f64).sqrt()
+    }
+
+    fn fit_consequents(&mut self, data: &[Vec<f64>]) {
+        for o in 0..self.output_size {
+            let offsets: Vec<usize> = self.consequents[o]
+                .iter()
+                .scan(0, |offset, consequent| {
+                    let start = *offset;
+                    *offset += consequent.size();
+                    Some(start)
+                })
+                .collect();
+            let size: usize = self.consequents[o].iter().map(|c| c.size()).sum();
+            if size == 0 {
+                continue;
+            }
+            let mut ata = vec![vec![0.0; size]; size];
+            let mut aty = vec![0.0; size];
+            for row in data {
+                let (x, y) = row.split_at(self.input_size);
+                let forward = self.forward(x);
+                if forward.total <= 0.0 {
+                    continue;
+                }
+                let mut a = vec![0.0; size];
+                let mut target = y[o];
+                for (rule, w) in self.rules.iter().zip(&forward.firing) {
+                    let normalized = w / forward.total;
+                    let index = rule.get_output_rules()[o].get_term().unwrap();
+                    let consequent = &self.consequents[o][index];
+                    if let Consequent::Fixed(_) = consequent {
+                        target -= normalized * consequent.value(x);
+                    }
+                    for (k, basis) in consequent.basis(x).into_iter().enumerate() {
+                        a[offsets[index] + k] += normalized * basis;
+                    }
+                }
+                for i in 0..size {
+                    aty[i] += a[i] * target;
+                    for k in 0..size {
+                        ata[i][k] += a[i] * a[k];
+                    }
+                }
+            }
+            let scale = (0..size).map(|i| ata[i][i]).fold(0.0, f64::max);
+            for (i, row) in ata.iter_mut().enumerate() {
+                row[i] += RIDGE * scale.max(1.0);
+            }
+            if let Some(theta) = solve(ata, aty) {
+                for (consequent, offset) in self.consequents[o].iter_mut().zip(&offsets) {
+                    let size = consequent.size();
+                    consequent
+                        .parameters_mut()
+                        .copy_from_slice(&theta[*offset..*offset + size]);
+                }
+            }
+        }
+    }
+
+    fn step_premises(&mut self, data: &[Vec<f64>], learning_rate: f64) {
+        let mut gradients: Vec<Vec<Vec<f64>>> = self
+            .premises
+            .iter()
+            .map(|terms| {
+                terms
+                    .iter()
+                    .map(|premise| vec![0.0; premise.gradient(0.0).len()])
+                    .collect()
+            })
+            .collect();
+        for row in data {
+            let (x, y) = row.split_at(self.input_size);
+            let forward = self.forward(x);
+            if forward.total <= 0.0 {
+                continue;
+            }
+            let prediction = self.predict(x, &forward);
+            for (r, rule) in self.rules.iter().enumerate() {
+                // how the squared error changes with the firing strength of this rule
+                let error_by_firing: f64 = (0..self.output_size)
+                    .map(|o| {
+                        let value = self.consequent(rule, o).value(x);
+                        (prediction[o] - y[o]) * (value - prediction[o]) / forward.total
+                    })
+                    .sum();
+                let mu = &forward.degrees[r];
+                let active = (0..self.input_size)
+                    .filter_map(|j| self.relation(rule, j).map(|relation| (j, relation)));
+                for (k, (j, (index, complement))) in active.enumerate() {
+                    let firing_by_degree =
+                        rule.get_weight() * self.connective_gradient(rule, mu, k);
+                    let sign = if complement { -1.0 } else { 1.0 };
+                    for (g, d) in gradients[j][index]
+                        .iter_mut()
+                        .zip(self.premises[j][index].gradient(x[j]))
+                    {
+                        *g += error_by_firing * firing_by_degree * sign * d;
+                    }
+                }
+            }
+        }
+        let n = data.len() as f64;
+        for (terms, term_gradients) in self.premises.iter_mut().zip(gradients) {
+            for (premise, gradient) in terms.iter_mut().zip(term_gradients) {
+                for (p, g) in premise.parameters_mut().iter_mut().zip(gradient) {
+                    *p -= learning_rate * g / n;
+                }
+                premise.repair();
+            }
+        }
+    }
+
+    // derivative of the connected degree for the `j`th degree of the rule
+    fn connective_gradient(&self, rule: &TSKRule, mu: &[f64], j: usize) -> f64 {
+        let selected = |better: fn(f64, f64) -> bool| {
+            let index = (0..mu.len())
+                .reduce(|best, i| if better(mu[i], mu[best]) { i } else { best })
+                .unwrap();
+            if index == j {
+                1.0
+            } else {
+                0.0
+            }
+        };
+        match (rule.get_kind(), &self.t_norm) {
+            (rules::Kind::OR, _) => selected(|a, b| a > b),
+            (rules::Kind::AND, TNorms::Min) => selected(|a, b| a < b),
+            _ => mu
+                .iter()
+                .enumerate()
+                .filter(|(i, _)| *i != j)
+                .map(|(_, m)| m)
+                .product(),
+        }
+    }
+}
+
+// Gaussian elimination with partial pivoting
+fn solve(mut a: Vec<Vec<f64>>, mut b: Vec<f64>) -> Option<Vec<f64>> {
+    let n = b.len();
+    for col in 0..n {
+        let pivot =
+            (col..n).max_by(|i, k| a[*i][col].abs().partial_cmp(&a[*k][col].abs()).unwrap())?;
+        if a[pivot][col].abs() < f64::MIN_POSITIVE {
+            return None;
+        }
+        a.swap(col, pivot);
+        b.swap(col, pivot);
+        let pivot_row = a[col].clone();
+        for row in col + 1..n {
+            let factor = a[row][col] / pivot_row[col];
+            for (value, p) in a[row].iter_mut().zip(&pivot_row).skip(col) {
+                *value -= factor * p;
+            }
+            b[row] -= factor * b[col];
+        }
+    }
+    let mut x = vec![0.0; n];
+    for row in (0..n).rev() {
+        let sum: f64 = (row + 1..n).map(|k| a[row][k] * x[k]).sum();
+        x[row] = (b[row] - sum) / a[row][row];
+    }
+    Some(x)
+}
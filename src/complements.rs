@@ -0,0 +1,50 @@
+use crate::error::{check, FuzzyError};
+
+// Complements (fuzzy negations) map a degree to the degree of its negation.
+#[derive(Debug, Clone, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Complements {
+    #[default]
+    Standard,
+    // lambda > -1, 0.0 is the standard complement
+    Sugeno(f64),
+    // w > 0, 1.0 is the standard complement
+    Yager(f64),
+    // must be involutive to build a dual s-norm with it
+    #[cfg_attr(feature = "serde", serde(skip))]
+    Custom(fn(f64) -> f64),
+}
+
+impl Complements {
+    pub fn new_sugeno(lambda: f64) -> Self {
+        Self::try_new_sugeno(lambda).unwrap_or_else(|err| panic!("{}", err))
+    }
+
+    pub fn try_new_sugeno(lambda: f64) -> Result<Self, FuzzyError> {
+        check(lambda > -1.0, "lambda must be greater than -1")?;
+        Ok(Self::Sugeno(lambda))
+    }
+
+    pub fn new_yager(w: f64) -> Self {
+        Self::try_new_yager(w).unwrap_or_else(|err| panic!("{}", err))
+    }
+
+    pub fn try_new_yager(w: f64) -> Result<Self, FuzzyError> {
+        check(w > 0.0, "w must be greater than 0")?;
+        Ok(Self::Yager(w))
+    }
+
+    pub fn complement(&self, mu: f64) -> f64 {
+        match self {
+            Self::Standard => 1.0 - mu,
+            Self::Sugeno(lambda) => (1.0 - mu) / (1.0 + lambda * mu),
+            Self::Yager(w) => (1.0 - mu.powf(*w)).max(0.0).powf(1.0 / w),
+            Self::Custom(c) => c(mu),
+        }
+    }
+
+    // complemented intervals swap their bounds because every complement is decreasing
+    pub fn complement_interval(&self, (lower, upper): (f64, f64)) -> (f64, f64) {
+        (self.complement(upper), self.complement(lower))
+    }
+}
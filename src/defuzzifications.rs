@@ -1,9 +1,26 @@
 use std::iter::zip;
 
+use crate::aggregations::max_aggregation;
+
+// degrees closer than this to the maximum are part of the maximum
+const TOLERANCE: f64 = 1e-9;
+const QUADRATURE_TOLERANCE: f64 = 1e-8;
+// pieces are always split a few times so narrow features are not missed
+const MIN_DEPTH: usize = 4;
+const MAX_DEPTH: usize = 40;
+
 #[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Defuzzifiers {
     Centroid,
     Bisection,
+    MeanOfMaximum,
+    SmallestOfMaximum,
+    LargestOfMaximum,
+    WeightedAverage,
+    Height,
+    CenterOfSums,
+    #[cfg_attr(feature = "serde", serde(skip))]
     Custom(fn(Vec<f64>, &Vec<f64>) -> f64),
 }
 
@@ -12,9 +29,53 @@ impl Defuzzifiers {
         match self {
             Self::Centroid => centroid_defuzzification(vec, universe),
             Self::Bisection => bisection_defuzzification(vec, universe),
+            Self::MeanOfMaximum => mean_of_maximum_defuzzification(&vec, universe),
+            Self::SmallestOfMaximum => smallest_of_maximum_defuzzification(&vec, universe),
+            Self::LargestOfMaximum => largest_of_maximum_defuzzification(&vec, universe),
+            // a single set is the same as a single rule that fires completely
+            Self::WeightedAverage | Self::Height | Self::CenterOfSums => {
+                let sets = [vec];
+                self.defuzzify_rules(&[1.0], &sets, &sets, universe)
+            }
             Self::Custom(f) => f(vec, universe),
         }
     }
+
+    // these methods need every rule instead of the aggregated set
+    pub fn is_rule_based(&self) -> bool {
+        matches!(
+            self,
+            Self::WeightedAverage | Self::Height | Self::CenterOfSums
+        )
+    }
+
+    // `consequents` are the output sets of the rules and `implied` the same sets after the
+    // implication with `firing`, the other methods defuzzify the union (max) of `implied`
+    pub fn defuzzify_rules(
+        &self,
+        firing: &[f64],
+        consequents: &[Vec<f64>],
+        implied: &[Vec<f64>],
+        universe: &Vec<f64>,
+    ) -> f64 {
+        match self {
+            Self::WeightedAverage => {
+                weighted_average_defuzzification(firing, consequents, universe)
+            }
+            Self::Height => height_defuzzification(firing, consequents, universe),
+            Self::CenterOfSums => center_of_sums_defuzzification(implied, universe),
+            _ => self.defuzzify(max_aggregation(&implied.to_vec()), universe),
+        }
+    }
+}
+
+// Used by the systems when the set to defuzzify is empty (every degree is 0.0).
+#[derive(Debug, Clone, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Fallbacks {
+    #[default]
+    MidRange,
+    Value(f64),
 }
 
 pub fn centroid_defuzzification(vec: Vec<f64>, universe: &Vec<f64>) -> f64 {
@@ -24,25 +85,192 @@ pub fn centroid_defuzzification(vec: Vec<f64>, universe: &Vec<f64>) -> f64 {
     idx
 }
 
+// the set is linear between two samples, so the half area point is found exactly
 pub fn bisection_defuzzification(vec: Vec<f64>, universe: &Vec<f64>) -> f64 {
-    let total_area: f64 = vec.iter().sum();
-    let mut idx = 0;
-    for i in 0..vec.len() {
-        let mut area = 0.0;
-        for ii in 0..i {
-            area += vec[ii]
+    let areas: Vec<f64> = (1..vec.len())
+        .map(|i| (vec[i - 1] + vec[i]) / 2.0 * (universe[i] - universe[i - 1]))
+        .collect();
+    let mut remaining = areas.iter().sum::<f64>() / 2.0;
+    for (i, area) in areas.iter().enumerate() {
+        if *area >= remaining && *area > 0.0 {
+            let width = universe[i + 1] - universe[i];
+            let slope = (vec[i + 1] - vec[i]) / width;
+            // root of vec[i] * t + slope * t^2 / 2 = remaining
+            let t = 2.0 * remaining
+                / (vec[i] + (vec[i] * vec[i] + 2.0 * slope * remaining).max(0.0).sqrt());
+            return universe[i] + t.clamp(0.0, width);
         }
-        if area > total_area * 0.5 {
-            idx = i;
-            break;
+        remaining -= area;
+    }
+    universe[universe.len() - 1]
+}
+
+fn maximum_points(vec: &[f64], universe: &[f64]) -> Vec<f64> {
+    let max = vec.iter().cloned().fold(f64::NEG_INFINITY, f64::max);
+    zip(vec, universe)
+        .filter(|(mu, _)| max - **mu <= TOLERANCE)
+        .map(|(_, x)| *x)
+        .collect()
+}
+
+pub fn mean_of_maximum_defuzzification(vec: &[f64], universe: &[f64]) -> f64 {
+    let points = maximum_points(vec, universe);
+    points.iter().sum::<f64>() / points.len() as f64
+}
+
+pub fn smallest_of_maximum_defuzzification(vec: &[f64], universe: &[f64]) -> f64 {
+    maximum_points(vec, universe)
+        .into_iter()
+        .fold(f64::INFINITY, f64::min)
+}
+
+pub fn largest_of_maximum_defuzzification(vec: &[f64], universe: &[f64]) -> f64 {
+    maximum_points(vec, universe)
+        .into_iter()
+        .fold(f64::NEG_INFINITY, f64::max)
+}
+
+// every rule gives one point of its consequent weighted by its firing strength,
+// rules that do not fire or have an empty consequent are skipped
+fn weighted_points(
+    firing: &[f64],
+    consequents: &[Vec<f64>],
+    point: fn(&[f64], &[f64]) -> f64,
+    universe: &[f64],
+) -> f64 {
+    let mut numerator = 0.0;
+    let mut denominator = 0.0;
+    for (mu, set) in zip(firing, consequents) {
+        if *mu > 0.0 && set.iter().any(|e| *e > 0.0) {
+            numerator += mu * point(set, universe);
+            denominator += mu;
         }
     }
-    universe[idx]
+    numerator / denominator
 }
 
-#[derive(Debug)]
+// the centroid of every consequent weighted by the firing strength of its rule
+pub fn weighted_average_defuzzification(
+    firing: &[f64],
+    consequents: &[Vec<f64>],
+    universe: &[f64],
+) -> f64 {
+    weighted_points(
+        firing,
+        consequents,
+        |set, universe| {
+            let numerator: f64 = zip(set, universe).map(|(e, u)| e * u).sum();
+            numerator / set.iter().sum::<f64>()
+        },
+        universe,
+    )
+}
+
+// the peak of every consequent weighted by the firing strength of its rule
+pub fn height_defuzzification(firing: &[f64], consequents: &[Vec<f64>], universe: &[f64]) -> f64 {
+    weighted_points(
+        firing,
+        consequents,
+        mean_of_maximum_defuzzification,
+        universe,
+    )
+}
+
+// like the centroid, but overlapping sets are counted once per rule
+pub fn center_of_sums_defuzzification(implied: &[Vec<f64>], universe: &[f64]) -> f64 {
+    let mut numerator = 0.0;
+    let mut denominator = 0.0;
+    for set in implied {
+        numerator += zip(set, universe).map(|(e, u)| e * u).sum::<f64>();
+        denominator += set.iter().sum::<f64>();
+    }
+    numerator / denominator
+}
+
+// adaptive Simpson quadrature, `points` are sorted and split the interval where the set
+// may not be smooth
+pub fn integrate<F: Fn(f64) -> f64>(f: &F, points: &[f64]) -> f64 {
+    points
+        .windows(2)
+        .map(|w| {
+            let (a, b) = (w[0], w[1]);
+            let m = (a + b) / 2.0;
+            let (fa, fm, fb) = (f(a), f(m), f(b));
+            let whole = (b - a) / 6.0 * (fa + 4.0 * fm + fb);
+            simpson(f, (a, fa), (m, fm), (b, fb), whole, QUADRATURE_TOLERANCE, 0)
+        })
+        .sum()
+}
+
+fn simpson<F: Fn(f64) -> f64>(
+    f: &F,
+    (a, fa): (f64, f64),
+    (m, fm): (f64, f64),
+    (b, fb): (f64, f64),
+    whole: f64,
+    tolerance: f64,
+    depth: usize,
+) -> f64 {
+    let (lm, rm) = ((a + m) / 2.0, (m + b) / 2.0);
+    let (flm, frm) = (f(lm), f(rm));
+    let left = (m - a) / 6.0 * (fa + 4.0 * flm + fm);
+    let right = (b - m) / 6.0 * (fm + 4.0 * frm + fb);
+    let error = left + right - whole;
+    if depth >= MAX_DEPTH || (depth >= MIN_DEPTH && error.abs() <= 15.0 * tolerance) {
+        return left + right + error / 15.0;
+    }
+    simpson(
+        f,
+        (a, fa),
+        (lm, flm),
+        (m, fm),
+        left,
+        tolerance / 2.0,
+        depth + 1,
+    ) + simpson(
+        f,
+        (m, fm),
+        (rm, frm),
+        (b, fb),
+        right,
+        tolerance / 2.0,
+        depth + 1,
+    )
+}
+
+pub fn continuous_centroid_defuzzification<F: Fn(f64) -> f64>(f: &F, points: &[f64]) -> f64 {
+    integrate(&|x| x * f(x), points) / integrate(f, points)
+}
+
+pub fn continuous_bisection_defuzzification<F: Fn(f64) -> f64>(f: &F, points: &[f64]) -> f64 {
+    let areas: Vec<f64> = points.windows(2).map(|w| integrate(f, w)).collect();
+    let mut remaining = areas.iter().sum::<f64>() / 2.0;
+    for (i, area) in areas.iter().enumerate() {
+        if *area >= remaining && *area > 0.0 {
+            // the area grows with the bound, so bisect on it
+            let (mut low, mut high) = (points[i], points[i + 1]);
+            for _ in 0..MAX_DEPTH * 2 {
+                let middle = (low + high) / 2.0;
+                if integrate(f, &[points[i], middle]) < remaining {
+                    low = middle;
+                } else {
+                    high = middle;
+                }
+            }
+            return (low + high) / 2.0;
+        }
+        remaining -= area;
+    }
+    points[points.len() - 1]
+}
+
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum TSKDefuzzifiers {
     Mean,
+    Sum,
+    Softmax,
+    #[cfg_attr(feature = "serde", serde(skip))]
     Custom(fn(&Vec<f64>, &Vec<f64>) -> f64),
 }
 
@@ -50,6 +278,8 @@ impl TSKDefuzzifiers {
     pub fn defuzzify(&self, mu_vec: &Vec<f64>, weighed_input: &Vec<f64>) -> f64 {
         match self {
             Self::Mean => mean_tsk_defuzzification(mu_vec, weighed_input),
+            Self::Sum => sum_tsk_defuzzification(mu_vec, weighed_input),
+            Self::Softmax => softmax_tsk_defuzzification(mu_vec, weighed_input),
             Self::Custom(fun) => fun(mu_vec, weighed_input),
         }
     }
@@ -60,3 +290,20 @@ pub fn mean_tsk_defuzzification(mu_vec: &Vec<f64>, weighed_input: &Vec<f64>) ->
     let num: f64 = mu_vec.iter().zip(weighed_input).map(|(mu, i)| mu * i).sum();
     num / den
 }
+
+// MATLAB's `wtsum`, the firing strengths are not normalized
+pub fn sum_tsk_defuzzification(mu_vec: &[f64], weighed_input: &[f64]) -> f64 {
+    mu_vec.iter().zip(weighed_input).map(|(mu, i)| mu * i).sum()
+}
+
+// the firing strengths go through a softmax, rules that don't fire still count a little
+pub fn softmax_tsk_defuzzification(mu_vec: &[f64], weighed_input: &[f64]) -> f64 {
+    let max = weighed_input
+        .iter()
+        .cloned()
+        .fold(f64::NEG_INFINITY, f64::max);
+    let exp: Vec<f64> = weighed_input.iter().map(|i| (i - max).exp()).collect();
+    let den: f64 = exp.iter().sum();
+    let num: f64 = mu_vec.iter().zip(&exp).map(|(mu, e)| mu * e).sum();
+    num / den
+}
@@ -0,0 +1,134 @@
+use std::fmt;
+
+use crate::rules::Relation;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum FuzzyError {
+    InvalidParameters(String),
+    WeightOutOfRange(f64),
+    RuleArity {
+        rule: usize,
+        expected: usize,
+        found: usize,
+    },
+    UnknownTerm {
+        rule: usize,
+        variable: String,
+        index: usize,
+    },
+    RangeLength {
+        variable: String,
+        term: String,
+        expected: usize,
+        found: usize,
+    },
+    MissingShape {
+        variable: String,
+        term: String,
+    },
+    ConsequentInputs {
+        variable: String,
+        term: String,
+        expected: usize,
+        found: usize,
+    },
+    UnsupportedRelation {
+        rule: usize,
+        variable: String,
+        relation: Relation,
+    },
+    NoInputs,
+    NoOutputs,
+    EmptyRuleBase,
+    UnusedOutput {
+        variable: String,
+    },
+    InputLength {
+        expected: usize,
+        found: usize,
+    },
+    ZeroFiringStrength,
+}
+
+impl fmt::Display for FuzzyError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InvalidParameters(message) => write!(f, "{}", message),
+            Self::WeightOutOfRange(weight) => {
+                write!(f, "Weight must be between 0.0 and 1.0, found {}", weight)
+            }
+            Self::RuleArity {
+                rule,
+                expected,
+                found,
+            } => write!(
+                f,
+                "Rule {} must have {} relations, found {}",
+                rule, expected, found
+            ),
+            Self::UnknownTerm {
+                rule,
+                variable,
+                index,
+            } => write!(
+                f,
+                "Rule {} uses term {} which does not exist in `{}`",
+                rule, index, variable
+            ),
+            Self::RangeLength {
+                variable,
+                term,
+                expected,
+                found,
+            } => write!(
+                f,
+                "Term `{}` of `{}` has {} values but the universe has {}",
+                term, variable, found, expected
+            ),
+            Self::MissingShape { variable, term } => write!(
+                f,
+                "Term `{}` of `{}` is only sampled and has no shape to evaluate",
+                term, variable
+            ),
+            Self::ConsequentInputs {
+                variable,
+                term,
+                expected,
+                found,
+            } => write!(
+                f,
+                "Term `{}` of `{}` is made for {} inputs but {} are expected",
+                term, variable, found, expected
+            ),
+            Self::UnsupportedRelation {
+                rule,
+                variable,
+                relation,
+            } => write!(
+                f,
+                "Rule {} can not use {:?} for `{}` in this system",
+                rule, relation, variable
+            ),
+            Self::NoInputs => write!(f, "You must add at least one INPUT"),
+            Self::NoOutputs => write!(f, "You must add at least one OUTPUT"),
+            Self::EmptyRuleBase => write!(f, "You must add at least one RULE"),
+            Self::UnusedOutput { variable } => {
+                write!(f, "Output `{}` is not used by any rule", variable)
+            }
+            Self::InputLength { expected, found } => {
+                write!(f, "Expected {} input values, found {}", expected, found)
+            }
+            Self::ZeroFiringStrength => write!(f, "No rule has a firing strength above 0.0"),
+        }
+    }
+}
+
+impl std::error::Error for FuzzyError {}
+
+pub(crate) fn check(condition: bool, message: &str) -> Result<(), FuzzyError> {
+    if condition {
+        Ok(())
+    } else {
+        Err(FuzzyError::InvalidParameters(message.to_string()))
+    }
+}
@@ -0,0 +1,1156 @@
+use std::fmt::{self, Write};
+use std::fs;
+use std::path::Path;
+
+use crate::aggregations::Aggregations;
+use crate::complements::Complements;
+use crate::defuzzifications::Defuzzifiers;
+use crate::fis_format::DEFAULT_RESOLUTION;
+use crate::fuzzy_inference_systems::MamdaniFIS;
+use crate::implications::{Implications, Reasoning};
+use crate::membership_functions::{
+    Bell, Gaussian, Kind, LinearS, LinearZ, MembershipFunction, Trapezoid, Triangle,
+};
+use crate::membership_ranges::MembershipRange;
+use crate::rules::{self, Expression, Relation, Rule};
+use crate::s_norms::SNorms;
+use crate::t_norms::TNorms;
+use crate::variables::{InputVariable, NoActivationPolicies, OutputVariable};
+
+#[derive(Debug)]
+pub enum FclError {
+    Io(std::io::Error),
+    Parse {
+        line: usize,
+        column: usize,
+        message: String,
+    },
+    Unsupported(String),
+}
+
+impl fmt::Display for FclError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Io(err) => write!(f, "io error: {}", err),
+            Self::Parse {
+                line,
+                column,
+                message,
+            } => write!(f, "{}:{}: {}", line, column, message),
+            Self::Unsupported(message) => write!(f, "unsupported: {}", message),
+        }
+    }
+}
+
+impl std::error::Error for FclError {}
+
+impl From<std::io::Error> for FclError {
+    fn from(err: std::io::Error) -> Self {
+        Self::Io(err)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+struct Location {
+    line: usize,
+    column: usize,
+}
+
+impl Location {
+    fn error<T>(&self, message: impl Into<String>) -> Result<T, FclError> {
+        Err(FclError::Parse {
+            line: self.line,
+            column: self.column,
+            message: message.into(),
+        })
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Ident(String),
+    Number(f64),
+    Assign,
+    Colon,
+    Semicolon,
+    LParen,
+    RParen,
+    Comma,
+    Range,
+    End,
+}
+
+impl fmt::Display for Token {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Ident(ident) => write!(f, "`{}`", ident),
+            Self::Number(number) => write!(f, "`{}`", number),
+            Self::Assign => write!(f, "`:=`"),
+            Self::Colon => write!(f, "`:`"),
+            Self::Semicolon => write!(f, "`;`"),
+            Self::LParen => write!(f, "`(`"),
+            Self::RParen => write!(f, "`)`"),
+            Self::Comma => write!(f, "`,`"),
+            Self::Range => write!(f, "`..`"),
+            Self::End => write!(f, "end of file"),
+        }
+    }
+}
+
+fn tokenize(source: &str) -> Result<Vec<(Token, Location)>, FclError> {
+    let chars: Vec<char> = source.chars().collect();
+    let mut tokens = Vec::new();
+    let (mut i, mut line, mut column) = (0, 1, 1);
+    let advance = |i: &mut usize, line: &mut usize, column: &mut usize, n: usize| {
+        for _ in 0..n {
+            if chars[*i] == '\n' {
+                *line += 1;
+                *column = 1;
+            } else {
+                *column += 1;
+            }
+            *i += 1;
+        }
+    };
+    while i < chars.len() {
+        let c = chars[i];
+        let next = chars.get(i + 1).copied();
+        let location = Location { line, column };
+        if c.is_whitespace() {
+            advance(&mut i, &mut line, &mut column, 1);
+        } else if c == '(' && next == Some('*') {
+            // the closing `*)` can't reuse the `*` of the opening `(*`
+            let mut n = 3;
+            while i + n < chars.len() && !(chars[i + n] == ')' && chars[i + n - 1] == '*') {
+                n += 1;
+            }
+            if i + n >= chars.len() {
+                return location.error("unterminated comment");
+            }
+            advance(&mut i, &mut line, &mut column, n + 1);
+        } else if c == '/' && next == Some('/') {
+            while i < chars.len() && chars[i] != '\n' {
+                advance(&mut i, &mut line, &mut column, 1);
+            }
+        } else if c.is_ascii_alphabetic() || c == '_' {
+            let mut n = 0;
+            while i + n < chars.len()
+                && (chars[i + n].is_ascii_alphanumeric() || chars[i + n] == '_')
+            {
+                n += 1;
+            }
+            tokens.push((Token::Ident(chars[i..i + n].iter().collect()), location));
+            advance(&mut i, &mut line, &mut column, n);
+        } else if c.is_ascii_digit()
+            || ((c == '-' || c == '+') && next.is_some_and(|n| n.is_ascii_digit()))
+        {
+            let mut n = 1;
+            while i + n < chars.len() {
+                let d = chars[i + n];
+                let exponent_sign = (d == '-' || d == '+') && matches!(chars[i + n - 1], 'e' | 'E');
+                let decimal = d == '.' && chars.get(i + n + 1) != Some(&'.');
+                if d.is_ascii_digit() || d == 'e' || d == 'E' || exponent_sign || decimal {
+                    n += 1;
+                } else {
+                    break;
+                }
+            }
+            let text: String = chars[i..i + n].iter().collect();
+            match text.parse::<f64>() {
+                Ok(number) => tokens.push((Token::Number(number), location)),
+                Err(_) => return location.error(format!("`{}` is not a number", text)),
+            }
+            advance(&mut i, &mut line, &mut column, n);
+        } else {
+            let (token, n) = match (c, next) {
+                (':', Some('=')) => (Token::Assign, 2),
+                ('.', Some('.')) => (Token::Range, 2),
+                (':', _) => (Token::Colon, 1),
+                (';', _) => (Token::Semicolon, 1),
+                ('(', _) => (Token::LParen, 1),
+                (')', _) => (Token::RParen, 1),
+                (',', _) => (Token::Comma, 1),
+                _ => return location.error(format!("unexpected character `{}`", c)),
+            };
+            tokens.push((token, location));
+            advance(&mut i, &mut line, &mut column, n);
+        }
+    }
+    tokens.push((Token::End, Location { line, column }));
+    Ok(tokens)
+}
+
+#[derive(Debug)]
+enum TermShape {
+    Points(Vec<(f64, f64)>),
+    Triangle(f64, f64, f64),
+    Trapezoid(f64, f64, f64, f64),
+    Gaussian(f64, f64),
+    Bell(f64, f64, f64),
+}
+
+#[derive(Debug)]
+struct Term {
+    name: String,
+    shape: TermShape,
+    location: Location,
+}
+
+#[derive(Debug, Default)]
+struct Block {
+    name: String,
+    location: Location,
+    range: Option<(f64, f64)>,
+    terms: Vec<Term>,
+    method: Option<(String, Location)>,
+    default: Option<NoActivationPolicies>,
+}
+
+#[derive(Debug)]
+struct Clause {
+    variable: (String, Location),
+    term: (String, Location),
+    complement: bool,
+}
+
+#[derive(Debug)]
+enum Condition {
+    Clause(Clause),
+    And(Vec<Condition>),
+    Or(Vec<Condition>),
+    Not(Box<Condition>),
+}
+
+#[derive(Debug)]
+struct RuleDef {
+    antecedent: Condition,
+    consequent: Vec<Clause>,
+    weight: f64,
+}
+
+#[derive(Debug, Default)]
+struct Operators {
+    and: Option<(String, Location)>,
+    or: Option<(String, Location)>,
+    act: Option<(String, Location)>,
+    accu: Option<(String, Location)>,
+}
+
+#[derive(Debug, Default)]
+struct FunctionBlock {
+    inputs: Vec<(String, Location)>,
+    outputs: Vec<(String, Location)>,
+    fuzzify: Vec<Block>,
+    defuzzify: Vec<Block>,
+    operators: Operators,
+    rules: Vec<RuleDef>,
+}
+
+struct Parser {
+    tokens: Vec<(Token, Location)>,
+    position: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> &(Token, Location) {
+        &self.tokens[self.position]
+    }
+
+    fn next(&mut self) -> (Token, Location) {
+        let token = self.tokens[self.position].clone();
+        if token.0 != Token::End {
+            self.position += 1;
+        }
+        token
+    }
+
+    fn is_keyword(&self, keyword: &str) -> bool {
+        matches!(&self.peek().0, Token::Ident(ident) if ident.eq_ignore_ascii_case(keyword))
+    }
+
+    fn unexpected<T>(&self, expected: &str) -> Result<T, FclError> {
+        let (token, location) = self.peek();
+        location.error(format!("expected {}, found {}", expected, token))
+    }
+
+    fn expect(&mut self, expected: Token) -> Result<Location, FclError> {
+        if self.peek().0 == expected {
+            Ok(self.next().1)
+        } else {
+            self.unexpected(&expected.to_string())
+        }
+    }
+
+    fn keyword(&mut self, keyword: &str) -> Result<Location, FclError> {
+        if self.is_keyword(keyword) {
+            Ok(self.next().1)
+        } else {
+            self.unexpected(&format!("`{}`", keyword))
+        }
+    }
+
+    fn ident(&mut self) -> Result<(String, Location), FclError> {
+        match self.peek().clone() {
+            (Token::Ident(ident), location) => {
+                self.next();
+                Ok((ident, location))
+            }
+            _ => self.unexpected("a name"),
+        }
+    }
+
+    fn number(&mut self) -> Result<f64, FclError> {
+        match self.peek().0 {
+            Token::Number(number) => {
+                self.next();
+                Ok(number)
+            }
+            _ => self.unexpected("a number"),
+        }
+    }
+
+    fn operator(&mut self) -> Result<(String, Location), FclError> {
+        self.next();
+        self.expect(Token::Colon)?;
+        let (name, location) = self.ident()?;
+        self.expect(Token::Semicolon)?;
+        Ok((name.to_uppercase(), location))
+    }
+
+    fn function_block(&mut self) -> Result<FunctionBlock, FclError> {
+        let mut block = FunctionBlock::default();
+        self.keyword("FUNCTION_BLOCK")?;
+        if matches!(self.peek().0, Token::Ident(_)) && !self.is_block_start() {
+            self.ident()?;
+        }
+        loop {
+            if self.is_keyword("END_FUNCTION_BLOCK") {
+                self.next();
+                break;
+            } else if self.is_keyword("VAR_INPUT") {
+                self.next();
+                block.inputs.extend(self.variables()?);
+            } else if self.is_keyword("VAR_OUTPUT") {
+                self.next();
+                block.outputs.extend(self.variables()?);
+            } else if self.is_keyword("FUZZIFY") {
+                self.next();
+                block.fuzzify.push(self.block("END_FUZZIFY")?);
+            } else if self.is_keyword("DEFUZZIFY") {
+                self.next();
+                block.defuzzify.push(self.block("END_DEFUZZIFY")?);
+            } else if self.is_keyword("RULEBLOCK") {
+                self.next();
+                self.rule_block(&mut block)?;
+            } else {
+                return self.unexpected("a block or `END_FUNCTION_BLOCK`");
+            }
+        }
+        self.expect(Token::End)?;
+        Ok(block)
+    }
+
+    fn is_block_start(&self) -> bool {
+        [
+            "VAR_INPUT",
+            "VAR_OUTPUT",
+            "FUZZIFY",
+            "DEFUZZIFY",
+            "RULEBLOCK",
+        ]
+        .iter()
+        .any(|keyword| self.is_keyword(keyword))
+    }
+
+    fn variables(&mut self) -> Result<Vec<(String, Location)>, FclError> {
+        let mut variables = Vec::new();
+        while !self.is_keyword("END_VAR") {
+            let variable = self.ident()?;
+            self.expect(Token::Colon)?;
+            if !self.is_keyword("REAL") {
+                return self.unexpected("`REAL`");
+            }
+            self.next();
+            self.expect(Token::Semicolon)?;
+            variables.push(variable);
+        }
+        self.next();
+        Ok(variables)
+    }
+
+    fn block(&mut self, end: &str) -> Result<Block, FclError> {
+        let (name, location) = self.ident()?;
+        let mut block = Block {
+            name,
+            location,
+            ..Default::default()
+        };
+        while !self.is_keyword(end) {
+            if self.is_keyword("RANGE") {
+                self.next();
+                self.expect(Token::Assign)?;
+                self.expect(Token::LParen)?;
+                let start = self.number()?;
+                self.expect(Token::Range)?;
+                let stop = self.number()?;
+                self.expect(Token::RParen)?;
+                self.expect(Token::Semicolon)?;
+                if start >= stop {
+                    return location.error("RANGE must be increasing");
+                }
+                block.range = Some((start, stop));
+            } else if self.is_keyword("TERM") {
+                self.next();
+                block.terms.push(self.term()?);
+            } else if self.is_keyword("METHOD") {
+                block.method = Some(self.operator()?);
+            } else if self.is_keyword("DEFAULT") {
+                // NC (no change) keeps the last value of the output
+                self.next();
+                self.expect(Token::Assign)?;
+                block.default = Some(if self.is_keyword("NC") {
+                    self.next();
+                    NoActivationPolicies::HoldLast
+                } else {
+                    NoActivationPolicies::Value(self.number()?)
+                });
+                self.expect(Token::Semicolon)?;
+            } else {
+                return self.unexpected(&format!("`TERM`, `RANGE` or `{}`", end));
+            }
+        }
+        self.next();
+        Ok(block)
+    }
+
+    fn term(&mut self) -> Result<Term, FclError> {
+        let (name, location) = self.ident()?;
+        self.expect(Token::Assign)?;
+        let shape = match self.peek().clone() {
+            (Token::LParen, _) => {
+                let mut points = Vec::new();
+                while self.peek().0 == Token::LParen {
+                    self.next();
+                    let x = self.number()?;
+                    self.expect(Token::Comma)?;
+                    let y = self.number()?;
+                    self.expect(Token::RParen)?;
+                    points.push((x, y));
+                }
+                TermShape::Points(points)
+            }
+            (Token::Ident(shape), shape_location) => {
+                self.next();
+                match shape.to_lowercase().as_str() {
+                    "trian" => TermShape::Triangle(self.number()?, self.number()?, self.number()?),
+                    "trape" => TermShape::Trapezoid(
+                        self.number()?,
+                        self.number()?,
+                        self.number()?,
+                        self.number()?,
+                    ),
+                    "gauss" => TermShape::Gaussian(self.number()?, self.number()?),
+                    "gbell" => TermShape::Bell(self.number()?, self.number()?, self.number()?),
+                    _ => {
+                        return shape_location.error(format!("unsupported term shape `{}`", shape))
+                    }
+                }
+            }
+            (Token::Number(_), number_location) => {
+                return number_location.error("singleton terms are not supported");
+            }
+            _ => return self.unexpected("a term definition"),
+        };
+        self.expect(Token::Semicolon)?;
+        Ok(Term {
+            name,
+            shape,
+            location,
+        })
+    }
+
+    fn rule_block(&mut self, block: &mut FunctionBlock) -> Result<(), FclError> {
+        let is_operator = ["AND", "OR", "ACT", "ACCU", "RULE", "END_RULEBLOCK"]
+            .iter()
+            .any(|keyword| self.is_keyword(keyword));
+        if !is_operator {
+            self.ident()?;
+        }
+        while !self.is_keyword("END_RULEBLOCK") {
+            if self.is_keyword("RULE") {
+                let rule = self.rule()?;
+                block.rules.push(rule);
+                continue;
+            }
+            let keyword = match &self.peek().0 {
+                Token::Ident(keyword) => keyword.to_uppercase(),
+                _ => String::new(),
+            };
+            let slot = match keyword.as_str() {
+                "AND" => &mut block.operators.and,
+                "OR" => &mut block.operators.or,
+                "ACT" => &mut block.operators.act,
+                "ACCU" => &mut block.operators.accu,
+                _ => return self.unexpected("an operator, `RULE` or `END_RULEBLOCK`"),
+            };
+            let (operator, location) = self.operator()?;
+            match slot {
+                Some((existing, _)) if *existing != operator => {
+                    return location.error(format!(
+                        "rule blocks must share their operators, `{}` was used before",
+                        existing
+                    ));
+                }
+                _ => *slot = Some((operator, location)),
+            }
+        }
+        self.next();
+        Ok(())
+    }
+
+    fn rule(&mut self) -> Result<RuleDef, FclError> {
+        self.keyword("RULE")?;
+        if let Token::Number(_) | Token::Ident(_) = self.peek().0 {
+            self.next();
+        }
+        self.expect(Token::Colon)?;
+        self.keyword("IF")?;
+        let antecedent = self.or_condition()?;
+        self.keyword("THEN")?;
+        let mut consequent = vec![self.clause()?];
+        while self.peek().0 == Token::Comma {
+            self.next();
+            consequent.push(self.clause()?);
+        }
+        let mut weight = 1.0;
+        if self.is_keyword("WITH") {
+            let (_, weight_location) = self.next();
+            weight = self.number()?;
+            if !(0.0..=1.0).contains(&weight) {
+                return weight_location.error("weight must be between 0.0 and 1.0");
+            }
+        }
+        self.expect(Token::Semicolon)?;
+        Ok(RuleDef {
+            antecedent,
+            consequent,
+            weight,
+        })
+    }
+
+    // OR binds weaker than AND, NOT binds stronger
+    fn or_condition(&mut self) -> Result<Condition, FclError> {
+        let mut conditions = vec![self.and_condition()?];
+        while self.is_keyword("OR") {
+            self.next();
+            conditions.push(self.and_condition()?);
+        }
+        Ok(match conditions.len() {
+            1 => conditions.remove(0),
+            _ => Condition::Or(conditions),
+        })
+    }
+
+    fn and_condition(&mut self) -> Result<Condition, FclError> {
+        let mut conditions = vec![self.unary_condition()?];
+        while self.is_keyword("AND") {
+            self.next();
+            conditions.push(self.unary_condition()?);
+        }
+        Ok(match conditions.len() {
+            1 => conditions.remove(0),
+            _ => Condition::And(conditions),
+        })
+    }
+
+    fn unary_condition(&mut self) -> Result<Condition, FclError> {
+        if self.is_keyword("NOT") {
+            self.next();
+            return Ok(Condition::Not(Box::new(self.unary_condition()?)));
+        }
+        if self.peek().0 == Token::LParen {
+            self.next();
+            let condition = self.or_condition()?;
+            self.expect(Token::RParen)?;
+            return Ok(condition);
+        }
+        Ok(Condition::Clause(self.clause()?))
+    }
+
+    fn clause(&mut self) -> Result<Clause, FclError> {
+        let mut complement = false;
+        if self.is_keyword("NOT") {
+            self.next();
+            complement = true;
+        }
+        let variable = self.ident()?;
+        self.keyword("IS")?;
+        if self.is_keyword("NOT") {
+            self.next();
+            complement = !complement;
+        }
+        let term = self.ident()?;
+        Ok(Clause {
+            variable,
+            term,
+            complement,
+        })
+    }
+}
+
+fn shape_kind(term: &Term) -> Result<Kind, FclError> {
+    let location = term.location;
+    let ordered = |values: &[f64]| values.windows(2).all(|w| w[0] <= w[1]);
+    Ok(match &term.shape {
+        TermShape::Points(points) => {
+            let x: Vec<f64> = points.iter().map(|p| p.0).collect();
+            let y: Vec<f64> = points.iter().map(|p| p.1).collect();
+            if !ordered(&x) {
+                return location.error("term points must be in increasing order");
+            }
+            match y[..] {
+                [0.0, 1.0] if x[0] < x[1] => Kind::LinearS(LinearS::new(x[0], x[1])),
+                [1.0, 0.0] if x[0] < x[1] => Kind::LinearZ(LinearZ::new(x[0], x[1])),
+                [0.0, 1.0, 0.0] => Kind::Triangle(Triangle::new(x[0], x[1], x[2])),
+                [0.0, 1.0, 1.0, 0.0] => Kind::Trapezoid(Trapezoid::new(x[0], x[1], x[2], x[3])),
+                _ => {
+                    return location
+                        .error("only triangles, trapezoids and ramps are supported as points")
+                }
+            }
+        }
+        TermShape::Triangle(a, b, c) => {
+            if !ordered(&[*a, *b, *c]) {
+                return location.error("a <= b <= c is required");
+            }
+            Kind::Triangle(Triangle::new(*a, *b, *c))
+        }
+        TermShape::Trapezoid(a, b, c, d) => {
+            if !ordered(&[*a, *b, *c, *d]) {
+                return location.error("a <= b <= c <= d is required");
+            }
+            Kind::Trapezoid(Trapezoid::new(*a, *b, *c, *d))
+        }
+        TermShape::Gaussian(mean, sigma) => {
+            if *sigma <= 0.0 {
+                return location.error("sigma must be positive");
+            }
+            Kind::Gaussian(Gaussian::new(*mean, *sigma))
+        }
+        TermShape::Bell(width, shape, center) => {
+            if *width <= 0.0 || *shape <= 0.0 {
+                return location.error("width and shape must be positive");
+            }
+            Kind::Bell(Bell::new(*width, *shape, *center))
+        }
+    })
+}
+
+fn shape_range(universe: &[f64], term: &Term) -> Result<MembershipRange, FclError> {
+    let kind = shape_kind(term)?;
+    Ok(MembershipRange::from_kind(
+        universe,
+        term.name.clone(),
+        kind,
+    ))
+}
+
+fn find_block<'a>(
+    blocks: &'a [Block],
+    variable: &(String, Location),
+    kind: &str,
+) -> Result<&'a Block, FclError> {
+    match blocks.iter().find(|block| block.name == variable.0) {
+        Some(block) => Ok(block),
+        None => variable
+            .1
+            .error(format!("`{}` has no {} block", variable.0, kind)),
+    }
+}
+
+fn find_term(
+    clause: &Clause,
+    blocks: &[Block],
+    variables: &[(String, Location)],
+) -> Option<Result<(usize, Relation), FclError>> {
+    let index = variables.iter().position(|v| v.0 == clause.variable.0)?;
+    let block = blocks
+        .iter()
+        .find(|block| block.name == clause.variable.0)?;
+    let term = match block.terms.iter().position(|t| t.name == clause.term.0) {
+        Some(term) => term,
+        None => {
+            return Some(clause.term.1.error(format!(
+                "`{}` is not a term of `{}`",
+                clause.term.0, clause.variable.0
+            )))
+        }
+    };
+    Some(Ok((
+        index,
+        match clause.complement {
+            true => Relation::Not(term),
+            false => Relation::Term(term),
+        },
+    )))
+}
+
+fn expression(
+    condition: &Condition,
+    blocks: &[Block],
+    variables: &[(String, Location)],
+) -> Result<Expression, FclError> {
+    let all = |conditions: &[Condition]| {
+        conditions
+            .iter()
+            .map(|c| expression(c, blocks, variables))
+            .collect::<Result<Vec<Expression>, FclError>>()
+    };
+    Ok(match condition {
+        Condition::Clause(clause) => match find_term(clause, blocks, variables) {
+            Some(found) => {
+                let (index, relation) = found?;
+                Expression::Is(index, relation)
+            }
+            None => {
+                return clause
+                    .variable
+                    .1
+                    .error(format!("`{}` is not an input variable", clause.variable.0))
+            }
+        },
+        Condition::And(conditions) => Expression::and(all(conditions)?),
+        Condition::Or(conditions) => Expression::or(all(conditions)?),
+        Condition::Not(condition) => Expression::negate(expression(condition, blocks, variables)?),
+    })
+}
+
+fn has_own_norms(expression: &Expression) -> bool {
+    match expression {
+        Expression::Is(..) => false,
+        Expression::And(_, Some(_)) | Expression::Or(_, Some(_)) => true,
+        Expression::And(expressions, None) | Expression::Or(expressions, None) => {
+            expressions.iter().any(has_own_norms)
+        }
+        Expression::Not(expression) => has_own_norms(expression),
+    }
+}
+
+fn relations(
+    clauses: &[Clause],
+    blocks: &[Block],
+    variables: &[(String, Location)],
+    side: &str,
+) -> Result<Vec<Relation>, FclError> {
+    let mut relations: Vec<Option<Relation>> = vec![None; variables.len()];
+    for clause in clauses {
+        let (index, term) = match find_term(clause, blocks, variables) {
+            Some(found) => found?,
+            None => {
+                return clause.variable.1.error(format!(
+                    "`{}` is not an {} variable",
+                    clause.variable.0, side
+                ))
+            }
+        };
+        if relations[index].is_some() {
+            return clause.variable.1.error(format!(
+                "`{}` is used twice in this rule",
+                clause.variable.0
+            ));
+        }
+        relations[index] = Some(term);
+    }
+    // variables that are not mentioned are don't care
+    Ok(relations
+        .into_iter()
+        .map(|relation| relation.unwrap_or(Relation::Any))
+        .collect())
+}
+
+// missing operators fall back to their defaults, which are always supported
+fn operator<'a>(operator: &'a Option<(String, Location)>, default: &'a str) -> (&'a str, Location) {
+    match operator {
+        Some((name, location)) => (name, *location),
+        None => (default, Location::default()),
+    }
+}
+
+fn not_supported<T>(name: &str, location: Location) -> Result<T, FclError> {
+    location.error(format!("`{}` is not supported", name))
+}
+
+pub fn parse_fcl(source: &str) -> Result<MamdaniFIS, FclError> {
+    parse_fcl_with_resolution(source, DEFAULT_RESOLUTION)
+}
+
+pub fn parse_fcl_with_resolution(source: &str, n: i32) -> Result<MamdaniFIS, FclError> {
+    let mut parser = Parser {
+        tokens: tokenize(source)?,
+        position: 0,
+    };
+    let block = parser.function_block()?;
+    for (blocks, variables, kind) in [
+        (&block.fuzzify, &block.inputs, "input"),
+        (&block.defuzzify, &block.outputs, "output"),
+    ] {
+        for b in blocks {
+            if !variables.iter().any(|v| v.0 == b.name) {
+                return b
+                    .location
+                    .error(format!("`{}` is not declared as an {}", b.name, kind));
+            }
+        }
+    }
+
+    let mut inputs = Vec::new();
+    for variable in &block.inputs {
+        let fuzzify = find_block(&block.fuzzify, variable, "FUZZIFY")?;
+        let range = match fuzzify.range {
+            Some(range) => range,
+            None => {
+                let x: Vec<f64> = fuzzify
+                    .terms
+                    .iter()
+                    .flat_map(|term| match &term.shape {
+                        TermShape::Points(points) => points.iter().map(|p| p.0).collect(),
+                        _ => Vec::new(),
+                    })
+                    .collect();
+                match (x.first(), x.last()) {
+                    (Some(_), Some(_)) => (
+                        x.iter().cloned().fold(f64::INFINITY, f64::min),
+                        x.iter().cloned().fold(f64::NEG_INFINITY, f64::max),
+                    ),
+                    _ => return variable.1.error(format!("`{}` needs a RANGE", variable.0)),
+                }
+            }
+        };
+        let mut input = InputVariable::new(variable.0.clone(), range);
+        for term in &fuzzify.terms {
+            input.add_membership(MembershipFunction::new(
+                term.name.clone(),
+                shape_kind(term)?,
+            ));
+        }
+        inputs.push(input);
+    }
+
+    let mut method: Option<&(String, Location)> = None;
+    let mut outputs = Vec::new();
+    for variable in &block.outputs {
+        let defuzzify = find_block(&block.defuzzify, variable, "DEFUZZIFY")?;
+        let range = match defuzzify.range {
+            Some(range) => range,
+            None => return variable.1.error(format!("`{}` needs a RANGE", variable.0)),
+        };
+        if let (Some(first), Some(current)) = (method, &defuzzify.method) {
+            if first.0 != current.0 {
+                return current.1.error("all outputs must use the same METHOD");
+            }
+        }
+        method = method.or(defuzzify.method.as_ref());
+        let mut output = OutputVariable::new(variable.0.clone(), range, n);
+        if let Some(default) = &defuzzify.default {
+            output.set_no_activation_policy(default.clone());
+        }
+        for term in &defuzzify.terms {
+            let range = shape_range(output.get_universe(), term)?;
+            output.add_membership(range);
+        }
+        outputs.push(output);
+    }
+
+    let operators = &block.operators;
+    let t_norm = match operator(&operators.and, "MIN") {
+        ("MIN", _) => TNorms::Min,
+        ("PROD", _) => TNorms::Product,
+        ("BDIF", _) => TNorms::Lukasiewicz,
+        (name, location) => return not_supported(name, location),
+    };
+    // OR defaults to the dual of AND
+    let s_norm = match &operators.or {
+        None => SNorms::dual_of(&t_norm),
+        or => match operator(or, "MAX") {
+            ("MAX", _) => SNorms::Max,
+            ("ASUM", _) => SNorms::ProbabilisticSum,
+            ("BSUM", _) => SNorms::BoundedSum,
+            (name, location) => return not_supported(name, location),
+        },
+    };
+    let implication = match operator(&operators.act, "MIN") {
+        ("MIN", _) => Implications::Min,
+        ("PROD", _) => Implications::Product,
+        (name, location) => return not_supported(name, location),
+    };
+    let aggregation = match operator(&operators.accu, "MAX") {
+        ("MAX", _) => Aggregations::Max,
+        ("BSUM", _) => Aggregations::BoundedSum,
+        ("ASUM", _) => Aggregations::ProbabilisticOr,
+        ("NSUM", _) => Aggregations::NormalizedSum,
+        (name, location) => return not_supported(name, location),
+    };
+    let method = method.cloned();
+    let defuzzifier = match operator(&method, "COG") {
+        ("COG", _) => Defuzzifiers::Centroid,
+        ("COA", _) => Defuzzifiers::Bisection,
+        ("LM", _) => Defuzzifiers::SmallestOfMaximum,
+        ("RM", _) => Defuzzifiers::LargestOfMaximum,
+        (name, location) => return not_supported(name, location),
+    };
+
+    let mut rules = Vec::new();
+    for rule in &block.rules {
+        let antecedent = expression(&rule.antecedent, &block.fuzzify, &block.inputs)?;
+        let consequent = relations(&rule.consequent, &block.defuzzify, &block.outputs, "output")?;
+        // rules with one connective keep the simple form
+        rules.push(match antecedent.flatten(block.inputs.len()) {
+            Some((kind, mut terms)) => {
+                terms.extend(consequent);
+                match kind {
+                    rules::Kind::OR => Rule::new_or(terms, rule.weight),
+                    rules::Kind::AND => Rule::new_and(terms, rule.weight),
+                }
+            }
+            None => Rule::new_expression(antecedent, consequent, rule.weight),
+        });
+    }
+
+    Ok(MamdaniFIS::new_all(
+        s_norm,
+        t_norm,
+        implication,
+        aggregation,
+        defuzzifier,
+        rules,
+        inputs,
+        outputs,
+    ))
+}
+
+pub fn load_fcl<P: AsRef<Path>>(path: P) -> Result<MamdaniFIS, FclError> {
+    parse_fcl(&fs::read_to_string(path)?)
+}
+
+fn check_name(name: &str) -> Result<&str, FclError> {
+    let mut chars = name.chars();
+    let valid = chars
+        .next()
+        .is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_');
+    if valid {
+        Ok(name)
+    } else {
+        Err(FclError::Unsupported(format!(
+            "`{}` is not a valid FCL name",
+            name
+        )))
+    }
+}
+
+fn term_definition(kind: &Kind) -> Result<String, FclError> {
+    let p = kind.get_parameters();
+    Ok(match kind {
+        Kind::Triangle(_) => format!("({}, 0) ({}, 1) ({}, 0)", p[0], p[1], p[2]),
+        Kind::Trapezoid(_) => format!("({}, 0) ({}, 1) ({}, 1) ({}, 0)", p[0], p[1], p[2], p[3]),
+        Kind::LinearS(_) => format!("({}, 0) ({}, 1)", p[0], p[1]),
+        Kind::LinearZ(_) => format!("({}, 1) ({}, 0)", p[0], p[1]),
+        Kind::Gaussian(_) | Kind::Normal(_) => format!("gauss {} {}", p[0], p[1]),
+        Kind::Bell(_) => format!("gbell {} {} {}", p[0], p[1], p[2]),
+        _ => {
+            return Err(FclError::Unsupported(format!(
+                "membership function {:?} has no FCL equivalent",
+                kind
+            )))
+        }
+    })
+}
+
+pub fn write_fcl(fis: &MamdaniFIS, name: &str) -> Result<String, FclError> {
+    let unsupported = |what: &str| Err(FclError::Unsupported(format!("custom {}", what)));
+    let and = match fis.get_t_norm_method() {
+        TNorms::Min => "MIN",
+        TNorms::Product => "PROD",
+        TNorms::Lukasiewicz => "BDIF",
+        TNorms::Custom(_) => return unsupported("t-norm"),
+        t_norm => {
+            return Err(FclError::Unsupported(format!(
+                "t-norm {:?} has no FCL equivalent",
+                t_norm
+            )))
+        }
+    };
+    let or = match fis.get_s_norm_method() {
+        SNorms::Max => "MAX",
+        SNorms::ProbabilisticSum => "ASUM",
+        SNorms::BoundedSum => "BSUM",
+        SNorms::Custom(_) => return unsupported("s-norm"),
+        s_norm => {
+            return Err(FclError::Unsupported(format!(
+                "s-norm {:?} has no FCL equivalent",
+                s_norm
+            )))
+        }
+    };
+    if fis.get_reasoning_method() != Reasoning::Conjunctive {
+        return Err(FclError::Unsupported("implicative reasoning".to_string()));
+    }
+    if !matches!(fis.get_complement_method(), Complements::Standard) {
+        return Err(FclError::Unsupported(format!(
+            "complement {:?} has no FCL equivalent",
+            fis.get_complement_method()
+        )));
+    }
+    let input_size = fis.get_inputs().len();
+    for (i, rule) in fis.get_rule_base().iter().enumerate() {
+        if let Some(Relation::Hedged(hedge, _)) = rule
+            .get_rules()
+            .iter()
+            .find(|relation| matches!(relation, Relation::Hedged(..)))
+        {
+            return Err(FclError::Unsupported(format!(
+                "hedge {:?} has no FCL equivalent",
+                hedge
+            )));
+        }
+        if let Some(antecedent) = rule.get_antecedent() {
+            if has_own_norms(antecedent) {
+                return Err(FclError::Unsupported(format!(
+                    "rule {} has its own t-norm or s-norm",
+                    i + 1
+                )));
+            }
+        } else if rule
+            .get_input_rules(input_size)
+            .iter()
+            .all(Relation::is_any)
+        {
+            return Err(FclError::Unsupported(format!(
+                "rule {} has no condition",
+                i + 1
+            )));
+        }
+    }
+    let act = match fis.get_implication_method() {
+        Implications::Min => "MIN",
+        Implications::Product => "PROD",
+        Implications::Custom(_) => return unsupported("implication"),
+        implication => {
+            return Err(FclError::Unsupported(format!(
+                "implication {:?} has no FCL equivalent",
+                implication
+            )))
+        }
+    };
+    let accu = match fis.get_aggregation_method() {
+        Aggregations::Max => "MAX",
+        Aggregations::BoundedSum => "BSUM",
+        Aggregations::ProbabilisticOr => "ASUM",
+        Aggregations::NormalizedSum => "NSUM",
+        Aggregations::Custom(_) => return unsupported("aggregation"),
+        aggregation => {
+            return Err(FclError::Unsupported(format!(
+                "aggregation {:?} has no FCL equivalent",
+                aggregation
+            )))
+        }
+    };
+    let method = match fis.get_defuzzifier_method() {
+        Defuzzifiers::Centroid => "COG",
+        Defuzzifiers::Bisection => "COA",
+        Defuzzifiers::SmallestOfMaximum => "LM",
+        Defuzzifiers::LargestOfMaximum => "RM",
+        Defuzzifiers::Custom(_) => return unsupported("defuzzifier"),
+        defuzzifier => {
+            return Err(FclError::Unsupported(format!(
+                "defuzzifier {:?} has no FCL equivalent",
+                defuzzifier
+            )))
+        }
+    };
+
+    let inputs = fis.get_inputs();
+    let outputs = fis.get_outputs();
+    let mut out = String::new();
+    writeln!(out, "FUNCTION_BLOCK {}\n", check_name(name)?).unwrap();
+    writeln!(out, "VAR_INPUT").unwrap();
+    for input in inputs {
+        writeln!(out, "    {} : REAL;", check_name(input.get_name())?).unwrap();
+    }
+    writeln!(out, "END_VAR\n").unwrap();
+    writeln!(out, "VAR_OUTPUT").unwrap();
+    for output in outputs {
+        writeln!(out, "    {} : REAL;", check_name(output.get_name())?).unwrap();
+    }
+    writeln!(out, "END_VAR\n").unwrap();
+
+    for input in inputs {
+        let (start, stop) = input.get_range();
+        writeln!(out, "FUZZIFY {}", input.get_name()).unwrap();
+        writeln!(out, "    RANGE := ({} .. {});", start, stop).unwrap();
+        for mf in input.get_memberships() {
+            let definition = term_definition(mf.get_kind())?;
+            writeln!(
+                out,
+                "    TERM {} := {};",
+                check_name(mf.get_name())?,
+                definition
+            )
+            .unwrap();
+        }
+        writeln!(out, "END_FUZZIFY\n").unwrap();
+    }
+
+    for output in outputs {
+        let (start, stop) = output.get_range();
+        writeln!(out, "DEFUZZIFY {}", output.get_name()).unwrap();
+        writeln!(out, "    RANGE := ({} .. {});", start, stop).unwrap();
+        for mr in output.get_memberships() {
+            let definition = match mr.get_kind() {
+                Some(kind) => term_definition(kind)?,
+                None => {
+                    return Err(FclError::Unsupported(format!(
+                        "sampled membership range `{}`",
+                        mr.get_name()
+                    )))
+                }
+            };
+            writeln!(
+                out,
+                "    TERM {} := {};",
+                check_name(&mr.get_name())?,
+                definition
+            )
+            .unwrap();
+        }
+        writeln!(out, "    METHOD : {};", method).unwrap();
+        match output.get_no_activation_policy() {
+            Some(NoActivationPolicies::Value(value)) => {
+                writeln!(out, "    DEFAULT := {};", value).unwrap()
+            }
+            Some(NoActivationPolicies::HoldLast) => writeln!(out, "    DEFAULT := NC;").unwrap(),
+            Some(NoActivationPolicies::MidRange) => {
+                writeln!(out, "    DEFAULT := {};", (start + stop) / 2.0).unwrap()
+            }
+            Some(NoActivationPolicies::Error) | None => {}
+        }
+        writeln!(out, "END_DEFUZZIFY\n").unwrap();
+    }
+
+    writeln!(out, "RULEBLOCK rules").unwrap();
+    writeln!(out, "    AND : {};", and).unwrap();
+    writeln!(out, "    OR : {};", or).unwrap();
+    writeln!(out, "    ACT : {};", act).unwrap();
+    writeln!(out, "    ACCU : {};", accu).unwrap();
+    for i in 0..fis.get_rule_base().len() {
+        writeln!(out, "    RULE {} : {};", i + 1, fis.rule_to_text(i)).unwrap();
+    }
+    writeln!(out, "END_RULEBLOCK\n").unwrap();
+    writeln!(out, "END_FUNCTION_BLOCK").unwrap();
+    Ok(out)
+}
+
+pub fn save_fcl<P: AsRef<Path>>(fis: &MamdaniFIS, name: &str, path: P) -> Result<(), FclError> {
+    fs::write(path, write_fcl(fis, name)?)?;
+    Ok(())
+}
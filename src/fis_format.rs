@@ -0,0 +1,886 @@
+use std::collections::HashMap;
+use std::fmt::{self, Write};
+use std::fs;
+use std::path::Path;
+
+use crate::aggregations::Aggregations;
+use crate::complements::Complements;
+use crate::defuzzifications::{Defuzzifiers, TSKDefuzzifiers};
+use crate::fuzzy_inference_systems::{MamdaniFIS, TSKFIS};
+use crate::implications::{Implications, Reasoning};
+use crate::membership_functions::{
+    Bell, DoubleGaussian, Gaussian, Kind, LinearS, LinearZ, MembershipFunction, SplineS, SplineZ,
+    TSKMembershipFunction, Trapezoid, Triangle,
+};
+use crate::membership_ranges::MembershipRange;
+use crate::rules::{self, OutputRelation, Relation, Rule};
+use crate::s_norms::SNorms;
+use crate::t_norms::TNorms;
+use crate::variables::{InputVariable, OutputVariable, TSKOutputVariable};
+
+// MATLAB evaluates Mamdani outputs on 101 points unless told otherwise.
+pub const DEFAULT_RESOLUTION: i32 = 101;
+
+#[derive(Debug)]
+pub enum FisError {
+    Io(std::io::Error),
+    Parse { line: usize, message: String },
+    Unsupported(String),
+}
+
+impl fmt::Display for FisError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Io(err) => write!(f, "io error: {}", err),
+            Self::Parse { line, message } => write!(f, "line {}: {}", line, message),
+            Self::Unsupported(message) => write!(f, "unsupported: {}", message),
+        }
+    }
+}
+
+impl std::error::Error for FisError {}
+
+impl From<std::io::Error> for FisError {
+    fn from(err: std::io::Error) -> Self {
+        Self::Io(err)
+    }
+}
+
+fn parse_error<T>(line: usize, message: impl Into<String>) -> Result<T, FisError> {
+    Err(FisError::Parse {
+        line,
+        message: message.into(),
+    })
+}
+
+#[derive(Debug)]
+struct MFEntry {
+    name: String,
+    mf_type: String,
+    params: Vec<f64>,
+    line: usize,
+}
+
+#[derive(Debug)]
+struct Section {
+    name: String,
+    line: usize,
+    entries: HashMap<String, (String, usize)>,
+    lines: Vec<(usize, String)>,
+}
+
+impl Section {
+    fn get(&self, key: &str) -> Result<(&str, usize), FisError> {
+        match self.entries.get(key) {
+            Some((value, line)) => Ok((value.as_str(), *line)),
+            None => parse_error(self.line, format!("[{}] is missing `{}`", self.name, key)),
+        }
+    }
+
+    fn get_string(&self, key: &str) -> Result<String, FisError> {
+        let (value, line) = self.get(key)?;
+        parse_quoted(value, line)
+    }
+
+    fn get_usize(&self, key: &str) -> Result<usize, FisError> {
+        let (value, line) = self.get(key)?;
+        match value.parse::<usize>() {
+            Ok(n) => Ok(n),
+            Err(_) => parse_error(line, format!("`{}` is not a valid count", value)),
+        }
+    }
+
+    fn get_range(&self) -> Result<(f64, f64), FisError> {
+        let (value, line) = self.get("Range")?;
+        let range = parse_vector(value, line)?;
+        if range.len() != 2 || range[0] >= range[1] {
+            return parse_error(line, format!("`{}` is not a valid range", value));
+        }
+        Ok((range[0], range[1]))
+    }
+
+    fn get_mfs(&self) -> Result<Vec<MFEntry>, FisError> {
+        let mut mfs = Vec::new();
+        for i in 1..=self.get_usize("NumMFs")? {
+            let (value, line) = self.get(&format!("MF{}", i))?;
+            let (name, rest) = split_quoted(value, line)?;
+            let rest = match rest.trim_start().strip_prefix(':') {
+                Some(rest) => rest,
+                None => return parse_error(line, "expected `:` after the membership name"),
+            };
+            let (mf_type, rest) = split_quoted(rest, line)?;
+            let rest = match rest.trim_start().strip_prefix(',') {
+                Some(rest) => rest,
+                None => return parse_error(line, "expected `,` after the membership type"),
+            };
+            mfs.push(MFEntry {
+                name,
+                mf_type,
+                params: parse_vector(rest, line)?,
+                line,
+            });
+        }
+        Ok(mfs)
+    }
+}
+
+fn split_sections(source: &str) -> Result<Vec<Section>, FisError> {
+    let mut sections: Vec<Section> = Vec::new();
+    for (index, raw) in source.lines().enumerate() {
+        let line = index + 1;
+        let text = raw.trim();
+        if text.is_empty() {
+            continue;
+        }
+        if text.starts_with('[') && text.ends_with(']') {
+            sections.push(Section {
+                name: text[1..text.len() - 1].to_string(),
+                line,
+                entries: HashMap::new(),
+                lines: Vec::new(),
+            });
+            continue;
+        }
+        let section = match sections.last_mut() {
+            Some(section) => section,
+            None => return parse_error(line, "expected a section header like `[System]`"),
+        };
+        if section.name == "Rules" {
+            section.lines.push((line, text.to_string()));
+        } else {
+            match text.split_once('=') {
+                Some((key, value)) => {
+                    section
+                        .entries
+                        .insert(key.trim().to_string(), (value.trim().to_string(), line));
+                }
+                None => {
+                    return parse_error(line, format!("expected `key=value`, found `{}`", text))
+                }
+            }
+        }
+    }
+    Ok(sections)
+}
+
+fn split_quoted(text: &str, line: usize) -> Result<(String, &str), FisError> {
+    let text = text.trim_start();
+    let rest = match text.strip_prefix('\'') {
+        Some(rest) => rest,
+        None => return parse_error(line, format!("expected a quoted string, found `{}`", text)),
+    };
+    match rest.find('\'') {
+        Some(end) => Ok((rest[..end].to_string(), &rest[end + 1..])),
+        None => parse_error(line, "unterminated quoted string"),
+    }
+}
+
+fn parse_quoted(text: &str, line: usize) -> Result<String, FisError> {
+    let (value, rest) = split_quoted(text, line)?;
+    if !rest.trim().is_empty() {
+        return parse_error(
+            line,
+            format!("unexpected `{}` after quoted string", rest.trim()),
+        );
+    }
+    Ok(value)
+}
+
+fn parse_vector(text: &str, line: usize) -> Result<Vec<f64>, FisError> {
+    let text = text.trim();
+    let inner = match text.strip_prefix('[').and_then(|t| t.strip_suffix(']')) {
+        Some(inner) => inner,
+        None => return parse_error(line, format!("expected `[...]`, found `{}`", text)),
+    };
+    inner
+        .split(|c: char| c.is_whitespace() || c == ',')
+        .filter(|token| !token.is_empty())
+        .map(|token| match token.parse::<f64>() {
+            Ok(value) => Ok(value),
+            Err(_) => parse_error(line, format!("`{}` is not a number", token)),
+        })
+        .collect()
+}
+
+fn check(condition: bool, line: usize, message: &str) -> Result<(), FisError> {
+    if condition {
+        Ok(())
+    } else {
+        parse_error(line, message)
+    }
+}
+
+fn check_parameters(mf_type: &str, params: &[f64], line: usize) -> Result<(), FisError> {
+    let expected = match mf_type {
+        "trimf" | "gbellmf" => 3,
+        "trapmf" | "gauss2mf" => 4,
+        "gaussmf" | "smf" | "zmf" | "linsmf" | "linzmf" => 2,
+        _ => {
+            return Err(FisError::Unsupported(format!(
+                "membership function type '{}' on line {}",
+                mf_type, line
+            )))
+        }
+    };
+    if params.len() != expected {
+        return parse_error(
+            line,
+            format!(
+                "'{}' needs {} parameters, found {}",
+                mf_type,
+                expected,
+                params.len()
+            ),
+        );
+    }
+    match mf_type {
+        "trimf" => {
+            check(
+                params[0] <= params[1],
+                line,
+                "a must be less than or equal to b",
+            )?;
+            check(
+                params[1] <= params[2],
+                line,
+                "b must be less than or equal to c",
+            )
+        }
+        "trapmf" => {
+            check(params[0] <= params[1], line, "a must be less than b")?;
+            check(params[1] <= params[2], line, "b must be less than c")?;
+            check(params[2] <= params[3], line, "c must be less than d")
+        }
+        "gaussmf" => check(params[0] > 0.0, line, "sigma must be positive"),
+        "gauss2mf" => {
+            check(params[0] > 0.0, line, "sigma1 must be positive")?;
+            check(params[2] > 0.0, line, "sigma2 must be positive")?;
+            check(
+                params[1] <= params[3],
+                line,
+                "mean1 must be less than mean2",
+            )
+        }
+        "gbellmf" => {
+            check(params[0] > 0.0, line, "width must be positive")?;
+            check(params[1] > 0.0, line, "shape must be positive")
+        }
+        _ => check(params[0] < params[1], line, "a must be less than b"),
+    }
+}
+
+fn mf_kind(mf_type: &str, params: &[f64], line: usize) -> Result<Kind, FisError> {
+    check_parameters(mf_type, params, line)?;
+    let p = params;
+    Ok(match mf_type {
+        "trimf" => Kind::Triangle(Triangle::new(p[0], p[1], p[2])),
+        "trapmf" => Kind::Trapezoid(Trapezoid::new(p[0], p[1], p[2], p[3])),
+        "gaussmf" => Kind::Gaussian(Gaussian::new(p[1], p[0])),
+        "gauss2mf" => Kind::DoubleGaussian(DoubleGaussian::new(p[1], p[0], p[3], p[2])),
+        "gbellmf" => Kind::Bell(Bell::new(p[0], p[1], p[2])),
+        "smf" => Kind::SplineS(SplineS::new(p[0], p[1])),
+        "zmf" => Kind::SplineZ(SplineZ::new(p[0], p[1])),
+        "linsmf" => Kind::LinearS(LinearS::new(p[0], p[1])),
+        _ => Kind::LinearZ(LinearZ::new(p[0], p[1])),
+    })
+}
+
+fn membership_range(
+    universe: &[f64],
+    name: String,
+    mf_type: &str,
+    params: &[f64],
+    line: usize,
+) -> Result<MembershipRange, FisError> {
+    let kind = mf_kind(mf_type, params, line)?;
+    Ok(MembershipRange::from_kind(universe, name, kind))
+}
+
+fn find_section<'a>(sections: &'a [Section], name: &str) -> Result<&'a Section, FisError> {
+    match sections.iter().find(|s| s.name == name) {
+        Some(section) => Ok(section),
+        None => parse_error(0, format!("missing section [{}]", name)),
+    }
+}
+
+fn unsupported<T>(what: &str, value: &str) -> Result<T, FisError> {
+    Err(FisError::Unsupported(format!("{} '{}'", what, value)))
+}
+
+fn t_norm(value: &str) -> Result<TNorms, FisError> {
+    match value {
+        "min" => Ok(TNorms::Min),
+        "prod" => Ok(TNorms::Product),
+        _ => unsupported("AndMethod", value),
+    }
+}
+
+fn s_norm(value: &str) -> Result<SNorms, FisError> {
+    match value {
+        "max" => Ok(SNorms::Max),
+        "probor" => Ok(SNorms::ProbabilisticSum),
+        _ => unsupported("OrMethod", value),
+    }
+}
+
+fn inputs(sections: &[Section], count: usize) -> Result<Vec<InputVariable>, FisError> {
+    let mut inputs = Vec::new();
+    for i in 1..=count {
+        let section = find_section(sections, &format!("Input{}", i))?;
+        let mut input = InputVariable::new(section.get_string("Name")?, section.get_range()?);
+        for MFEntry {
+            name,
+            mf_type,
+            params,
+            line,
+        } in section.get_mfs()?
+        {
+            input.add_membership(MembershipFunction::new(
+                name,
+                mf_kind(&mf_type, &params, line)?,
+            ));
+        }
+        inputs.push(input);
+    }
+    Ok(inputs)
+}
+
+fn parse_rules(
+    section: &Section,
+    input_terms: &[usize],
+    output_terms: &[usize],
+) -> Result<Vec<Rule>, FisError> {
+    let mut rules = Vec::new();
+    for (line, text) in &section.lines {
+        let line = *line;
+        let (body, connection) = match text.rsplit_once(':') {
+            Some(split) => split,
+            None => return parse_error(line, "expected `: 1` or `: 2` at the end of the rule"),
+        };
+        let (antecedent, rest) = match body.split_once(',') {
+            Some(split) => split,
+            None => return parse_error(line, "expected `,` between inputs and outputs"),
+        };
+        let (consequent, weight) = match rest.split_once('(') {
+            Some((consequent, weight)) => (consequent, weight.trim().trim_end_matches(')')),
+            None => return parse_error(line, "expected the rule weight in parentheses"),
+        };
+        let weight = match weight.trim().parse::<f64>() {
+            Ok(weight) if (0.0..=1.0).contains(&weight) => weight,
+            _ => return parse_error(line, format!("`{}` is not a weight in [0, 1]", weight)),
+        };
+
+        let mut relations = Vec::new();
+        for (tokens, terms) in [(antecedent, input_terms), (consequent, output_terms)] {
+            let indices = tokens
+                .split_whitespace()
+                .map(|token| match token.parse::<i32>() {
+                    Ok(index) => Ok(index),
+                    Err(_) => parse_error(line, format!("`{}` is not a term index", token)),
+                })
+                .collect::<Result<Vec<i32>, FisError>>()?;
+            if indices.len() != terms.len() {
+                return parse_error(
+                    line,
+                    format!("expected {} indices, found {}", terms.len(), indices.len()),
+                );
+            }
+            // MATLAB counts terms from 1 and uses 0 for don't care
+            for (index, count) in indices.into_iter().zip(terms) {
+                if index.unsigned_abs() as usize > *count {
+                    return parse_error(line, format!("term {} does not exist", index));
+                }
+                let term = index.unsigned_abs() as usize;
+                relations.push(match index {
+                    0 => Relation::Any,
+                    _ if index < 0 => Relation::Not(term - 1),
+                    _ => Relation::Term(term - 1),
+                });
+            }
+        }
+
+        rules.push(match connection.trim() {
+            "1" => Rule::new_and(relations, weight),
+            "2" => Rule::new_or(relations, weight),
+            other => return parse_error(line, format!("`{}` is not a connection", other)),
+        });
+    }
+    Ok(rules)
+}
+
+fn system(source: &str, kind: &str) -> Result<(Vec<Section>, usize, usize), FisError> {
+    let sections = split_sections(source)?;
+    let system = find_section(&sections, "System")?;
+    let (_, line) = system.get("Type")?;
+    let found = system.get_string("Type")?;
+    if found != kind {
+        return parse_error(
+            line,
+            format!("expected a {} system, found '{}'", kind, found),
+        );
+    }
+    let num_inputs = system.get_usize("NumInputs")?;
+    let num_outputs = system.get_usize("NumOutputs")?;
+    Ok((sections, num_inputs, num_outputs))
+}
+
+pub fn parse_mamdani(source: &str) -> Result<MamdaniFIS, FisError> {
+    parse_mamdani_with_resolution(source, DEFAULT_RESOLUTION)
+}
+
+pub fn parse_mamdani_with_resolution(source: &str, n: i32) -> Result<MamdaniFIS, FisError> {
+    let (sections, num_inputs, num_outputs) = system(source, "mamdani")?;
+    let system = find_section(&sections, "System")?;
+    let t_norm = t_norm(&system.get_string("AndMethod")?)?;
+    let s_norm = s_norm(&system.get_string("OrMethod")?)?;
+    let implication = match system.get_string("ImpMethod")?.as_str() {
+        "min" => Implications::Min,
+        "prod" => Implications::Product,
+        other => return unsupported("ImpMethod", other),
+    };
+    let aggregation = match system.get_string("AggMethod")?.as_str() {
+        "max" => Aggregations::Max,
+        "sum" => Aggregations::Sum,
+        "probor" => Aggregations::ProbabilisticOr,
+        other => return unsupported("AggMethod", other),
+    };
+    let defuzzifier = match system.get_string("DefuzzMethod")?.as_str() {
+        "centroid" => Defuzzifiers::Centroid,
+        "bisector" => Defuzzifiers::Bisection,
+        "mom" => Defuzzifiers::MeanOfMaximum,
+        "som" => Defuzzifiers::SmallestOfMaximum,
+        "lom" => Defuzzifiers::LargestOfMaximum,
+        other => return unsupported("DefuzzMethod", other),
+    };
+
+    let inputs = inputs(&sections, num_inputs)?;
+    let mut outputs = Vec::new();
+    for i in 1..=num_outputs {
+        let section = find_section(&sections, &format!("Output{}", i))?;
+        let mut output = OutputVariable::new(section.get_string("Name")?, section.get_range()?, n);
+        for MFEntry {
+            name,
+            mf_type,
+            params,
+            line,
+        } in section.get_mfs()?
+        {
+            let range = membership_range(output.get_universe(), name, &mf_type, &params, line)?;
+            output.add_membership(range);
+        }
+        outputs.push(output);
+    }
+
+    let input_terms: Vec<usize> = inputs.iter().map(|v| v.get_memberships().len()).collect();
+    let output_terms: Vec<usize> = outputs.iter().map(|v| v.get_memberships().len()).collect();
+    let rules = parse_rules(
+        find_section(&sections, "Rules")?,
+        &input_terms,
+        &output_terms,
+    )?;
+
+    Ok(MamdaniFIS::new_all(
+        s_norm,
+        t_norm,
+        implication,
+        aggregation,
+        defuzzifier,
+        rules,
+        inputs,
+        outputs,
+    ))
+}
+
+pub fn parse_tsk(source: &str) -> Result<TSKFIS, FisError> {
+    let (sections, num_inputs, num_outputs) = system(source, "sugeno")?;
+    let system = find_section(&sections, "System")?;
+    let t_norm = t_norm(&system.get_string("AndMethod")?)?;
+    let s_norm = s_norm(&system.get_string("OrMethod")?)?;
+    let defuzzification = match system.get_string("DefuzzMethod")?.as_str() {
+        "wtaver" => TSKDefuzzifiers::Mean,
+        "wtsum" => TSKDefuzzifiers::Sum,
+        other => return unsupported("DefuzzMethod", other),
+    };
+    let mut fis = TSKFIS::new(s_norm, t_norm, defuzzification);
+
+    let inputs = inputs(&sections, num_inputs)?;
+    let input_terms: Vec<usize> = inputs.iter().map(|v| v.get_memberships().len()).collect();
+    let mut output_terms = Vec::new();
+    for i in 1..=num_outputs {
+        let section = find_section(&sections, &format!("Output{}", i))?;
+        let mut output = TSKOutputVariable::new(section.get_string("Name")?);
+        for MFEntry {
+            mf_type,
+            params,
+            line,
+            ..
+        } in section.get_mfs()?
+        {
+            match mf_type.as_str() {
+                "constant" => {
+                    check(params.len() == 1, line, "'constant' needs 1 parameter")?;
+                    output.add_constant_membership(params[0]);
+                }
+                "linear" => {
+                    check(
+                        params.len() == num_inputs + 1,
+                        line,
+                        "'linear' needs one coefficient per input plus a constant",
+                    )?;
+                    output.add_affine_membership(params);
+                }
+                other => return unsupported("output membership function type", other),
+            }
+        }
+        output_terms.push(output.get_memberships().len());
+        fis.add_output(output);
+    }
+    for input in inputs {
+        fis.add_input(input);
+    }
+
+    let section = find_section(&sections, "Rules")?;
+    for rule in parse_rules(section, &input_terms, &output_terms)? {
+        for relation in rule.get_output_rules(num_inputs) {
+            match relation {
+                Relation::Term(_) => (),
+                Relation::Any => return unsupported("TSK consequent", "0"),
+                _ => return unsupported("TSK consequent", "NOT"),
+            }
+        }
+        fis.add_rule(rule);
+    }
+    Ok(fis)
+}
+
+pub fn load_mamdani<P: AsRef<Path>>(path: P) -> Result<MamdaniFIS, FisError> {
+    parse_mamdani(&fs::read_to_string(path)?)
+}
+
+pub fn load_tsk<P: AsRef<Path>>(path: P) -> Result<TSKFIS, FisError> {
+    parse_tsk(&fs::read_to_string(path)?)
+}
+
+fn format_vector(values: &[f64]) -> String {
+    let values: Vec<String> = values.iter().map(|v| v.to_string()).collect();
+    format!("[{}]", values.join(" "))
+}
+
+fn mf_type(kind: &Kind) -> Result<(&'static str, Vec<f64>), FisError> {
+    let p = kind.get_parameters();
+    Ok(match kind {
+        Kind::Triangle(_) => ("trimf", p),
+        Kind::Trapezoid(_) => ("trapmf", p),
+        Kind::Gaussian(_) | Kind::Normal(_) => ("gaussmf", vec![p[1], p[0]]),
+        Kind::DoubleGaussian(_) => ("gauss2mf", vec![p[1], p[0], p[3], p[2]]),
+        Kind::Bell(_) => ("gbellmf", p),
+        Kind::LinearS(_) => ("linsmf", p),
+        Kind::LinearZ(_) => ("linzmf", p),
+        Kind::SplineS(_) => ("smf", p),
+        Kind::SplineZ(_) => ("zmf", p),
+        Kind::StepUp(_) | Kind::StepDown(_) | Kind::Custom(_) => {
+            return Err(FisError::Unsupported(format!(
+                "membership function {:?} has no MATLAB equivalent",
+                kind
+            )))
+        }
+    })
+}
+
+fn tsk_defuzzification_name(defuzzification: &TSKDefuzzifiers) -> Result<&'static str, FisError> {
+    match defuzzification {
+        TSKDefuzzifiers::Mean => Ok("wtaver"),
+        TSKDefuzzifiers::Sum => Ok("wtsum"),
+        TSKDefuzzifiers::Softmax => unsupported("defuzzifier", "Softmax"),
+        TSKDefuzzifiers::Custom(_) => unsupported("defuzzifier", "Custom"),
+    }
+}
+
+fn t_norm_name(t_norm: &TNorms) -> Result<&'static str, FisError> {
+    match t_norm {
+        TNorms::Min => Ok("min"),
+        TNorms::Product => Ok("prod"),
+        TNorms::Custom(_) => unsupported("t-norm", "Custom"),
+        t_norm => unsupported("t-norm", &format!("{:?}", t_norm)),
+    }
+}
+
+fn s_norm_name(s_norm: &SNorms) -> Result<&'static str, FisError> {
+    match s_norm {
+        SNorms::Max => Ok("max"),
+        SNorms::ProbabilisticSum => Ok("probor"),
+        SNorms::Custom(_) => unsupported("s-norm", "Custom"),
+        s_norm => unsupported("s-norm", &format!("{:?}", s_norm)),
+    }
+}
+
+// MATLAB always negates a term with 1 - mu
+fn check_complement(complement: &Complements) -> Result<(), FisError> {
+    match complement {
+        Complements::Standard => Ok(()),
+        complement => unsupported("complement", &format!("{:?}", complement)),
+    }
+}
+
+fn write_system(
+    out: &mut String,
+    name: &str,
+    kind: &str,
+    counts: (usize, usize, usize),
+    methods: [&str; 5],
+) {
+    writeln!(out, "[System]").unwrap();
+    writeln!(out, "Name='{}'", name).unwrap();
+    writeln!(out, "Type='{}'", kind).unwrap();
+    writeln!(out, "Version=2.0").unwrap();
+    writeln!(out, "NumInputs={}", counts.0).unwrap();
+    writeln!(out, "NumOutputs={}", counts.1).unwrap();
+    writeln!(out, "NumRules={}", counts.2).unwrap();
+    writeln!(out, "AndMethod='{}'", methods[0]).unwrap();
+    writeln!(out, "OrMethod='{}'", methods[1]).unwrap();
+    writeln!(out, "ImpMethod='{}'", methods[2]).unwrap();
+    writeln!(out, "AggMethod='{}'", methods[3]).unwrap();
+    writeln!(out, "DefuzzMethod='{}'", methods[4]).unwrap();
+    writeln!(out).unwrap();
+}
+
+fn write_inputs(out: &mut String, inputs: &[InputVariable]) -> Result<(), FisError> {
+    for (i, input) in inputs.iter().enumerate() {
+        let (start, stop) = input.get_range();
+        let mfs = input.get_memberships();
+        writeln!(out, "[Input{}]", i + 1).unwrap();
+        writeln!(out, "Name='{}'", input.get_name()).unwrap();
+        writeln!(out, "Range={}", format_vector(&[*start, *stop])).unwrap();
+        writeln!(out, "NumMFs={}", mfs.len()).unwrap();
+        for (ii, mf) in mfs.iter().enumerate() {
+            let (mf_type, params) = mf_type(mf.get_kind())?;
+            writeln!(
+                out,
+                "MF{}='{}':'{}',{}",
+                ii + 1,
+                mf.get_name(),
+                mf_type,
+                format_vector(&params)
+            )
+            .unwrap();
+        }
+        writeln!(out).unwrap();
+    }
+    Ok(())
+}
+
+fn matlab_index(relation: &Relation) -> Result<String, FisError> {
+    let index = match relation {
+        Relation::Term(term) => *term as i32 + 1,
+        Relation::Not(term) => -(*term as i32) - 1,
+        Relation::Any => 0,
+        Relation::Hedged(hedge, _) => return unsupported("hedge", hedge.get_keyword()),
+    };
+    Ok(index.to_string())
+}
+
+// `consequent` gives the MATLAB indices of the outputs of a rule
+fn write_rules<R: AsRef<Rule>>(
+    out: &mut String,
+    rules: &[R],
+    input_size: usize,
+    consequent: impl Fn(&R) -> Result<Vec<String>, FisError>,
+) -> Result<(), FisError> {
+    writeln!(out, "[Rules]").unwrap();
+    for (rule, outputs) in rules.iter().map(|rule| (rule.as_ref(), consequent(rule))) {
+        let outputs = outputs?;
+        if let Some(antecedent) = rule.get_antecedent() {
+            return unsupported("rule antecedent", &format!("{:?}", antecedent));
+        }
+        let inputs = rule
+            .get_input_rules(input_size)
+            .iter()
+            .map(matlab_index)
+            .collect::<Result<Vec<String>, FisError>>()?;
+        let connection = match rule.get_kind() {
+            rules::Kind::AND => 1,
+            rules::Kind::OR => 2,
+        };
+        writeln!(
+            out,
+            "{}, {} ({}) : {}",
+            inputs.join(" "),
+            outputs.join(" "),
+            rule.get_weight(),
+            connection
+        )
+        .unwrap();
+    }
+    Ok(())
+}
+
+pub fn write_mamdani(fis: &MamdaniFIS, name: &str) -> Result<String, FisError> {
+    let implication = match fis.get_implication_method() {
+        Implications::Min => "min",
+        Implications::Product => "prod",
+        Implications::Custom(_) => return unsupported("implication", "Custom"),
+        implication => return unsupported("implication", &format!("{:?}", implication)),
+    };
+    let aggregation = match fis.get_aggregation_method() {
+        Aggregations::Max => "max",
+        Aggregations::Sum => "sum",
+        Aggregations::ProbabilisticOr => "probor",
+        Aggregations::Custom(_) => return unsupported("aggregation", "Custom"),
+        aggregation => return unsupported("aggregation", &format!("{:?}", aggregation)),
+    };
+    let defuzzifier = match fis.get_defuzzifier_method() {
+        Defuzzifiers::Centroid => "centroid",
+        Defuzzifiers::Bisection => "bisector",
+        Defuzzifiers::MeanOfMaximum => "mom",
+        Defuzzifiers::SmallestOfMaximum => "som",
+        Defuzzifiers::LargestOfMaximum => "lom",
+        Defuzzifiers::Custom(_) => return unsupported("defuzzifier", "Custom"),
+        defuzzifier => return unsupported("defuzzifier", &format!("{:?}", defuzzifier)),
+    };
+
+    check_complement(fis.get_complement_method())?;
+    if fis.get_reasoning_method() != Reasoning::Conjunctive {
+        return unsupported("reasoning", &format!("{:?}", fis.get_reasoning_method()));
+    }
+
+    let mut out = String::new();
+    let inputs = fis.get_inputs();
+    let rules = fis.get_rule_base();
+    write_system(
+        &mut out,
+        name,
+        "mamdani",
+        (inputs.len(), fis.get_outputs().len(), rules.len()),
+        [
+            t_norm_name(fis.get_t_norm_method())?,
+            s_norm_name(fis.get_s_norm_method())?,
+            implication,
+            aggregation,
+            defuzzifier,
+        ],
+    );
+    write_inputs(&mut out, inputs)?;
+    for (i, output) in fis.get_outputs().iter().enumerate() {
+        let (start, stop) = output.get_range();
+        let mrs = output.get_memberships();
+        writeln!(out, "[Output{}]", i + 1).unwrap();
+        writeln!(out, "Name='{}'", output.get_name()).unwrap();
+        writeln!(out, "Range={}", format_vector(&[*start, *stop])).unwrap();
+        writeln!(out, "NumMFs={}", mrs.len()).unwrap();
+        for (ii, mr) in mrs.iter().enumerate() {
+            let (mf_type, params) = match mr.get_kind() {
+                Some(kind) => mf_type(kind)?,
+                None => return unsupported("sampled membership range", &mr.get_name()),
+            };
+            writeln!(
+                out,
+                "MF{}='{}':'{}',{}",
+                ii + 1,
+                mr.get_name(),
+                mf_type,
+                format_vector(&params)
+            )
+            .unwrap();
+        }
+        writeln!(out).unwrap();
+    }
+    write_rules(&mut out, rules, inputs.len(), |rule| {
+        rule.get_output_rules(inputs.len())
+            .iter()
+            .map(matlab_index)
+            .collect()
+    })?;
+    Ok(out)
+}
+
+pub fn write_tsk(fis: &TSKFIS, name: &str) -> Result<String, FisError> {
+    let defuzzification = tsk_defuzzification_name(fis.get_defuzzification_method())?;
+    // .fis files have one DefuzzMethod for every output
+    for output in fis.get_outputs() {
+        if let Some(method) = output.get_defuzzification_method() {
+            if tsk_defuzzification_name(method)? != defuzzification {
+                return unsupported("output defuzzifier", &format!("{:?}", method));
+            }
+        }
+    }
+
+    check_complement(fis.get_complement_method())?;
+
+    let mut out = String::new();
+    let inputs = fis.get_inputs();
+    let rules = fis.get_rule_base();
+    write_system(
+        &mut out,
+        name,
+        "sugeno",
+        (inputs.len(), fis.get_outputs().len(), rules.len()),
+        [
+            t_norm_name(fis.get_t_norm_method())?,
+            s_norm_name(fis.get_s_norm_method())?,
+            "prod",
+            "sum",
+            defuzzification,
+        ],
+    );
+    write_inputs(&mut out, inputs)?;
+    for (i, output) in fis.get_outputs().iter().enumerate() {
+        let mfs = output.get_memberships();
+        writeln!(out, "[Output{}]", i + 1).unwrap();
+        writeln!(out, "Name='{}'", output.get_name()).unwrap();
+        // TSK outputs have no range, MATLAB only uses it for plotting
+        writeln!(out, "Range=[0 1]").unwrap();
+        writeln!(out, "NumMFs={}", mfs.len()).unwrap();
+        for (ii, mf) in mfs.iter().enumerate() {
+            let (mf_type, params) = match mf {
+                TSKMembershipFunction::Constant(c) => ("constant", vec![*c]),
+                TSKMembershipFunction::Linear(coefficients) => {
+                    let mut params = coefficients.clone();
+                    params.resize(inputs.len(), 0.0);
+                    params.push(0.0);
+                    ("linear", params)
+                }
+                TSKMembershipFunction::Affine(coefficients) => ("linear", coefficients.clone()),
+                TSKMembershipFunction::Quadratic(_) => {
+                    return unsupported("output membership function", "Quadratic")
+                }
+                TSKMembershipFunction::Polynomial(_) => {
+                    return unsupported("output membership function", "Polynomial")
+                }
+                TSKMembershipFunction::Custom(_) => {
+                    return unsupported("output membership function", "Custom")
+                }
+            };
+            writeln!(
+                out,
+                "MF{}='{}':'{}',{}",
+                ii + 1,
+                output.membership_function_name(ii as i32),
+                mf_type,
+                format_vector(&params)
+            )
+            .unwrap();
+        }
+        writeln!(out).unwrap();
+    }
+    write_rules(&mut out, rules, inputs.len(), |rule| {
+        rule.get_output_rules()
+            .iter()
+            .map(|relation| match relation {
+                OutputRelation::Term(relation) => matlab_index(relation),
+                // .fis rules can only point to the terms of the outputs
+                _ => unsupported("TSK consequent", &format!("{:?}", relation)),
+            })
+            .collect()
+    })?;
+    Ok(out)
+}
+
+pub fn save_mamdani<P: AsRef<Path>>(fis: &MamdaniFIS, name: &str, path: P) -> Result<(), FisError> {
+    fs::write(path, write_mamdani(fis, name)?)?;
+    Ok(())
+}
+
+pub fn save_tsk<P: AsRef<Path>>(fis: &TSKFIS, name: &str, path: P) -> Result<(), FisError> {
+    fs::write(path, write_tsk(fis, name)?)?;
+    Ok(())
+}
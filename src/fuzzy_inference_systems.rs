@@ -1,18 +1,176 @@
 use crate::aggregations::Aggregations;
-use crate::defuzzifications::{Defuzzifiers, TSKDefuzzifiers};
-use crate::implications::Implications;
-use crate::rules::{self, Rule};
+use crate::complements::Complements;
+use crate::defuzzifications::{self, Defuzzifiers, Fallbacks, TSKDefuzzifiers};
+use crate::error::FuzzyError;
+use crate::implications::{Implications, Reasoning};
+use crate::membership_functions::GetDegree;
+use crate::rules::{OutputRelation, Relation, Rule, RuleParseError, TSKRule};
 use crate::s_norms::SNorms;
 use crate::t_norms::TNorms;
-use crate::variables::{InputVariable, OutputVariable, TSKOutputVariable};
+use crate::trace::{InferenceTrace, InputTrace, OutputStage, OutputTrace, RuleTrace};
+use crate::type_reductions::TypeReducers;
+use crate::validation::{self, CheckedRule, Consequents, Diagnostic};
+use crate::variables::{
+    InputVariable, IntervalType2InputVariable, IntervalType2OutputVariable, NamedTerms,
+    NoActivationPolicies, OutputModes, OutputVariable, TSKOutputVariable, TsukamotoOutputVariable,
+};
+
+// The crisp outputs of one evaluation, an output is a fallback when it does not come from
+// the rules (no rule fired, or its set was empty).
+#[derive(Debug, Clone, PartialEq)]
+pub struct InferenceResult {
+    outputs: Vec<f64>,
+    fallbacks: Vec<bool>,
+}
+
+impl InferenceResult {
+    pub fn get_outputs(&self) -> &Vec<f64> {
+        &self.outputs
+    }
+
+    pub fn into_outputs(self) -> Vec<f64> {
+        self.outputs
+    }
+
+    pub fn is_fallback(&self, idx: usize) -> bool {
+        self.fallbacks[idx]
+    }
+
+    pub fn used_fallback(&self) -> bool {
+        self.fallbacks.iter().any(|fallback| *fallback)
+    }
+}
+
+// outputs that get nothing from the rules (None) follow their no activation policy, `last`
+// are the outputs of the previous evaluation for HoldLast
+fn no_activation(
+    outputs: Vec<Option<f64>>,
+    policies: impl Iterator<Item = (NoActivationPolicies, f64)>,
+    last: &[f64],
+) -> Result<InferenceResult, FuzzyError> {
+    let fallbacks: Vec<bool> = outputs.iter().map(Option::is_none).collect();
+    let outputs = outputs
+        .into_iter()
+        .zip(policies)
+        .enumerate()
+        .map(|(i, (output, (policy, middle)))| match output {
+            Some(output) => Ok(output),
+            None => policy.resolve(middle, last.get(i).copied()),
+        })
+        .collect::<Result<Vec<f64>, FuzzyError>>()?;
+    Ok(InferenceResult { outputs, fallbacks })
+}
+
+fn check_system<I: NamedTerms, O: NamedTerms, R: CheckedRule>(
+    inputs: &[I],
+    outputs: &[O],
+    rules: &[R],
+    input_vec: &[f64],
+) -> Result<(), FuzzyError> {
+    if inputs.is_empty() {
+        return Err(FuzzyError::NoInputs);
+    }
+    if outputs.is_empty() {
+        return Err(FuzzyError::NoOutputs);
+    }
+    if rules.is_empty() {
+        return Err(FuzzyError::EmptyRuleBase);
+    }
+    if input_vec.len() != inputs.len() {
+        return Err(FuzzyError::InputLength {
+            expected: inputs.len(),
+            found: input_vec.len(),
+        });
+    }
+    for (i, rule) in rules.iter().enumerate() {
+        rule.check_rule(i, inputs, outputs)?;
+    }
+    Ok(())
+}
+
+// the first steps are the same for every type-1 system
+fn fuzzification<R: AsRef<Rule>>(
+    rules: &[R],
+    inputs: &[InputVariable],
+    complement: &Complements,
+    input_vec: &[f64],
+) -> Vec<Vec<f64>> {
+    let mut fuzzified: Vec<Vec<f64>> = Vec::new();
+    for rule in rules.iter().map(AsRef::as_ref) {
+        let mut temp_vec: Vec<f64> = Vec::new();
+        // don't care inputs are skipped, except in expressions where they are 1.0
+        for (ii, relation) in rule.get_conditions(inputs.len()) {
+            match relation.get_term() {
+                Some(index) => {
+                    let fuzzed: f64 = inputs[ii].fuzzify(index, input_vec[ii]);
+                    temp_vec.push(relation.degree(fuzzed, complement));
+                }
+                None if rule.get_antecedent().is_some() => temp_vec.push(1.0),
+                None => (),
+            }
+        }
+        fuzzified.push(temp_vec);
+    }
+    fuzzified
+}
+
+fn connect_inputs<R: AsRef<Rule>>(
+    rules: &[R],
+    s_norm: &SNorms,
+    t_norm: &TNorms,
+    complement: &Complements,
+    fuzzified: Vec<Vec<f64>>,
+) -> Vec<f64> {
+    fuzzified
+        .into_iter()
+        .zip(rules.iter().map(AsRef::as_ref))
+        .map(|(fuzz, rule)| match rule.get_antecedent() {
+            Some(antecedent) => {
+                antecedent.evaluate(&mut fuzz.into_iter(), s_norm, t_norm, complement)
+            }
+            None => rule.get_kind().connect(s_norm, t_norm, &fuzz),
+        })
+        .collect()
+}
+
+fn weighed_inputs<R: AsRef<Rule>>(rules: &[R], connected_inputs: Vec<f64>) -> Vec<f64> {
+    connected_inputs
+        .into_iter()
+        .zip(rules)
+        .map(|(mu, rule)| rule.as_ref().get_weight() * mu)
+        .collect()
+}
+
+fn trace(
+    inputs: &[InputVariable],
+    input_vec: &[f64],
+    texts: impl Iterator<Item = String>,
+    connected_inputs: Vec<f64>,
+    weighted_inputs: Vec<f64>,
+    outputs: Vec<OutputTrace>,
+) -> InferenceTrace {
+    let inputs = inputs
+        .iter()
+        .zip(input_vec)
+        .map(|(input, x)| InputTrace::new(input, *x))
+        .collect();
+    let rules = texts
+        .zip(connected_inputs.into_iter().zip(weighted_inputs))
+        .map(|(text, (firing, weighted))| RuleTrace::new(text, firing, weighted))
+        .collect();
+    InferenceTrace::new(inputs, rules, outputs)
+}
 
 #[derive(Debug)]
 pub struct MamdaniFuzzyInferenceSystem {
     s_norm: SNorms,
     t_norm: TNorms,
+    complement: Complements,
     implication: Implications,
     aggregation: Aggregations,
+    reasoning: Reasoning,
     defuzzifier: Defuzzifiers,
+    fallback: Fallbacks,
     rules: Vec<Rule>,
     inputs: Vec<InputVariable>,
     outputs: Vec<OutputVariable>,
@@ -31,15 +189,34 @@ impl MamdaniFuzzyInferenceSystem {
         Self {
             s_norm,
             t_norm,
+            complement: Complements::Standard,
             implication,
             aggregation,
+            reasoning: Reasoning::Conjunctive,
             defuzzifier,
+            fallback: Fallbacks::MidRange,
             rules: Vec::new(),
             inputs: Vec::new(),
             outputs: Vec::new(),
         }
     }
 
+    // OR rules use the dual of `t_norm`
+    pub fn with_t_norm(
+        t_norm: TNorms,
+        implication: Implications,
+        aggregation: Aggregations,
+        defuzzifier: Defuzzifiers,
+    ) -> Self {
+        Self::new(
+            SNorms::dual_of(&t_norm),
+            t_norm,
+            implication,
+            aggregation,
+            defuzzifier,
+        )
+    }
+
     pub fn new_all(
         s_norm: SNorms,
         t_norm: TNorms,
@@ -53,9 +230,12 @@ impl MamdaniFuzzyInferenceSystem {
         Self {
             s_norm,
             t_norm,
+            complement: Complements::Standard,
             implication,
             aggregation,
+            reasoning: Reasoning::Conjunctive,
             defuzzifier,
+            fallback: Fallbacks::MidRange,
             rules,
             inputs,
             outputs,
@@ -74,6 +254,20 @@ impl MamdaniFuzzyInferenceSystem {
         self.rules.push(rule);
     }
 
+    pub fn parse_rule(&self, text: &str) -> Result<Rule, RuleParseError> {
+        Rule::parse(text, &self.inputs, &self.outputs)
+    }
+
+    pub fn add_text_rule(&mut self, text: &str) -> Result<(), RuleParseError> {
+        let rule = self.parse_rule(text)?;
+        self.rules.push(rule);
+        Ok(())
+    }
+
+    pub fn rule_to_text(&self, rule_index: usize) -> String {
+        self.rules[rule_index].to_text(&self.inputs, &self.outputs)
+    }
+
     pub fn get_s_norm(&self, fuzzified: &[f64]) -> f64 {
         self.s_norm.s_norm(fuzzified)
     }
@@ -82,125 +276,452 @@ impl MamdaniFuzzyInferenceSystem {
         self.t_norm.t_norm(fuzzified)
     }
 
-    pub fn get_rules(&self, rule_index: usize) -> &[i32] {
+    pub fn get_rules(&self, rule_index: usize) -> &[Relation] {
         self.rules[rule_index].get_rules()
     }
 
-    pub fn get_input_rules(&self, rule_index: usize) -> &[i32] {
+    pub fn get_input_rules(&self, rule_index: usize) -> &[Relation] {
         self.rules[rule_index].get_input_rules(self.inputs.len())
     }
 
-    pub fn get_output_rules(&self, rule_index: usize) -> &[i32] {
+    pub fn get_output_rules(&self, rule_index: usize) -> &[Relation] {
         self.rules[rule_index].get_output_rules(self.inputs.len())
     }
 
+    pub fn get_inputs(&self) -> &Vec<InputVariable> {
+        &self.inputs
+    }
+
+    pub fn get_outputs(&self) -> &Vec<OutputVariable> {
+        &self.outputs
+    }
+
+    pub fn get_rule_base(&self) -> &Vec<Rule> {
+        &self.rules
+    }
+
+    pub fn get_s_norm_method(&self) -> &SNorms {
+        &self.s_norm
+    }
+
+    pub fn get_t_norm_method(&self) -> &TNorms {
+        &self.t_norm
+    }
+
+    pub fn get_complement_method(&self) -> &Complements {
+        &self.complement
+    }
+
+    // used for every negated term of the rules
+    pub fn set_complement(&mut self, complement: Complements) {
+        self.complement = complement;
+    }
+
+    pub fn get_implication_method(&self) -> &Implications {
+        &self.implication
+    }
+
+    pub fn get_aggregation_method(&self) -> &Aggregations {
+        &self.aggregation
+    }
+
+    pub fn get_reasoning_method(&self) -> Reasoning {
+        self.reasoning
+    }
+
+    // implicative reasoning intersects the implied sets instead of using the aggregation method
+    pub fn set_reasoning(&mut self, reasoning: Reasoning) {
+        self.reasoning = reasoning;
+    }
+
+    pub fn get_defuzzifier_method(&self) -> &Defuzzifiers {
+        &self.defuzzifier
+    }
+
+    pub fn get_fallback_method(&self) -> &Fallbacks {
+        &self.fallback
+    }
+
+    // used when the set of an output is empty
+    pub fn set_fallback(&mut self, fallback: Fallbacks) {
+        self.fallback = fallback;
+    }
+
     pub fn fuzzification(&self, input_vec: Vec<f64>) -> Vec<Vec<f64>> {
-        let mut fuzzified: Vec<Vec<f64>> = Vec::new();
-        for i in 0..self.rules.len() {
-            let input_rule: &[i32] = self.get_input_rules(i);
-            let mut temp_vec: Vec<f64> = Vec::new();
-            for ii in 0..self.inputs.len() {
-                let index;
-                let complement;
-                if input_rule[ii] < 0 {
-                    index = (-input_rule[ii]) as usize;
-                    complement = true;
-                } else {
-                    index = input_rule[ii] as usize;
-                    complement = false;
-                }
-                let fuzzed: f64 = self.inputs[ii].fuzzify(index, input_vec[ii]);
-                temp_vec.push(match complement {
-                    true => 1.0 - fuzzed,
-                    false => fuzzed,
-                });
-                //temp_vec.push(self.inputs[ii].fuzzify(input_rule[ii] as usize, input_vec[ii]));
-            }
-            fuzzified.push(temp_vec);
-        }
-        fuzzified
+        fuzzification(&self.rules, &self.inputs, &self.complement, &input_vec)
     }
 
     pub fn connect_inputs(&self, fuzzified: Vec<Vec<f64>>) -> Vec<f64> {
-        fuzzified
-            .into_iter()
-            .zip(&self.rules)
-            .map(|(fuzz, rule)| match rule.get_kind() {
-                rules::Kind::OR => self.get_s_norm(&fuzz),
-                rules::Kind::AND => self.get_t_norm(&fuzz),
+        connect_inputs(
+            &self.rules,
+            &self.s_norm,
+            &self.t_norm,
+            &self.complement,
+            fuzzified,
+        )
+    }
+
+    pub fn weighed_inputs(&self, connected_inputs: Vec<f64>) -> Vec<f64> {
+        weighed_inputs(&self.rules, connected_inputs)
+    }
+
+    // the output set of every rule for every output, before the implication, rules that do
+    // not care about an output have an empty set
+    pub fn consequents(&self) -> Vec<Vec<Vec<f64>>> {
+        (0..self.outputs.len())
+            .map(|i| self.output_consequents(i))
+            .collect()
+    }
+
+    fn output_consequents(&self, output_index: usize) -> Vec<Vec<f64>> {
+        (0..self.rules.len())
+            .map(|ii| {
+                let relation = &self.get_output_rules(ii)[output_index];
+                match relation.get_term() {
+                    Some(index) => self.outputs[output_index]
+                        .get_mu(index)
+                        .iter()
+                        .map(|e| relation.degree(*e, &self.complement))
+                        .collect(),
+                    None => Vec::new(),
+                }
             })
             .collect()
     }
 
-    pub fn weighed_inputs(&self, connected_inputs: Vec<f64>) -> Vec<f64> {
-        connected_inputs
+    pub fn implication(&self, connected_inputs: Vec<f64>) -> Vec<Vec<Vec<f64>>> {
+        (0..self.outputs.len())
+            .map(|i| self.implied_sets(i, &connected_inputs))
+            .collect()
+    }
+
+    // the sets of every rule for one output, rules that do not care about it are left out
+    fn implied_sets(&self, output_index: usize, connected_inputs: &[f64]) -> Vec<Vec<f64>> {
+        self.implied_rule_sets(output_index, connected_inputs)
             .into_iter()
-            .zip(&self.rules)
-            .map(|(mu, rule)| rule.get_weight() * mu)
+            .flatten()
             .collect()
     }
 
-    pub fn implication(&self, connected_inputs: Vec<f64>) -> Vec<Vec<Vec<f64>>> {
-        let mut implication_vec = Vec::new();
-        for i in 0..self.outputs.len() {
-            let mut temp_vec = Vec::new();
-            for ii in 0..self.rules.len() {
-                let output_rule = self.get_output_rules(ii);
-                let index;
-                let complement;
-                if output_rule[i] < 0 {
-                    index = (-output_rule[i]) as usize;
-                    complement = true;
-                } else {
-                    index = output_rule[i] as usize;
-                    complement = false;
-                }
-                let range: Vec<f64> = self.outputs[i]
-                    .get_mu(index)
-                    .into_iter()
-                    .map(|e| if complement { 1.0 - e } else { e.to_owned() })
-                    .collect();
+    // None for the rules that do not care about the output
+    fn implied_rule_sets(
+        &self,
+        output_index: usize,
+        connected_inputs: &[f64],
+    ) -> Vec<Option<Vec<f64>>> {
+        self.output_consequents(output_index)
+            .iter()
+            .zip(connected_inputs)
+            .map(|(range, mu)| {
+                (!range.is_empty()).then(|| self.implication.implication(*mu, range))
+            })
+            .collect()
+    }
 
-                temp_vec.push(self.implication.implication(connected_inputs[ii], &range));
-            }
-            implication_vec.push(temp_vec);
+    fn aggregation_method(&self) -> &Aggregations {
+        match self.reasoning {
+            Reasoning::Conjunctive => &self.aggregation,
+            Reasoning::Implicative => &Aggregations::Min,
         }
-        implication_vec
     }
 
     pub fn aggregation(&self, implication_vec: Vec<Vec<Vec<f64>>>) -> Vec<Vec<f64>> {
         implication_vec
             .into_iter()
-            .map(|vec| self.aggregation.aggregation(&vec))
+            .map(|vec| self.aggregation_method().aggregation(&vec))
             .collect()
     }
 
     pub fn defuzzification(&self, aggregation_vec: Vec<Vec<f64>>) -> Vec<f64> {
-        aggregation_vec
+        self.try_defuzzification(aggregation_vec)
+            .unwrap_or_else(|err| panic!("{}", err))
+    }
+
+    // outputs with an empty set follow their no activation policy
+    pub fn try_defuzzification(
+        &self,
+        aggregation_vec: Vec<Vec<f64>>,
+    ) -> Result<Vec<f64>, FuzzyError> {
+        let outputs = aggregation_vec
             .into_iter()
             .enumerate()
-            .map(|(index, aggregated)| {
-                self.defuzzifier
-                    .defuzzify(aggregated, self.outputs[index].get_universe())
-            })
-            .collect()
+            .map(|(index, aggregated)| self.defuzzify_set(index, aggregated))
+            .collect();
+        self.no_activation(outputs, &[])
+            .map(InferenceResult::into_outputs)
+    }
+
+    // used instead of implication, aggregation and defuzzification by the rule based defuzzifiers
+    pub fn rule_defuzzification(&self, weighted_inputs: Vec<f64>) -> Vec<f64> {
+        self.try_rule_defuzzification(weighted_inputs)
+            .unwrap_or_else(|err| panic!("{}", err))
+    }
+
+    pub fn try_rule_defuzzification(
+        &self,
+        weighted_inputs: Vec<f64>,
+    ) -> Result<Vec<f64>, FuzzyError> {
+        let outputs = (0..self.outputs.len())
+            .map(|index| self.defuzzify_rule_sets(index, &weighted_inputs))
+            .collect();
+        self.no_activation(outputs, &[])
+            .map(InferenceResult::into_outputs)
+    }
+
+    // None when the set is empty
+    fn defuzzify_set(&self, output_index: usize, aggregated: Vec<f64>) -> Option<f64> {
+        if aggregated.iter().all(|mu| *mu == 0.0) {
+            None
+        } else {
+            let universe = self.outputs[output_index].get_universe();
+            Some(self.defuzzifier.defuzzify(aggregated, universe))
+        }
+    }
+
+    fn defuzzify_rule_sets(&self, output_index: usize, weighted_inputs: &[f64]) -> Option<f64> {
+        let implied = self.implied_sets(output_index, weighted_inputs);
+        if implied.iter().flatten().all(|mu| *mu == 0.0) {
+            None
+        } else {
+            Some(self.defuzzifier.defuzzify_rules(
+                weighted_inputs,
+                &self.output_consequents(output_index),
+                &implied,
+                self.outputs[output_index].get_universe(),
+            ))
+        }
+    }
+
+    // the policy of an output when the rules give it nothing, its own or the fallback of
+    // the system
+    fn no_activation_policy(&self, output_index: usize) -> NoActivationPolicies {
+        match self.outputs[output_index].get_no_activation_policy() {
+            Some(policy) => policy.clone(),
+            None => NoActivationPolicies::from(&self.fallback),
+        }
+    }
+
+    fn no_activation(
+        &self,
+        outputs: Vec<Option<f64>>,
+        last: &[f64],
+    ) -> Result<InferenceResult, FuzzyError> {
+        no_activation(
+            outputs,
+            self.outputs.iter().enumerate().map(|(index, output)| {
+                let (start, stop) = output.get_range();
+                (self.no_activation_policy(index), (start + stop) / 2.0)
+            }),
+            last,
+        )
+    }
+
+    fn sampled_output(&self, output_index: usize, weighted_inputs: &[f64]) -> Option<f64> {
+        if self.defuzzifier.is_rule_based() {
+            return self.defuzzify_rule_sets(output_index, weighted_inputs);
+        }
+        // 2. implication
+        let implied = self.implied_sets(output_index, weighted_inputs);
+        // 3. aggregation
+        let aggregated = self.aggregation_method().aggregation(&implied);
+        // 4. defuzzification
+        self.defuzzify_set(output_index, aggregated)
+    }
+
+    // Continuous outputs use the shapes of their terms instead of the sampled ranges, the
+    // aggregated set is integrated between the parameters of the shapes. None when the set
+    // is empty.
+    fn continuous_output(
+        &self,
+        output_index: usize,
+        weighted_inputs: &[f64],
+    ) -> Result<Option<f64>, FuzzyError> {
+        let output = &self.outputs[output_index];
+        let mut consequents = Vec::new();
+        for (rule, mu) in self.rules.iter().zip(weighted_inputs) {
+            let relation = &rule.get_output_rules(self.inputs.len())[output_index];
+            let index = match relation.get_term() {
+                Some(index) => index,
+                None => continue,
+            };
+            let mr = &output.get_memberships()[index];
+            match mr.get_kind() {
+                Some(kind) => consequents.push((kind, relation, *mu)),
+                None => {
+                    return Err(FuzzyError::MissingShape {
+                        variable: output.get_name().clone(),
+                        term: mr.get_name(),
+                    })
+                }
+            }
+        }
+        if consequents.is_empty() {
+            return Ok(None);
+        }
+        let aggregation = self.aggregation_method();
+        let degree = |x: f64| {
+            let implied: Vec<Vec<f64>> = consequents
+                .iter()
+                .map(|(kind, relation, mu)| {
+                    let degree = relation.degree(kind.get_degree(x), &self.complement);
+                    self.implication.implication(*mu, &vec![degree])
+                })
+                .collect();
+            aggregation.aggregation(&implied)[0]
+        };
+        let (start, stop) = *output.get_range();
+        let mut points = vec![start, stop];
+        for (kind, _, _) in &consequents {
+            points.extend(
+                kind.get_parameters()
+                    .into_iter()
+                    .filter(|x| *x > start && *x < stop),
+            );
+        }
+        points.sort_by(|a, b| a.partial_cmp(b).unwrap());
+        points.dedup();
+
+        if defuzzifications::integrate(&degree, &points) <= 0.0 {
+            return Ok(None);
+        }
+        let crisp = match self.defuzzifier {
+            Defuzzifiers::Bisection => {
+                defuzzifications::continuous_bisection_defuzzification(&degree, &points)
+            }
+            _ => defuzzifications::continuous_centroid_defuzzification(&degree, &points),
+        };
+        Ok(Some(crisp))
+    }
+
+    fn is_continuous(&self, output_index: usize) -> bool {
+        self.outputs[output_index].get_mode() == OutputModes::Continuous
+    }
+
+    pub fn validate(&self) -> Vec<Diagnostic> {
+        let mut diagnostics = validation::validate_system(
+            &self.inputs,
+            &self.outputs,
+            &self.rules,
+            Consequents::Sets,
+        );
+        diagnostics.extend(validation::validate_output_ranges(&self.outputs));
+        if let Err(err) = validation::check_output_modes(&self.outputs, &self.defuzzifier) {
+            diagnostics.push(Diagnostic::Error(err));
+        }
+        if let Err(err) = validation::check_reasoning(&self.defuzzifier, self.reasoning) {
+            diagnostics.push(Diagnostic::Error(err));
+        }
+        diagnostics
     }
 
     pub fn compute_outputs(&self, input_vec: Vec<f64>) -> Vec<f64> {
-        assert!(self.inputs.len() != 0, "You must add at least one INPUT");
-        assert!(self.outputs.len() != 0, "You must add at least one OUTPUT");
-        assert!(self.rules.len() != 0, "You must add at least one RULE");
+        self.try_compute_outputs(input_vec)
+            .unwrap_or_else(|err| panic!("{}", err))
+    }
+
+    pub fn try_compute_outputs(&self, input_vec: Vec<f64>) -> Result<Vec<f64>, FuzzyError> {
+        self.try_compute(input_vec)
+            .map(InferenceResult::into_outputs)
+    }
+
+    pub fn compute(&self, input_vec: Vec<f64>) -> InferenceResult {
+        self.try_compute(input_vec)
+            .unwrap_or_else(|err| panic!("{}", err))
+    }
+
+    pub fn try_compute(&self, input_vec: Vec<f64>) -> Result<InferenceResult, FuzzyError> {
+        self.try_compute_holding(input_vec, &[])
+    }
+
+    pub fn compute_holding(&self, input_vec: Vec<f64>, last: &[f64]) -> InferenceResult {
+        self.try_compute_holding(input_vec, last)
+            .unwrap_or_else(|err| panic!("{}", err))
+    }
+
+    // `last` are the outputs of the previous evaluation, kept by the caller, for the outputs
+    // that hold their last value when no rule fires
+    pub fn try_compute_holding(
+        &self,
+        input_vec: Vec<f64>,
+        last: &[f64],
+    ) -> Result<InferenceResult, FuzzyError> {
+        check_system(&self.inputs, &self.outputs, &self.rules, &input_vec)?;
+        validation::check_output_ranges(&self.outputs)?;
+        validation::check_output_modes(&self.outputs, &self.defuzzifier)?;
+        validation::check_reasoning(&self.defuzzifier, self.reasoning)?;
 
         // 1. fuzzification
         let fuzzified = self.fuzzification(input_vec);
         let connected_inputs = self.connect_inputs(fuzzified);
         let weighted_inputs = self.weighed_inputs(connected_inputs);
-        // 2. implication
-        let implication_vec = self.implication(weighted_inputs);
-        // 3. aggregation
-        let aggregation_vec = self.aggregation(implication_vec);
-        // 4. defuzzification
-        self.defuzzification(aggregation_vec)
+        let fired = weighted_inputs.iter().any(|mu| *mu != 0.0);
+        let mut outputs = Vec::new();
+        for index in 0..self.outputs.len() {
+            outputs.push(if !fired {
+                None
+            } else if self.is_continuous(index) {
+                self.continuous_output(index, &weighted_inputs)?
+            } else {
+                self.sampled_output(index, &weighted_inputs)
+            });
+        }
+        self.no_activation(outputs, last)
+    }
+
+    pub fn compute_outputs_traced(&self, input_vec: Vec<f64>) -> InferenceTrace {
+        self.try_compute_outputs_traced(input_vec)
+            .unwrap_or_else(|err| panic!("{}", err))
+    }
+
+    pub fn try_compute_outputs_traced(
+        &self,
+        input_vec: Vec<f64>,
+    ) -> Result<InferenceTrace, FuzzyError> {
+        self.try_compute_traced_holding(input_vec, &[])
+    }
+
+    pub fn compute_traced_holding(&self, input_vec: Vec<f64>, last: &[f64]) -> InferenceTrace {
+        self.try_compute_traced_holding(input_vec, last)
+            .unwrap_or_else(|err| panic!("{}", err))
+    }
+
+    // the same evaluation as try_compute_holding, with the stages in between kept
+    pub fn try_compute_traced_holding(
+        &self,
+        input_vec: Vec<f64>,
+        last: &[f64],
+    ) -> Result<InferenceTrace, FuzzyError> {
+        let result = self.try_compute_holding(input_vec.clone(), last)?;
+
+        let fuzzified = self.fuzzification(input_vec.clone());
+        let connected_inputs = self.connect_inputs(fuzzified);
+        let weighted_inputs = self.weighed_inputs(connected_inputs.clone());
+
+        let mut outputs = Vec::new();
+        for (index, output) in self.outputs.iter().enumerate() {
+            let implied = self.implied_rule_sets(index, &weighted_inputs);
+            let sets: Vec<Vec<f64>> = implied.iter().flatten().cloned().collect();
+            let stage = OutputStage::Sets {
+                universe: output.get_universe().to_vec(),
+                aggregated: self.aggregation_method().aggregation(&sets),
+                implied,
+                continuous: self.is_continuous(index),
+            };
+            outputs.push(OutputTrace::new(
+                output.get_name().clone(),
+                stage,
+                result.get_outputs()[index],
+                result.is_fallback(index),
+            ));
+        }
+        Ok(trace(
+            &self.inputs,
+            &input_vec,
+            (0..self.rules.len()).map(|i| self.rule_to_text(i)),
+            connected_inputs,
+            weighted_inputs,
+            outputs,
+        ))
     }
 }
 
@@ -208,8 +729,9 @@ impl MamdaniFuzzyInferenceSystem {
 pub struct TSKFuzzyInferenceSystem {
     s_norm: SNorms,
     t_norm: TNorms,
+    complement: Complements,
     defuzzification: TSKDefuzzifiers,
-    rules: Vec<Rule>,
+    rules: Vec<TSKRule>,
     inputs: Vec<InputVariable>,
     outputs: Vec<TSKOutputVariable>,
 }
@@ -221,6 +743,7 @@ impl TSKFuzzyInferenceSystem {
         Self {
             s_norm,
             t_norm,
+            complement: Complements::Standard,
             defuzzification,
             rules: Vec::new(),
             inputs: Vec::new(),
@@ -228,6 +751,10 @@ impl TSKFuzzyInferenceSystem {
         }
     }
 
+    pub fn with_t_norm(t_norm: TNorms, defuzzification: TSKDefuzzifiers) -> Self {
+        Self::new(SNorms::dual_of(&t_norm), t_norm, defuzzification)
+    }
+
     pub fn add_input(&mut self, input: InputVariable) {
         self.inputs.push(input);
     }
@@ -236,8 +763,28 @@ impl TSKFuzzyInferenceSystem {
         self.outputs.push(variable);
     }
 
+    // the inputs must be added first, the rest of the relations are terms of the outputs
     pub fn add_rule(&mut self, rule: Rule) {
+        self.rules.push(TSKRule::from_rule(rule, self.inputs.len()));
+    }
+
+    pub fn add_tsk_rule(&mut self, rule: TSKRule) {
+        self.rules.push(rule);
+    }
+
+    pub fn parse_rule(&self, text: &str) -> Result<TSKRule, RuleParseError> {
+        let rule = Rule::parse(text, &self.inputs, &self.outputs)?;
+        Ok(TSKRule::from_rule(rule, self.inputs.len()))
+    }
+
+    pub fn add_text_rule(&mut self, text: &str) -> Result<(), RuleParseError> {
+        let rule = self.parse_rule(text)?;
         self.rules.push(rule);
+        Ok(())
+    }
+
+    pub fn rule_to_text(&self, rule_index: usize) -> String {
+        self.rules[rule_index].to_text(&self.inputs, &self.outputs)
     }
 
     pub fn get_s_norm(&self, fuzzified: &[f64]) -> f64 {
@@ -248,94 +795,920 @@ impl TSKFuzzyInferenceSystem {
         self.t_norm.t_norm(fuzzified)
     }
 
-    pub fn get_rules(&self, rule_index: usize) -> &[i32] {
-        self.rules[rule_index].get_rules()
+    pub fn get_input_rules(&self, rule_index: usize) -> &[Relation] {
+        self.rules[rule_index].get_input_rules()
     }
 
-    pub fn get_input_rules(&self, rule_index: usize) -> &[i32] {
-        self.rules[rule_index].get_input_rules(self.inputs.len())
+    pub fn get_output_rules(&self, rule_index: usize) -> &[OutputRelation] {
+        self.rules[rule_index].get_output_rules()
     }
 
-    pub fn get_output_rules(&self, rule_index: usize) -> &[i32] {
-        self.rules[rule_index].get_output_rules(self.inputs.len())
+    pub fn get_inputs(&self) -> &Vec<InputVariable> {
+        &self.inputs
+    }
+
+    pub fn get_outputs(&self) -> &Vec<TSKOutputVariable> {
+        &self.outputs
+    }
+
+    pub fn get_rule_base(&self) -> &Vec<TSKRule> {
+        &self.rules
+    }
+
+    pub fn get_s_norm_method(&self) -> &SNorms {
+        &self.s_norm
+    }
+
+    pub fn get_t_norm_method(&self) -> &TNorms {
+        &self.t_norm
+    }
+
+    pub fn get_complement_method(&self) -> &Complements {
+        &self.complement
+    }
+
+    // used for every negated term of the rules
+    pub fn set_complement(&mut self, complement: Complements) {
+        self.complement = complement;
+    }
+
+    pub fn get_defuzzification_method(&self) -> &TSKDefuzzifiers {
+        &self.defuzzification
     }
 
     pub fn fuzzification(&self, input_vec: Vec<f64>) -> Vec<Vec<f64>> {
-        let mut fuzzified: Vec<Vec<f64>> = Vec::new();
-        for i in 0..self.rules.len() {
-            let input_rule = self.get_input_rules(i);
-            let mut temp_vec: Vec<f64> = Vec::new();
-            for ii in 0..self.inputs.len() {
-                let index;
-                let complement;
-                if input_rule[ii] < 0 {
-                    index = (-input_rule[ii]) as usize;
-                    complement = true;
-                } else {
-                    index = input_rule[ii] as usize;
-                    complement = false;
-                }
-                let fuzzed = self.inputs[ii].fuzzify(index, input_vec[ii]);
-                temp_vec.push(match complement {
-                    true => 1.0 - fuzzed,
-                    false => fuzzed,
-                });
-                //temp_vec.push(self.inputs[ii].fuzzify(input_rule[ii] as usize, input_vec[ii]));
-            }
-            fuzzified.push(temp_vec);
-        }
-        fuzzified
+        fuzzification(&self.rules, &self.inputs, &self.complement, &input_vec)
     }
 
     pub fn connect_inputs(&self, fuzzified: Vec<Vec<f64>>) -> Vec<f64> {
-        fuzzified
-            .into_iter()
-            .zip(&self.rules)
-            .map(|(fuzz, rule)| match rule.get_kind() {
-                rules::Kind::OR => self.get_s_norm(&fuzz),
-                rules::Kind::AND => self.get_t_norm(&fuzz),
-            })
-            .collect()
+        connect_inputs(
+            &self.rules,
+            &self.s_norm,
+            &self.t_norm,
+            &self.complement,
+            fuzzified,
+        )
     }
 
     pub fn weighed_inputs(&self, connected_inputs: Vec<f64>) -> Vec<f64> {
-        connected_inputs
-            .into_iter()
-            .zip(&self.rules)
-            .map(|(mu, rule)| rule.get_weight() * mu)
-            .collect()
+        weighed_inputs(&self.rules, connected_inputs)
     }
 
-    pub fn get_mu(&self, input_vec: &Vec<f64>) -> Vec<Vec<f64>> {
+    // None for the rules that don't care about the output
+    pub fn get_mu(&self, input_vec: &Vec<f64>) -> Vec<Vec<Option<f64>>> {
         let mut output = Vec::new();
         for i in 0..self.outputs.len() {
             let mut temp_vec = Vec::new();
-            for ii in 0..self.rules.len() {
-                let output_rule = self.rules[ii].get_output_rules(self.inputs.len());
-                temp_vec.push(
-                    self.outputs[i].get_mu(output_rule[i as usize].try_into().unwrap(), &input_vec),
-                )
+            for rule in &self.rules {
+                temp_vec.push(rule.get_output_rules()[i].get_mu(&self.outputs[i], input_vec))
             }
             output.push(temp_vec);
         }
         output
     }
 
+    pub fn validate(&self) -> Vec<Diagnostic> {
+        let mut diagnostics = validation::validate_system(
+            &self.inputs,
+            &self.outputs,
+            &self.rules,
+            Consequents::Functions,
+        );
+        if let Err(err) = validation::check_consequent_inputs(self.inputs.len(), &self.outputs) {
+            diagnostics.push(Diagnostic::Error(err));
+        }
+        diagnostics
+    }
+
     pub fn compute_outputs(&self, input: Vec<f64>) -> Vec<f64> {
-        let mut output = Vec::new();
+        self.try_compute_outputs(input)
+            .unwrap_or_else(|err| panic!("{}", err))
+    }
+
+    pub fn try_compute_outputs(&self, input: Vec<f64>) -> Result<Vec<f64>, FuzzyError> {
+        self.try_compute(input).map(InferenceResult::into_outputs)
+    }
+
+    pub fn compute(&self, input: Vec<f64>) -> InferenceResult {
+        self.try_compute(input)
+            .unwrap_or_else(|err| panic!("{}", err))
+    }
+
+    pub fn try_compute(&self, input: Vec<f64>) -> Result<InferenceResult, FuzzyError> {
+        self.try_compute_holding(input, &[])
+    }
+
+    pub fn compute_holding(&self, input: Vec<f64>, last: &[f64]) -> InferenceResult {
+        self.try_compute_holding(input, last)
+            .unwrap_or_else(|err| panic!("{}", err))
+    }
+
+    // `last` are the outputs of the previous evaluation, see MamdaniFIS::try_compute_holding
+    pub fn try_compute_holding(
+        &self,
+        input: Vec<f64>,
+        last: &[f64],
+    ) -> Result<InferenceResult, FuzzyError> {
+        check_system(&self.inputs, &self.outputs, &self.rules, &input)?;
+        validation::check_consequent_inputs(self.inputs.len(), &self.outputs)?;
+        for (i, rule) in self.rules.iter().enumerate() {
+            rule.check_consequents(i, self.inputs.len(), &self.outputs, Consequents::Functions)?;
+        }
 
         // 1 - fuzzification
         let fuzzified = self.fuzzification(input.clone());
         let connected_inputs = self.connect_inputs(fuzzified);
         let weighted_input = self.weighed_inputs(connected_inputs);
-        // weighted_inputs
 
         // 2 - implication
-        let mu_vec = self.get_mu(&input);
-        for i in 0..self.outputs.len() {
-            output.push(self.defuzzification.defuzzify(&mu_vec[i], &weighted_input));
-        }
+        // rules that don't care about an output are left out of it
+        let consequents: Vec<(Vec<f64>, Vec<f64>)> = self
+            .get_mu(&input)
+            .into_iter()
+            .map(|mu| {
+                mu.into_iter()
+                    .zip(&weighted_input)
+                    .filter_map(|(mu, weight)| Some((mu?, *weight)))
+                    .unzip()
+            })
+            .collect();
+        let output = consequents
+            .iter()
+            .zip(&self.outputs)
+            .map(|((mu, weights), variable)| {
+                let defuzzification = variable
+                    .get_defuzzification_method()
+                    .unwrap_or(&self.defuzzification);
+                let fired = weights.iter().any(|weight| *weight != 0.0);
+                fired.then(|| defuzzification.defuzzify(mu, weights))
+            })
+            .collect();
+        // the output is always between the smallest and the largest consequent, 0 if it has none
+        no_activation(
+            output,
+            self.outputs
+                .iter()
+                .zip(&consequents)
+                .map(|(variable, (mu, _))| {
+                    let smallest = mu.iter().cloned().fold(f64::INFINITY, f64::min);
+                    let largest = mu.iter().cloned().fold(f64::NEG_INFINITY, f64::max);
+                    (
+                        variable
+                            .get_no_activation_policy()
+                            .cloned()
+                            .unwrap_or_default(),
+                        match mu.is_empty() {
+                            true => 0.0,
+                            false => (smallest + largest) / 2.0,
+                        },
+                    )
+                }),
+            last,
+        )
+    }
 
-        output
+    pub fn compute_outputs_traced(&self, input: Vec<f64>) -> InferenceTrace {
+        self.try_compute_outputs_traced(input)
+            .unwrap_or_else(|err| panic!("{}", err))
+    }
+
+    pub fn try_compute_outputs_traced(
+        &self,
+        input: Vec<f64>,
+    ) -> Result<InferenceTrace, FuzzyError> {
+        self.try_compute_traced_holding(input, &[])
+    }
+
+    pub fn compute_traced_holding(&self, input: Vec<f64>, last: &[f64]) -> InferenceTrace {
+        self.try_compute_traced_holding(input, last)
+            .unwrap_or_else(|err| panic!("{}", err))
+    }
+
+    // the same evaluation as try_compute_holding, with the stages in between kept
+    pub fn try_compute_traced_holding(
+        &self,
+        input: Vec<f64>,
+        last: &[f64],
+    ) -> Result<InferenceTrace, FuzzyError> {
+        let result = self.try_compute_holding(input.clone(), last)?;
+
+        let fuzzified = self.fuzzification(input.clone());
+        let connected_inputs = self.connect_inputs(fuzzified);
+        let weighted_input = self.weighed_inputs(connected_inputs.clone());
+
+        let outputs = self
+            .outputs
+            .iter()
+            .zip(self.get_mu(&input))
+            .enumerate()
+            .map(|(index, (output, mu))| {
+                OutputTrace::new(
+                    output.get_name().clone(),
+                    OutputStage::Consequents(mu),
+                    result.get_outputs()[index],
+                    result.is_fallback(index),
+                )
+            })
+            .collect();
+        Ok(trace(
+            &self.inputs,
+            &input,
+            (0..self.rules.len()).map(|i| self.rule_to_text(i)),
+            connected_inputs,
+            weighted_input,
+            outputs,
+        ))
+    }
+}
+
+#[derive(Debug)]
+pub struct TsukamotoFuzzyInferenceSystem {
+    s_norm: SNorms,
+    t_norm: TNorms,
+    complement: Complements,
+    rules: Vec<Rule>,
+    inputs: Vec<InputVariable>,
+    outputs: Vec<TsukamotoOutputVariable>,
+}
+
+pub type TsukamotoFIS = TsukamotoFuzzyInferenceSystem;
+
+impl TsukamotoFuzzyInferenceSystem {
+    pub fn new(s_norm: SNorms, t_norm: TNorms) -> Self {
+        Self {
+            s_norm,
+            t_norm,
+            complement: Complements::Standard,
+            rules: Vec::new(),
+            inputs: Vec::new(),
+            outputs: Vec::new(),
+        }
+    }
+
+    pub fn with_t_norm(t_norm: TNorms) -> Self {
+        Self::new(SNorms::dual_of(&t_norm), t_norm)
+    }
+
+    pub fn add_input(&mut self, input: InputVariable) {
+        self.inputs.push(input);
+    }
+
+    pub fn add_output(&mut self, output: TsukamotoOutputVariable) {
+        self.outputs.push(output);
+    }
+
+    pub fn add_rule(&mut self, rule: Rule) {
+        self.rules.push(rule);
+    }
+
+    pub fn parse_rule(&self, text: &str) -> Result<Rule, RuleParseError> {
+        Rule::parse(text, &self.inputs, &self.outputs)
+    }
+
+    pub fn add_text_rule(&mut self, text: &str) -> Result<(), RuleParseError> {
+        let rule = self.parse_rule(text)?;
+        self.rules.push(rule);
+        Ok(())
+    }
+
+    pub fn rule_to_text(&self, rule_index: usize) -> String {
+        self.rules[rule_index].to_text(&self.inputs, &self.outputs)
+    }
+
+    pub fn get_inputs(&self) -> &Vec<InputVariable> {
+        &self.inputs
+    }
+
+    pub fn get_outputs(&self) -> &Vec<TsukamotoOutputVariable> {
+        &self.outputs
+    }
+
+    pub fn get_rule_base(&self) -> &Vec<Rule> {
+        &self.rules
+    }
+
+    pub fn get_s_norm_method(&self) -> &SNorms {
+        &self.s_norm
+    }
+
+    pub fn get_t_norm_method(&self) -> &TNorms {
+        &self.t_norm
+    }
+
+    pub fn get_complement_method(&self) -> &Complements {
+        &self.complement
+    }
+
+    // used for every negated term of the rules
+    pub fn set_complement(&mut self, complement: Complements) {
+        self.complement = complement;
+    }
+
+    pub fn fuzzification(&self, input_vec: Vec<f64>) -> Vec<Vec<f64>> {
+        fuzzification(&self.rules, &self.inputs, &self.complement, &input_vec)
+    }
+
+    pub fn connect_inputs(&self, fuzzified: Vec<Vec<f64>>) -> Vec<f64> {
+        connect_inputs(
+            &self.rules,
+            &self.s_norm,
+            &self.t_norm,
+            &self.complement,
+            fuzzified,
+        )
+    }
+
+    pub fn weighed_inputs(&self, connected_inputs: Vec<f64>) -> Vec<f64> {
+        weighed_inputs(&self.rules, connected_inputs)
+    }
+
+    // crisp output of every rule for every output, rules that do not fire give NaN
+    pub fn get_rule_outputs(&self, weighted_inputs: &[f64]) -> Vec<Vec<f64>> {
+        let mut output = Vec::new();
+        for (i, variable) in self.outputs.iter().enumerate() {
+            let mut temp_vec = Vec::new();
+            for (rule, mu) in self.rules.iter().zip(weighted_inputs) {
+                temp_vec.push(match &rule.get_output_rules(self.inputs.len())[i] {
+                    _ if *mu == 0.0 => f64::NAN,
+                    // complements are involutive, so they are their own inverse
+                    Relation::Not(index) => {
+                        variable.get_inverse(*index, self.complement.complement(*mu))
+                    }
+                    relation => variable.get_inverse(relation.get_term().unwrap(), *mu),
+                });
+            }
+            output.push(temp_vec);
+        }
+        output
+    }
+
+    pub fn validate(&self) -> Vec<Diagnostic> {
+        validation::validate_system(
+            &self.inputs,
+            &self.outputs,
+            &self.rules,
+            Consequents::Inverses,
+        )
+    }
+
+    pub fn compute_outputs(&self, input: Vec<f64>) -> Vec<f64> {
+        self.try_compute_outputs(input)
+            .unwrap_or_else(|err| panic!("{}", err))
+    }
+
+    pub fn try_compute_outputs(&self, input: Vec<f64>) -> Result<Vec<f64>, FuzzyError> {
+        self.try_compute(input).map(InferenceResult::into_outputs)
+    }
+
+    pub fn compute(&self, input: Vec<f64>) -> InferenceResult {
+        self.try_compute(input)
+            .unwrap_or_else(|err| panic!("{}", err))
+    }
+
+    pub fn try_compute(&self, input: Vec<f64>) -> Result<InferenceResult, FuzzyError> {
+        self.try_compute_holding(input, &[])
+    }
+
+    pub fn compute_holding(&self, input: Vec<f64>, last: &[f64]) -> InferenceResult {
+        self.try_compute_holding(input, last)
+            .unwrap_or_else(|err| panic!("{}", err))
+    }
+
+    // `last` are the outputs of the previous evaluation, see MamdaniFIS::try_compute_holding
+    pub fn try_compute_holding(
+        &self,
+        input: Vec<f64>,
+        last: &[f64],
+    ) -> Result<InferenceResult, FuzzyError> {
+        check_system(&self.inputs, &self.outputs, &self.rules, &input)?;
+        for (i, rule) in self.rules.iter().enumerate() {
+            validation::check_consequents(
+                i,
+                rule,
+                self.inputs.len(),
+                &self.outputs,
+                Consequents::Inverses,
+            )?;
+        }
+
+        // 1 - fuzzification
+        let fuzzified = self.fuzzification(input);
+        let connected_inputs = self.connect_inputs(fuzzified);
+        let weighted_input = self.weighed_inputs(connected_inputs);
+        let fired = weighted_input.iter().any(|mu| *mu != 0.0);
+
+        // 2 - weighted average of the rule outputs
+        let total: f64 = weighted_input.iter().sum();
+        let outputs = self
+            .get_rule_outputs(&weighted_input)
+            .into_iter()
+            .map(|values| {
+                fired.then(|| {
+                    values
+                        .into_iter()
+                        .zip(&weighted_input)
+                        .filter(|(_, mu)| **mu > 0.0)
+                        .map(|(value, mu)| value * mu)
+                        .sum::<f64>()
+                        / total
+                })
+            })
+            .collect();
+        no_activation(
+            outputs,
+            self.outputs.iter().map(|variable| {
+                let (start, stop) = variable.get_range();
+                (
+                    variable
+                        .get_no_activation_policy()
+                        .cloned()
+                        .unwrap_or_default(),
+                    (start + stop) / 2.0,
+                )
+            }),
+            last,
+        )
+    }
+}
+
+#[derive(Debug)]
+pub struct IntervalType2MamdaniFuzzyInferenceSystem {
+    s_norm: SNorms,
+    t_norm: TNorms,
+    complement: Complements,
+    implication: Implications,
+    aggregation: Aggregations,
+    type_reducer: TypeReducers,
+    rules: Vec<Rule>,
+    inputs: Vec<IntervalType2InputVariable>,
+    outputs: Vec<IntervalType2OutputVariable>,
+}
+
+pub type IntervalType2MamdaniFIS = IntervalType2MamdaniFuzzyInferenceSystem;
+
+impl IntervalType2MamdaniFuzzyInferenceSystem {
+    pub fn new(
+        s_norm: SNorms,
+        t_norm: TNorms,
+        implication: Implications,
+        aggregation: Aggregations,
+        type_reducer: TypeReducers,
+    ) -> Self {
+        Self {
+            s_norm,
+            t_norm,
+            complement: Complements::Standard,
+            implication,
+            aggregation,
+            type_reducer,
+            rules: Vec::new(),
+            inputs: Vec::new(),
+            outputs: Vec::new(),
+        }
+    }
+
+    pub fn with_t_norm(
+        t_norm: TNorms,
+        implication: Implications,
+        aggregation: Aggregations,
+        type_reducer: TypeReducers,
+    ) -> Self {
+        Self::new(
+            SNorms::dual_of(&t_norm),
+            t_norm,
+            implication,
+            aggregation,
+            type_reducer,
+        )
+    }
+
+    pub fn add_input(&mut self, input: IntervalType2InputVariable) {
+        self.inputs.push(input);
+    }
+
+    pub fn add_output(&mut self, output: IntervalType2OutputVariable) {
+        self.outputs.push(output);
+    }
+
+    pub fn add_rule(&mut self, rule: Rule) {
+        self.rules.push(rule);
+    }
+
+    pub fn parse_rule(&self, text: &str) -> Result<Rule, RuleParseError> {
+        Rule::parse(text, &self.inputs, &self.outputs)
+    }
+
+    pub fn add_text_rule(&mut self, text: &str) -> Result<(), RuleParseError> {
+        let rule = self.parse_rule(text)?;
+        self.rules.push(rule);
+        Ok(())
+    }
+
+    pub fn rule_to_text(&self, rule_index: usize) -> String {
+        self.rules[rule_index].to_text(&self.inputs, &self.outputs)
+    }
+
+    pub fn get_inputs(&self) -> &Vec<IntervalType2InputVariable> {
+        &self.inputs
+    }
+
+    pub fn get_outputs(&self) -> &Vec<IntervalType2OutputVariable> {
+        &self.outputs
+    }
+
+    pub fn get_rule_base(&self) -> &Vec<Rule> {
+        &self.rules
+    }
+
+    pub fn get_s_norm_method(&self) -> &SNorms {
+        &self.s_norm
+    }
+
+    pub fn get_t_norm_method(&self) -> &TNorms {
+        &self.t_norm
+    }
+
+    pub fn get_complement_method(&self) -> &Complements {
+        &self.complement
+    }
+
+    // used for every negated term of the rules
+    pub fn set_complement(&mut self, complement: Complements) {
+        self.complement = complement;
+    }
+
+    pub fn get_implication_method(&self) -> &Implications {
+        &self.implication
+    }
+
+    pub fn get_aggregation_method(&self) -> &Aggregations {
+        &self.aggregation
+    }
+
+    pub fn get_type_reducer_method(&self) -> &TypeReducers {
+        &self.type_reducer
+    }
+
+    // every degree is an interval (lower, upper)
+    pub fn fuzzification(&self, input_vec: Vec<f64>) -> Vec<Vec<(f64, f64)>> {
+        let mut fuzzified = Vec::new();
+        for rule in &self.rules {
+            let mut temp_vec = Vec::new();
+            for (ii, relation) in rule.get_conditions(self.inputs.len()) {
+                match relation.get_term() {
+                    Some(index) => {
+                        let interval = self.inputs[ii].fuzzify(index, input_vec[ii]);
+                        temp_vec.push(relation.interval(interval, &self.complement));
+                    }
+                    None if rule.get_antecedent().is_some() => temp_vec.push((1.0, 1.0)),
+                    None => (),
+                }
+            }
+            fuzzified.push(temp_vec);
+        }
+        fuzzified
+    }
+
+    pub fn connect_inputs(&self, fuzzified: Vec<Vec<(f64, f64)>>) -> Vec<(f64, f64)> {
+        fuzzified
+            .into_iter()
+            .zip(&self.rules)
+            .map(|(fuzz, rule)| {
+                if let Some(antecedent) = rule.get_antecedent() {
+                    return antecedent.evaluate_interval(
+                        &mut fuzz.into_iter(),
+                        &self.s_norm,
+                        &self.t_norm,
+                        &self.complement,
+                    );
+                }
+                let (lower, upper): (Vec<f64>, Vec<f64>) = fuzz.into_iter().unzip();
+                let kind = rule.get_kind();
+                (
+                    kind.connect(&self.s_norm, &self.t_norm, &lower),
+                    kind.connect(&self.s_norm, &self.t_norm, &upper),
+                )
+            })
+            .collect()
+    }
+
+    pub fn weighed_inputs(&self, connected_inputs: Vec<(f64, f64)>) -> Vec<(f64, f64)> {
+        connected_inputs
+            .into_iter()
+            .zip(&self.rules)
+            .map(|((lower, upper), rule)| (rule.get_weight() * lower, rule.get_weight() * upper))
+            .collect()
+    }
+
+    // for every output and rule, the implied (lower, upper) sets
+    pub fn implication(&self, connected_inputs: Vec<(f64, f64)>) -> Vec<Vec<(Vec<f64>, Vec<f64>)>> {
+        let mut implication_vec = Vec::new();
+        for (i, output) in self.outputs.iter().enumerate() {
+            let mut temp_vec = Vec::new();
+            for (rule, (lower_mu, upper_mu)) in self.rules.iter().zip(&connected_inputs) {
+                let relation = &rule.get_output_rules(self.inputs.len())[i];
+                let index = match relation.get_term() {
+                    Some(index) => index,
+                    None => continue,
+                };
+                let (lower, upper): (Vec<f64>, Vec<f64>) = output
+                    .get_lower(index)
+                    .iter()
+                    .zip(output.get_upper(index))
+                    .map(|(lower, upper)| relation.interval((*lower, *upper), &self.complement))
+                    .unzip();
+                temp_vec.push((
+                    self.implication.implication(*lower_mu, &lower),
+                    self.implication.implication(*upper_mu, &upper),
+                ));
+            }
+            implication_vec.push(temp_vec);
+        }
+        implication_vec
+    }
+
+    pub fn aggregation(
+        &self,
+        implication_vec: Vec<Vec<(Vec<f64>, Vec<f64>)>>,
+    ) -> Vec<(Vec<f64>, Vec<f64>)> {
+        implication_vec
+            .into_iter()
+            .map(|vec| {
+                let (lower, upper): (Vec<Vec<f64>>, Vec<Vec<f64>>) = vec.into_iter().unzip();
+                (
+                    self.aggregation.aggregation(&lower),
+                    self.aggregation.aggregation(&upper),
+                )
+            })
+            .collect()
+    }
+
+    pub fn type_reduction(&self, aggregation_vec: Vec<(Vec<f64>, Vec<f64>)>) -> Vec<(f64, f64)> {
+        aggregation_vec
+            .into_iter()
+            .zip(&self.outputs)
+            .map(|((lower, upper), output)| {
+                self.type_reducer
+                    .reduce(output.get_universe(), &lower, &upper)
+            })
+            .collect()
+    }
+
+    pub fn compute_output_intervals(&self, input_vec: Vec<f64>) -> Vec<(f64, f64)> {
+        self.try_compute_output_intervals(input_vec)
+            .unwrap_or_else(|err| panic!("{}", err))
+    }
+
+    pub fn try_compute_output_intervals(
+        &self,
+        input_vec: Vec<f64>,
+    ) -> Result<Vec<(f64, f64)>, FuzzyError> {
+        check_system(&self.inputs, &self.outputs, &self.rules, &input_vec)?;
+        validation::check_interval_output_ranges(&self.outputs)?;
+
+        // 1. fuzzification
+        let fuzzified = self.fuzzification(input_vec);
+        let connected_inputs = self.connect_inputs(fuzzified);
+        let weighted_inputs = self.weighed_inputs(connected_inputs);
+        if weighted_inputs.iter().all(|(_, upper)| *upper == 0.0) {
+            return Err(FuzzyError::ZeroFiringStrength);
+        }
+        // 2. implication
+        let implication_vec = self.implication(weighted_inputs);
+        // an output that no rule uses has no activation either
+        if implication_vec.iter().any(|implied| implied.is_empty()) {
+            return Err(FuzzyError::ZeroFiringStrength);
+        }
+        // 3. aggregation
+        let aggregation_vec = self.aggregation(implication_vec);
+        // 4. type reduction
+        Ok(self.type_reduction(aggregation_vec))
+    }
+
+    pub fn compute_outputs(&self, input_vec: Vec<f64>) -> Vec<f64> {
+        self.try_compute_outputs(input_vec)
+            .unwrap_or_else(|err| panic!("{}", err))
+    }
+
+    pub fn try_compute_outputs(&self, input_vec: Vec<f64>) -> Result<Vec<f64>, FuzzyError> {
+        // 5. defuzzification
+        Ok(self
+            .try_compute_output_intervals(input_vec)?
+            .into_iter()
+            .map(|(left, right)| (left + right) / 2.0)
+            .collect())
+    }
+}
+
+#[derive(Debug)]
+pub struct IntervalType2TSKFuzzyInferenceSystem {
+    s_norm: SNorms,
+    t_norm: TNorms,
+    complement: Complements,
+    type_reducer: TypeReducers,
+    rules: Vec<Rule>,
+    inputs: Vec<IntervalType2InputVariable>,
+    outputs: Vec<TSKOutputVariable>,
+}
+
+pub type IntervalType2TSKFIS = IntervalType2TSKFuzzyInferenceSystem;
+
+impl IntervalType2TSKFuzzyInferenceSystem {
+    pub fn new(s_norm: SNorms, t_norm: TNorms, type_reducer: TypeReducers) -> Self {
+        Self {
+            s_norm,
+            t_norm,
+            complement: Complements::Standard,
+            type_reducer,
+            rules: Vec::new(),
+            inputs: Vec::new(),
+            outputs: Vec::new(),
+        }
+    }
+
+    pub fn with_t_norm(t_norm: TNorms, type_reducer: TypeReducers) -> Self {
+        Self::new(SNorms::dual_of(&t_norm), t_norm, type_reducer)
+    }
+
+    pub fn add_input(&mut self, input: IntervalType2InputVariable) {
+        self.inputs.push(input);
+    }
+
+    pub fn add_output(&mut self, variable: TSKOutputVariable) {
+        self.outputs.push(variable);
+    }
+
+    pub fn add_rule(&mut self, rule: Rule) {
+        self.rules.push(rule);
+    }
+
+    pub fn parse_rule(&self, text: &str) -> Result<Rule, RuleParseError> {
+        Rule::parse(text, &self.inputs, &self.outputs)
+    }
+
+    pub fn add_text_rule(&mut self, text: &str) -> Result<(), RuleParseError> {
+        let rule = self.parse_rule(text)?;
+        self.rules.push(rule);
+        Ok(())
+    }
+
+    pub fn rule_to_text(&self, rule_index: usize) -> String {
+        self.rules[rule_index].to_text(&self.inputs, &self.outputs)
+    }
+
+    pub fn get_inputs(&self) -> &Vec<IntervalType2InputVariable> {
+        &self.inputs
+    }
+
+    pub fn get_outputs(&self) -> &Vec<TSKOutputVariable> {
+        &self.outputs
+    }
+
+    pub fn get_rule_base(&self) -> &Vec<Rule> {
+        &self.rules
+    }
+
+    pub fn get_s_norm_method(&self) -> &SNorms {
+        &self.s_norm
+    }
+
+    pub fn get_t_norm_method(&self) -> &TNorms {
+        &self.t_norm
+    }
+
+    pub fn get_complement_method(&self) -> &Complements {
+        &self.complement
+    }
+
+    // used for every negated term of the rules
+    pub fn set_complement(&mut self, complement: Complements) {
+        self.complement = complement;
+    }
+
+    pub fn get_type_reducer_method(&self) -> &TypeReducers {
+        &self.type_reducer
+    }
+
+    pub fn fuzzification(&self, input_vec: Vec<f64>) -> Vec<Vec<(f64, f64)>> {
+        let mut fuzzified = Vec::new();
+        for rule in &self.rules {
+            let mut temp_vec = Vec::new();
+            for (ii, relation) in rule.get_conditions(self.inputs.len()) {
+                match relation.get_term() {
+                    Some(index) => {
+                        let interval = self.inputs[ii].fuzzify(index, input_vec[ii]);
+                        temp_vec.push(relation.interval(interval, &self.complement));
+                    }
+                    None if rule.get_antecedent().is_some() => temp_vec.push((1.0, 1.0)),
+                    None => (),
+                }
+            }
+            fuzzified.push(temp_vec);
+        }
+        fuzzified
+    }
+
+    pub fn connect_inputs(&self, fuzzified: Vec<Vec<(f64, f64)>>) -> Vec<(f64, f64)> {
+        fuzzified
+            .into_iter()
+            .zip(&self.rules)
+            .map(|(fuzz, rule)| {
+                if let Some(antecedent) = rule.get_antecedent() {
+                    return antecedent.evaluate_interval(
+                        &mut fuzz.into_iter(),
+                        &self.s_norm,
+                        &self.t_norm,
+                        &self.complement,
+                    );
+                }
+                let (lower, upper): (Vec<f64>, Vec<f64>) = fuzz.into_iter().unzip();
+                let kind = rule.get_kind();
+                (
+                    kind.connect(&self.s_norm, &self.t_norm, &lower),
+                    kind.connect(&self.s_norm, &self.t_norm, &upper),
+                )
+            })
+            .collect()
+    }
+
+    pub fn weighed_inputs(&self, connected_inputs: Vec<(f64, f64)>) -> Vec<(f64, f64)> {
+        connected_inputs
+            .into_iter()
+            .zip(&self.rules)
+            .map(|((lower, upper), rule)| (rule.get_weight() * lower, rule.get_weight() * upper))
+            .collect()
+    }
+
+    // None for the rules that don't care about the output
+    pub fn get_mu(&self, input_vec: &Vec<f64>) -> Vec<Vec<Option<f64>>> {
+        self.outputs
+            .iter()
+            .enumerate()
+            .map(|(i, output)| {
+                self.rules
+                    .iter()
+                    .map(|rule| {
+                        let index = rule.get_output_rules(self.inputs.len())[i].get_term();
+                        index.map(|index| output.get_mu(index, input_vec))
+                    })
+                    .collect()
+            })
+            .collect()
+    }
+
+    pub fn compute_output_intervals(&self, input: Vec<f64>) -> Vec<(f64, f64)> {
+        self.try_compute_output_intervals(input)
+            .unwrap_or_else(|err| panic!("{}", err))
+    }
+
+    pub fn try_compute_output_intervals(
+        &self,
+        input: Vec<f64>,
+    ) -> Result<Vec<(f64, f64)>, FuzzyError> {
+        check_system(&self.inputs, &self.outputs, &self.rules, &input)?;
+        validation::check_consequent_inputs(self.inputs.len(), &self.outputs)?;
+        for (i, rule) in self.rules.iter().enumerate() {
+            validation::check_consequents(
+                i,
+                rule,
+                self.inputs.len(),
+                &self.outputs,
+                Consequents::Functions,
+            )?;
+        }
+
+        // 1 - fuzzification
+        let fuzzified = self.fuzzification(input.clone());
+        let connected_inputs = self.connect_inputs(fuzzified);
+        let weighted_input = self.weighed_inputs(connected_inputs);
+
+        // 2 - type reduction of the rule consequents, without the rules that don't care
+        self.get_mu(&input)
+            .into_iter()
+            .map(|mu| {
+                let (mu, (lower, upper)): (Vec<f64>, (Vec<f64>, Vec<f64>)) = mu
+                    .into_iter()
+                    .zip(&weighted_input)
+                    .filter_map(|(mu, weight)| Some((mu?, *weight)))
+                    .unzip();
+                if upper.iter().all(|upper| *upper == 0.0) {
+                    return Err(FuzzyError::ZeroFiringStrength);
+                }
+                Ok(self.type_reducer.reduce(&mu, &lower, &upper))
+            })
+            .collect()
+    }
+
+    pub fn compute_outputs(&self, input: Vec<f64>) -> Vec<f64> {
+        self.try_compute_outputs(input)
+            .unwrap_or_else(|err| panic!("{}", err))
+    }
+
+    pub fn try_compute_outputs(&self, input: Vec<f64>) -> Result<Vec<f64>, FuzzyError> {
+        Ok(self
+            .try_compute_output_intervals(input)?
+            .into_iter()
+            .map(|(left, right)| (left + right) / 2.0)
+            .collect())
     }
 }
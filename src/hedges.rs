@@ -0,0 +1,54 @@
+// Hedges (linguistic modifiers) sharpen or soften a term, e.g. `x IS VERY high`.
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Hedges {
+    Very,
+    Extremely,
+    Somewhat,
+    // must be increasing, intervals are hedged bound by bound
+    #[cfg_attr(feature = "serde", serde(skip))]
+    Custom(fn(f64) -> f64),
+}
+
+// custom hedges are the same when they use the same function
+impl PartialEq for Hedges {
+    fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (Self::Custom(a), Self::Custom(b)) => std::ptr::fn_addr_eq(*a, *b),
+            _ => std::mem::discriminant(self) == std::mem::discriminant(other),
+        }
+    }
+}
+
+impl Hedges {
+    pub fn hedge(&self, mu: f64) -> f64 {
+        match self {
+            Self::Very => mu * mu,
+            Self::Extremely => mu * mu * mu,
+            Self::Somewhat => mu.sqrt(),
+            Self::Custom(h) => h(mu),
+        }
+    }
+
+    pub fn hedge_interval(&self, (lower, upper): (f64, f64)) -> (f64, f64) {
+        (self.hedge(lower), self.hedge(upper))
+    }
+
+    pub fn get_keyword(&self) -> &str {
+        match self {
+            Self::Very => "VERY",
+            Self::Extremely => "EXTREMELY",
+            Self::Somewhat => "SOMEWHAT",
+            Self::Custom(_) => "CUSTOM",
+        }
+    }
+
+    pub fn from_keyword(keyword: &str) -> Option<Self> {
+        match keyword.to_uppercase().as_str() {
+            "VERY" => Some(Self::Very),
+            "EXTREMELY" => Some(Self::Extremely),
+            "SOMEWHAT" => Some(Self::Somewhat),
+            _ => None,
+        }
+    }
+}
@@ -1,7 +1,16 @@
 #[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Implications {
     Min,
     Product,
+    Lukasiewicz,
+    Godel,
+    Goguen,
+    KleeneDienes,
+    Reichenbach,
+    Zadeh,
+    Yager,
+    #[cfg_attr(feature = "serde", serde(skip))]
     Custom(fn(f64, &Vec<f64>) -> Vec<f64>),
 }
 
@@ -10,11 +19,28 @@ impl Implications {
         match self {
             Self::Min => min_implication(mu, &vec),
             Self::Product => product_implication(mu, &vec),
+            Self::Lukasiewicz => lukasiewicz_implication(mu, vec),
+            Self::Godel => godel_implication(mu, vec),
+            Self::Goguen => goguen_implication(mu, vec),
+            Self::KleeneDienes => kleene_dienes_implication(mu, vec),
+            Self::Reichenbach => reichenbach_implication(mu, vec),
+            Self::Zadeh => zadeh_implication(mu, vec),
+            Self::Yager => yager_implication(mu, vec),
             Self::Custom(func) => func(mu, &vec),
         }
     }
 }
 
+// Conjunctive rules are joined with a union (the aggregation method),
+// implicative rules constrain each other and are joined with an intersection.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Reasoning {
+    #[default]
+    Conjunctive,
+    Implicative,
+}
+
 pub fn min_implication(mu: f64, vec: &Vec<f64>) -> Vec<f64> {
     vec.iter().map(|e| e.min(mu)).collect()
 }
@@ -22,3 +48,38 @@ pub fn min_implication(mu: f64, vec: &Vec<f64>) -> Vec<f64> {
 pub fn product_implication(mu: f64, vec: &Vec<f64>) -> Vec<f64> {
     vec.iter().map(|e| e * mu).collect()
 }
+
+pub fn lukasiewicz_implication(mu: f64, vec: &[f64]) -> Vec<f64> {
+    vec.iter().map(|e| (1.0 - mu + e).min(1.0)).collect()
+}
+
+pub fn godel_implication(mu: f64, vec: &[f64]) -> Vec<f64> {
+    vec.iter()
+        .map(|e| if mu <= *e { 1.0 } else { *e })
+        .collect()
+}
+
+pub fn goguen_implication(mu: f64, vec: &[f64]) -> Vec<f64> {
+    vec.iter()
+        .map(|e| if mu <= *e { 1.0 } else { e / mu })
+        .collect()
+}
+
+pub fn kleene_dienes_implication(mu: f64, vec: &[f64]) -> Vec<f64> {
+    vec.iter().map(|e| (1.0 - mu).max(*e)).collect()
+}
+
+pub fn reichenbach_implication(mu: f64, vec: &[f64]) -> Vec<f64> {
+    vec.iter().map(|e| 1.0 - mu + mu * e).collect()
+}
+
+pub fn zadeh_implication(mu: f64, vec: &[f64]) -> Vec<f64> {
+    vec.iter().map(|e| (1.0 - mu).max(mu.min(*e))).collect()
+}
+
+// 0^0 is taken as 1, so a rule that does not fire allows everything
+pub fn yager_implication(mu: f64, vec: &[f64]) -> Vec<f64> {
+    vec.iter()
+        .map(|e| if mu == 0.0 { 1.0 } else { e.powf(mu) })
+        .collect()
+}
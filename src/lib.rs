@@ -1,13 +1,2786 @@
 pub mod aggregations;
+pub mod anfis;
+pub mod complements;
 pub mod defuzzifications;
+pub mod error;
+pub mod fcl_format;
+pub mod fis_format;
 pub mod fuzzy_inference_systems;
+pub mod hedges;
 pub mod implications;
 pub mod membership_functions;
 pub mod membership_ranges;
 pub mod rules;
 pub mod s_norms;
+#[cfg(feature = "serde")]
+pub mod serialization;
 pub mod t_norms;
+pub mod trace;
+pub mod type_reductions;
+pub mod validation;
 pub mod variables;
 
 #[cfg(test)]
-mod test {}
+mod test {
+    mod fis_format {
+        use crate::fis_format::{parse_mamdani, parse_tsk, write_mamdani, write_tsk};
+
+        const MAMDANI: &str = "[System]
+Name='tipper'
+Type='mamdani'
+NumInputs=2
+NumOutputs=1
+NumRules=3
+AndMethod='min'
+OrMethod='max'
+ImpMethod='min'
+AggMethod='max'
+DefuzzMethod='centroid'
+
+[Input1]
+Name='service'
+Range=[0 10]
+NumMFs=3
+MF1='poor':'zmf',[0 5]
+MF2='good':'gaussmf',[1.5 5]
+MF3='excellent':'smf',[5 10]
+
+[Input2]
+Name='food'
+Range=[0 10]
+NumMFs=2
+MF1='rancid':'trapmf',[0 0 1 3]
+MF2='delicious':'linsmf',[7 9]
+
+[Output1]
+Name='tip'
+Range=[0 30]
+NumMFs=3
+MF1='cheap':'zmf',[0 12]
+MF2='average':'trimf',[15 15 20]
+MF3='generous':'smf',[18 30]
+
+[Rules]
+1 1, 1 (1) : 2
+2 1, 2 (1) : 1
+3 2, 3 (0.5) : 1
+";
+
+        const TSK: &str = "[System]
+Name='tipper'
+Type='sugeno'
+NumInputs=2
+NumOutputs=1
+NumRules=2
+AndMethod='prod'
+OrMethod='max'
+DefuzzMethod='wtaver'
+
+[Input1]
+Name='service'
+Range=[0 10]
+NumMFs=2
+MF1='poor':'zmf',[0 10]
+MF2='excellent':'smf',[0 10]
+
+[Input2]
+Name='food'
+Range=[0 10]
+NumMFs=2
+MF1='rancid':'gbellmf',[2 2 0]
+MF2='delicious':'gauss2mf',[1 8 1 10]
+
+[Output1]
+Name='tip'
+Range=[0 30]
+NumMFs=2
+MF1='cheap':'constant',[5]
+MF2='generous':'linear',[1 0.5 0]
+
+[Rules]
+1 1, 1 (1) : 1
+2 2, 2 (1) : 2
+";
+
+        const INPUTS: [[f64; 2]; 4] = [[0.0, 0.0], [2.5, 8.0], [7.5, 1.0], [10.0, 9.5]];
+
+        #[test]
+        fn mamdani_round_trip() {
+            let fis = parse_mamdani(MAMDANI).unwrap();
+            let loaded = parse_mamdani(&write_mamdani(&fis, "tipper").unwrap()).unwrap();
+            for x in INPUTS {
+                assert_eq!(
+                    fis.compute_outputs(x.to_vec()),
+                    loaded.compute_outputs(x.to_vec())
+                );
+            }
+        }
+
+        #[test]
+        fn tsk_round_trip() {
+            let fis = parse_tsk(TSK).unwrap();
+            let loaded = parse_tsk(&write_tsk(&fis, "tipper").unwrap()).unwrap();
+            for x in INPUTS {
+                assert_eq!(
+                    fis.compute_outputs(x.to_vec()),
+                    loaded.compute_outputs(x.to_vec())
+                );
+            }
+        }
+
+        #[test]
+        fn splines_are_kept() {
+            let fis = parse_mamdani(MAMDANI).unwrap();
+            let text = write_mamdani(&fis, "tipper").unwrap();
+            assert!(text.contains("MF1='poor':'zmf',[0 5]"), "{}", text);
+            assert!(text.contains("MF3='generous':'smf',[18 30]"), "{}", text);
+            // MATLAB's smf is 0.5 at the middle and 0.125 at a quarter
+            let service = &fis.get_inputs()[0];
+            assert!((service.fuzzify(2, 7.5) - 0.5).abs() < 1e-12);
+            assert!((service.fuzzify(2, 6.25) - 0.125).abs() < 1e-12);
+        }
+    }
+
+    mod fcl_format {
+        use crate::fcl_format::{parse_fcl, write_fcl, FclError};
+
+        const TIPPER: &str = "(*) tipper (**)
+FUNCTION_BLOCK tipper
+
+VAR_INPUT
+    service : REAL; (* 0 to 10 *)
+    food : REAL;
+END_VAR
+
+VAR_OUTPUT
+    tip : REAL;
+END_VAR
+
+FUZZIFY service
+    RANGE := (0 .. 10);
+    TERM poor := (0, 1) (4, 0);
+    TERM good := gauss 5 1.5;
+    TERM excellent := (6, 0) (10, 1);
+END_FUZZIFY
+
+FUZZIFY food
+    RANGE := (0 .. 10);
+    TERM rancid := trape 0 0 1 3;
+    TERM delicious := trian 6 9 10;
+END_FUZZIFY
+
+DEFUZZIFY tip
+    RANGE := (0 .. 30);
+    TERM cheap := trian 0 5 10;
+    TERM average := trian 10 15 20;
+    TERM generous := trian 20 25 30;
+    METHOD : COG;
+    DEFAULT := 0;
+END_DEFUZZIFY
+
+RULEBLOCK first
+    AND : MIN;
+    ACT : MIN;
+    ACCU : MAX;
+    RULE 1 : IF service IS poor OR food IS rancid THEN tip IS cheap;
+    RULE 2 : IF service IS good OR food IS delicious THEN tip IS average;
+    RULE 3 : IF service IS excellent AND food IS delicious THEN tip IS generous WITH 0.5;
+END_RULEBLOCK
+
+END_FUNCTION_BLOCK
+";
+
+        const INPUTS: [[f64; 2]; 4] = [[0.0, 0.0], [2.5, 8.0], [5.0, 5.0], [9.0, 9.5]];
+
+        fn location(source: &str) -> (usize, usize) {
+            match parse_fcl(source) {
+                Err(FclError::Parse { line, column, .. }) => (line, column),
+                other => panic!("expected a parse error, got {:?}", other.map(|_| ())),
+            }
+        }
+
+        #[test]
+        fn round_trip() {
+            let fis = parse_fcl(TIPPER).unwrap();
+            let text = write_fcl(&fis, "tipper").unwrap();
+            let loaded = parse_fcl(&text).unwrap();
+            assert_eq!(write_fcl(&loaded, "tipper").unwrap(), text);
+            for x in INPUTS {
+                assert_eq!(
+                    fis.compute_outputs(x.to_vec()),
+                    loaded.compute_outputs(x.to_vec())
+                );
+            }
+        }
+
+        #[test]
+        fn short_comments_are_closed() {
+            let source = TIPPER.replace("(* 0 to 10 *)", "(**)(*)*)");
+            assert!(parse_fcl(&source).is_ok());
+            assert_eq!(location("(*) unterminated"), (1, 1));
+        }
+
+        #[test]
+        fn bad_term_shape() {
+            let source = TIPPER.replace("gauss 5 1.5", "sigm 5 1.5");
+            assert_eq!(location(&source), (16, 18));
+        }
+
+        #[test]
+        fn missing_end_fuzzify() {
+            let source = TIPPER.replacen("END_FUZZIFY", "", 1);
+            assert_eq!(location(&source), (20, 1));
+        }
+
+        #[test]
+        fn weight_above_one() {
+            let source = TIPPER.replace("WITH 0.5", "WITH 1.5");
+            assert_eq!(location(&source), (41, 81));
+        }
+    }
+
+    mod rules {
+        use crate::membership_functions::{Kind, MembershipFunction, Triangle};
+        use crate::membership_ranges::MembershipRange;
+        use crate::rules::{self, Relation, Rule};
+        use crate::variables::{InputVariable, OutputVariable};
+
+        fn variables() -> (Vec<InputVariable>, Vec<OutputVariable>) {
+            let mut inputs = Vec::new();
+            for name in ["service", "food"] {
+                let mut input = InputVariable::new(name.to_string(), (0.0, 10.0));
+                for (term, b) in [("bad", 0.0), ("ok", 5.0), ("great", 10.0)] {
+                    let kind = Kind::Triangle(Triangle::new(b - 5.0, b, b + 5.0));
+                    input.add_membership(MembershipFunction::new(term.to_string(), kind));
+                }
+                inputs.push(input);
+            }
+            let mut tip = OutputVariable::new("tip".to_string(), (0.0, 30.0), 31);
+            let universe = tip.get_universe().clone();
+            for (term, b) in [("low", 0.0), ("high", 30.0)] {
+                let mr = MembershipRange::new_triangle(
+                    &universe,
+                    term.to_string(),
+                    b - 30.0,
+                    b,
+                    b + 30.0,
+                );
+                tip.add_membership(mr);
+            }
+            (inputs, vec![tip])
+        }
+
+        #[test]
+        fn round_trip() {
+            let (inputs, outputs) = variables();
+            for text in [
+                "IF service IS bad AND food IS NOT great THEN tip IS low",
+                "IF service IS great OR food IS ok THEN tip IS high WITH 0.5",
+            ] {
+                let rule = Rule::parse(text, &inputs, &outputs).unwrap();
+                assert_eq!(rule.to_text(&inputs, &outputs), text);
+            }
+        }
+
+        #[test]
+        fn parsed_relations() {
+            let (inputs, outputs) = variables();
+            let text = "if food is ok or service is not ok then tip is high with 0.25";
+            let rule = Rule::parse(text, &inputs, &outputs).unwrap();
+            assert_eq!(
+                rule.get_rules(),
+                &[Relation::Not(1), Relation::Term(1), Relation::Term(1)]
+            );
+            assert_eq!(rule.get_weight(), 0.25);
+            assert!(matches!(rule.get_kind(), rules::Kind::OR));
+            assert_eq!(
+                rule.to_text(&inputs, &outputs),
+                "IF service IS NOT ok OR food IS ok THEN tip IS high WITH 0.25"
+            );
+        }
+
+        #[test]
+        fn error_columns() {
+            let (inputs, outputs) = variables();
+            let column = |text: &str| Rule::parse(text, &inputs, &outputs).unwrap_err().column;
+            assert_eq!(column("WHEN service IS bad THEN tip IS low"), 1);
+            assert_eq!(column("IF waiter IS bad THEN tip IS low"), 4);
+            assert_eq!(
+                column("IF service IS awful AND food IS ok THEN tip IS low"),
+                15
+            );
+            assert_eq!(
+                column("IF service IS bad AND[MEDIAN] food IS ok THEN tip IS low"),
+                23
+            );
+            assert_eq!(
+                column("IF service IS bad AND food IS ok AND[PRODUCT] food IS bad THEN tip IS low"),
+                34
+            );
+            assert_eq!(
+                column("IF service IS bad AND food IS ok THEN tip IS low WITH 2"),
+                55
+            );
+            assert_eq!(
+                column("IF service IS bad AND food IS ok THEN tip IS low extra"),
+                50
+            );
+            assert_eq!(column("IF service IS bad AND food IS ok THEN"), 38);
+            // a missing variable is not an error, the rule does not care about it
+            let rule = Rule::parse("IF service IS bad THEN tip IS low", &inputs, &outputs);
+            assert_eq!(rule.unwrap().get_rules()[1], Relation::Any);
+        }
+    }
+
+    #[cfg(feature = "serde")]
+    mod serde {
+        use crate::aggregations::Aggregations;
+        use crate::defuzzifications::{Defuzzifiers, TSKDefuzzifiers};
+        use crate::fuzzy_inference_systems::{MamdaniFIS, TSKFIS};
+        use crate::implications::Implications;
+        use crate::membership_functions::{Gaussian, Kind, Triangle, MF};
+        use crate::membership_ranges::MembershipRange;
+        use crate::rules::Rule;
+        use crate::s_norms::SNorms;
+        use crate::t_norms::TNorms;
+        use crate::variables::{InputVariable, OutputVariable, TSKOutputVariable};
+
+        fn input() -> InputVariable {
+            let mut x = InputVariable::new("x".to_string(), (0.0, 10.0));
+            x.add_membership(MF::new(
+                "low".to_string(),
+                Kind::Triangle(Triangle::new(-5.0, 0.0, 5.0)),
+            ));
+            x.add_membership(MF::new(
+                "high".to_string(),
+                Kind::Gaussian(Gaussian::new(10.0, 3.0)),
+            ));
+            x
+        }
+
+        fn mamdani() -> MamdaniFIS {
+            let mut y = OutputVariable::new("y".to_string(), (0.0, 1.0), 100);
+            y.add_membership(MembershipRange::new_triangle(
+                y.get_universe(),
+                "small".to_string(),
+                -0.5,
+                0.0,
+                0.5,
+            ));
+            y.add_membership(MembershipRange::new_gaussian(
+                y.get_universe(),
+                "big".to_string(),
+                1.0,
+                0.2,
+            ));
+            let mut fis = MamdaniFIS::new(
+                SNorms::Max,
+                TNorms::Min,
+                Implications::Min,
+                Aggregations::Max,
+                Defuzzifiers::Centroid,
+            );
+            fis.add_input(input());
+            fis.add_output(y);
+            fis.add_rule(Rule::new_and(vec![0, 0], 1.0));
+            fis.add_rule(Rule::new_or(vec![-1, 1], 0.5));
+            fis
+        }
+
+        fn tsk() -> TSKFIS {
+            let mut y = TSKOutputVariable::new("y".to_string());
+            y.add_constant_membership(1.0);
+            y.add_linear_membership(vec![0.5]);
+            let mut fis = TSKFIS::new(SNorms::Max, TNorms::Product, TSKDefuzzifiers::Mean);
+            fis.add_input(input());
+            fis.add_output(y);
+            fis.add_rule(Rule::new_and(vec![0, 0], 1.0));
+            fis.add_rule(Rule::new_and(vec![1, 1], 1.0));
+            fis
+        }
+
+        const INPUTS: [f64; 4] = [0.0, 2.5, 6.0, 9.5];
+
+        #[test]
+        fn mamdani_json_round_trip() {
+            let fis = mamdani();
+            let json = serde_json::to_string(&fis).unwrap();
+            let loaded: MamdaniFIS = serde_json::from_str(&json).unwrap();
+            for x in INPUTS {
+                assert_eq!(
+                    fis.compute_outputs(vec![x]),
+                    loaded.compute_outputs(vec![x])
+                );
+            }
+        }
+
+        #[test]
+        fn mamdani_toml_round_trip() {
+            let fis = mamdani();
+            let text = toml::to_string(&fis).unwrap();
+            let loaded: MamdaniFIS = toml::from_str(&text).unwrap();
+            for x in INPUTS {
+                assert_eq!(
+                    fis.compute_outputs(vec![x]),
+                    loaded.compute_outputs(vec![x])
+                );
+            }
+        }
+
+        #[test]
+        fn tsk_json_round_trip() {
+            let fis = tsk();
+            let json = serde_json::to_string(&fis).unwrap();
+            let loaded: TSKFIS = serde_json::from_str(&json).unwrap();
+            for x in INPUTS {
+                assert_eq!(
+                    fis.compute_outputs(vec![x]),
+                    loaded.compute_outputs(vec![x])
+                );
+            }
+        }
+
+        #[test]
+        fn tsk_toml_round_trip() {
+            let fis = tsk();
+            let text = toml::to_string(&fis).unwrap();
+            let loaded: TSKFIS = toml::from_str(&text).unwrap();
+            for x in INPUTS {
+                assert_eq!(
+                    fis.compute_outputs(vec![x]),
+                    loaded.compute_outputs(vec![x])
+                );
+            }
+        }
+
+        #[test]
+        fn custom_variants_are_rejected() {
+            fn first(v: &[f64]) -> f64 {
+                v[0]
+            }
+            let fis = MamdaniFIS::new(
+                SNorms::Max,
+                TNorms::Custom(first),
+                Implications::Min,
+                Aggregations::Max,
+                Defuzzifiers::Centroid,
+            );
+            let err = serde_json::to_string(&fis).unwrap_err().to_string();
+            assert!(err.contains("TNorms::Custom"), "{}", err);
+        }
+
+        #[test]
+        fn unknown_version_is_rejected() {
+            let json = serde_json::to_string(&tsk()).unwrap();
+            let version = format!("\"version\":{}", crate::serialization::SCHEMA_VERSION);
+            let json = json.replace(&version, "\"version\":99");
+            let err = serde_json::from_str::<TSKFIS>(&json).err().unwrap();
+            assert!(err.to_string().contains("version 99"));
+        }
+    }
+
+    mod errors {
+        use crate::aggregations::Aggregations;
+        use crate::defuzzifications::{Defuzzifiers, TSKDefuzzifiers};
+        use crate::error::FuzzyError;
+        use crate::fuzzy_inference_systems::{MamdaniFIS, TSKFIS};
+        use crate::implications::Implications;
+        use crate::membership_functions::{Gaussian, Kind, SplineS, Triangle, MF};
+        use crate::membership_ranges::MembershipRange;
+        use crate::rules::{Relation, Rule};
+        use crate::s_norms::SNorms;
+        use crate::t_norms::TNorms;
+        use crate::variables::{InputVariable, OutputVariable, TSKOutputVariable};
+
+        fn input() -> InputVariable {
+            let mut x = InputVariable::new("x".to_string(), (0.0, 10.0));
+            x.add_membership(MF::new(
+                "low".to_string(),
+                Kind::Triangle(Triangle::new(-10.0, 0.0, 10.0)),
+            ));
+            x.add_membership(MF::new(
+                "high".to_string(),
+                Kind::Triangle(Triangle::new(0.0, 10.0, 20.0)),
+            ));
+            x
+        }
+
+        fn mamdani(rule: Rule) -> MamdaniFIS {
+            let mut y = OutputVariable::new("y".to_string(), (0.0, 1.0), 100);
+            y.add_membership(MembershipRange::new_triangle(
+                y.get_universe(),
+                "small".to_string(),
+                -1.0,
+                0.0,
+                1.0,
+            ));
+            let mut fis = MamdaniFIS::new(
+                SNorms::Max,
+                TNorms::Min,
+                Implications::Min,
+                Aggregations::Max,
+                Defuzzifiers::Centroid,
+            );
+            fis.add_input(input());
+            fis.add_output(y);
+            fis.add_rule(rule);
+            fis
+        }
+
+        #[test]
+        fn invalid_parameters() {
+            let message = |text: &str| Err(FuzzyError::InvalidParameters(text.to_string()));
+            assert_eq!(
+                Triangle::try_new(1.0, 0.0, 2.0).map(|_| ()),
+                message("a must be less than or equal to b")
+            );
+            assert_eq!(
+                Gaussian::try_new(0.0, 0.0).map(|_| ()),
+                message("variance must be greater than 0")
+            );
+            assert_eq!(
+                SplineS::try_new(1.0, 1.0).map(|_| ()),
+                message("a must be less than b")
+            );
+            let universe = vec![0.0, 0.5, 1.0];
+            assert_eq!(
+                MembershipRange::try_new_splinez(&universe, "z".to_string(), 1.0, 0.0).map(|_| ()),
+                message("a must be less than b")
+            );
+            assert_eq!(
+                Rule::try_new_and(vec![0, 0], 1.5).map(|_| ()),
+                Err(FuzzyError::WeightOutOfRange(1.5))
+            );
+        }
+
+        #[test]
+        fn rule_arity() {
+            let fis = mamdani(Rule::new_and(vec![0, 0, 0], 1.0));
+            assert_eq!(
+                fis.try_compute_outputs(vec![1.0]),
+                Err(FuzzyError::RuleArity {
+                    rule: 0,
+                    expected: 2,
+                    found: 3
+                })
+            );
+        }
+
+        #[test]
+        fn unknown_term() {
+            let fis = mamdani(Rule::new_and(vec![-2, 0], 1.0));
+            assert_eq!(
+                fis.try_compute_outputs(vec![1.0]),
+                Err(FuzzyError::UnknownTerm {
+                    rule: 0,
+                    variable: "x".to_string(),
+                    index: 2
+                })
+            );
+            let fis = mamdani(Rule::new_and(vec![0, 1], 1.0));
+            assert_eq!(
+                fis.try_compute_outputs(vec![1.0]),
+                Err(FuzzyError::UnknownTerm {
+                    rule: 0,
+                    variable: "y".to_string(),
+                    index: 1
+                })
+            );
+        }
+
+        #[test]
+        fn input_length() {
+            let fis = mamdani(Rule::new_and(vec![0, 0], 1.0));
+            assert_eq!(
+                fis.try_compute_outputs(vec![1.0, 2.0]),
+                Err(FuzzyError::InputLength {
+                    expected: 1,
+                    found: 2
+                })
+            );
+            assert!(fis.try_compute_outputs(vec![1.0]).is_ok());
+        }
+
+        #[test]
+        fn tsk_errors() {
+            let mut y = TSKOutputVariable::new("y".to_string());
+            y.add_constant_membership(1.0);
+            y.add_constant_membership(2.0);
+            let mut fis = TSKFIS::new(SNorms::Max, TNorms::Min, TSKDefuzzifiers::Mean);
+            assert_eq!(
+                fis.try_compute_outputs(vec![1.0]),
+                Err(FuzzyError::NoInputs)
+            );
+            fis.add_input(input());
+            fis.add_output(y);
+            assert_eq!(
+                fis.try_compute_outputs(vec![1.0]),
+                Err(FuzzyError::EmptyRuleBase)
+            );
+            fis.add_rule(Rule::new_and(vec![0, -1], 1.0));
+            assert_eq!(
+                fis.try_compute_outputs(vec![1.0]),
+                Err(FuzzyError::UnsupportedRelation {
+                    rule: 0,
+                    variable: "y".to_string(),
+                    relation: Relation::Not(1),
+                })
+            );
+        }
+    }
+
+    mod validation {
+        use crate::aggregations::Aggregations;
+        use crate::defuzzifications::Defuzzifiers;
+        use crate::error::FuzzyError;
+        use crate::fuzzy_inference_systems::MamdaniFIS;
+        use crate::implications::Implications;
+        use crate::membership_functions::{Gaussian, Kind, Triangle, MF};
+        use crate::membership_ranges::MembershipRange;
+        use crate::rules::Rule;
+        use crate::s_norms::SNorms;
+        use crate::t_norms::TNorms;
+        use crate::validation::Diagnostic;
+        use crate::variables::{InputVariable, OutputVariable};
+
+        fn mamdani(rules: Vec<Rule>) -> MamdaniFIS {
+            let mut x = InputVariable::new("x".to_string(), (0.0, 10.0));
+            x.add_membership(MF::new(
+                "low".to_string(),
+                Kind::Triangle(Triangle::new(-10.0, 0.0, 10.0)),
+            ));
+            // the top of this one is outside of the range
+            x.add_membership(MF::new(
+                "far".to_string(),
+                Kind::Gaussian(Gaussian::new(20.0, 10.0)),
+            ));
+            let mut y = OutputVariable::new("y".to_string(), (0.0, 1.0), 101);
+            y.add_membership(MembershipRange::new_triangle(
+                y.get_universe(),
+                "small".to_string(),
+                -1.0,
+                0.0,
+                1.0,
+            ));
+            y.add_membership(MembershipRange::new_triangle(
+                y.get_universe(),
+                "big".to_string(),
+                0.0,
+                1.0,
+                2.0,
+            ));
+            let mut fis = MamdaniFIS::new(
+                SNorms::Max,
+                TNorms::Min,
+                Implications::Min,
+                Aggregations::Max,
+                Defuzzifiers::Centroid,
+            );
+            fis.add_input(x);
+            fis.add_output(y);
+            for rule in rules {
+                fis.add_rule(rule);
+            }
+            fis
+        }
+
+        #[test]
+        fn duplicate_rule() {
+            let fis = mamdani(vec![
+                Rule::new_and(vec![0, 0], 1.0),
+                Rule::new_and(vec![1, 1], 1.0),
+                Rule::new_and(vec![0, 0], 0.5),
+            ]);
+            let diagnostics = fis.validate();
+            assert!(diagnostics.contains(&Diagnostic::DuplicateRule { rule: 2, first: 0 }));
+            assert!(diagnostics.iter().all(|d| !d.is_error()));
+        }
+
+        #[test]
+        fn unused_term() {
+            let fis = mamdani(vec![Rule::new_and(vec![0, 0], 1.0)]);
+            let unused: Vec<Diagnostic> = fis
+                .validate()
+                .into_iter()
+                .filter(|d| matches!(d, Diagnostic::UnusedTerm { .. }))
+                .collect();
+            assert_eq!(
+                unused,
+                vec![
+                    Diagnostic::UnusedTerm {
+                        variable: "x".to_string(),
+                        term: "far".to_string()
+                    },
+                    Diagnostic::UnusedTerm {
+                        variable: "y".to_string(),
+                        term: "big".to_string()
+                    }
+                ]
+            );
+        }
+
+        #[test]
+        fn never_reaches_one() {
+            let fis = mamdani(vec![
+                Rule::new_and(vec![0, 0], 1.0),
+                Rule::new_and(vec![1, 1], 1.0),
+            ]);
+            let diagnostics = fis.validate();
+            assert_eq!(diagnostics.len(), 1, "{:?}", diagnostics);
+            match &diagnostics[0] {
+                Diagnostic::NeverReachesOne {
+                    variable,
+                    term,
+                    max,
+                } => {
+                    assert_eq!((variable.as_str(), term.as_str()), ("x", "far"));
+                    // exp(-0.5 * ((10 - 20) / 10)^2)
+                    assert!((max - (-0.5f64).exp()).abs() < 1e-12);
+                }
+                other => panic!("unexpected {:?}", other),
+            }
+        }
+
+        #[test]
+        fn errors_are_collected() {
+            let fis = mamdani(vec![
+                Rule::new_and(vec![0, 0, 0], 1.0),
+                Rule::new_and(vec![2, 0], 1.0),
+            ]);
+            let errors: Vec<Diagnostic> = fis
+                .validate()
+                .into_iter()
+                .filter(|d| d.is_error())
+                .collect();
+            assert_eq!(errors.len(), 3);
+            assert!(matches!(
+                errors[1],
+                Diagnostic::Error(FuzzyError::UnknownTerm {
+                    rule: 1,
+                    index: 2,
+                    ..
+                })
+            ));
+            // no valid rule is left for the output
+            assert_eq!(
+                errors[2],
+                Diagnostic::Error(FuzzyError::UnusedOutput {
+                    variable: "y".to_string()
+                })
+            );
+        }
+    }
+
+    mod type_reductions {
+        use crate::aggregations::Aggregations;
+        use crate::fuzzy_inference_systems::IntervalType2MamdaniFIS;
+        use crate::implications::Implications;
+        use crate::membership_functions::{Footprint, IntervalType2Kind, Kind, Triangle, IT2MF};
+        use crate::membership_ranges::IntervalType2MembershipRange;
+        use crate::rules::Rule;
+        use crate::s_norms::SNorms;
+        use crate::t_norms::TNorms;
+        use crate::type_reductions::{enhanced_karnik_mendel, karnik_mendel, TypeReducers};
+        use crate::variables::{IntervalType2InputVariable, IntervalType2OutputVariable};
+
+        // the centroid bounds by trying every switch point
+        fn brute_force(points: &[f64], lower: &[f64], upper: &[f64]) -> (f64, f64) {
+            let mean = |first: &[f64], second: &[f64], switch: usize| {
+                let weights: Vec<f64> = (0..points.len())
+                    .map(|i| if i < switch { first[i] } else { second[i] })
+                    .collect();
+                let sum: f64 = weights.iter().sum();
+                points.iter().zip(&weights).map(|(x, w)| x * w).sum::<f64>() / sum
+            };
+            let switches = 0..=points.len();
+            (
+                switches
+                    .clone()
+                    .map(|k| mean(upper, lower, k))
+                    .fold(f64::MAX, f64::min),
+                switches
+                    .map(|k| mean(lower, upper, k))
+                    .fold(f64::MIN, f64::max),
+            )
+        }
+
+        fn close(a: (f64, f64), b: (f64, f64)) -> bool {
+            (a.0 - b.0).abs() < 1e-12 && (a.1 - b.1).abs() < 1e-12
+        }
+
+        #[test]
+        fn hand_computed_interval() {
+            let points = [1.0, 2.0, 3.0];
+            let lower = [0.5, 0.5, 0.5];
+            let upper = [1.0, 1.0, 1.0];
+            // left: (1 * 1 + 2 * 0.5 + 3 * 0.5) / 2, right is its mirror around 2
+            assert!(close(karnik_mendel(&points, &lower, &upper), (1.75, 2.25)));
+            assert!(close(
+                enhanced_karnik_mendel(&points, &lower, &upper),
+                (1.75, 2.25)
+            ));
+        }
+
+        #[test]
+        fn matches_brute_force() {
+            let points: Vec<f64> = (0..21).map(|i| i as f64 / 2.0).collect();
+            let upper: Vec<f64> = points
+                .iter()
+                .map(|x| (1.0 - (x - 4.0f64).abs() / 6.0).max(0.1))
+                .collect();
+            let lower: Vec<f64> = upper
+                .iter()
+                .zip(&points)
+                .map(|(u, x)| u * x / 10.0)
+                .collect();
+            let expected = brute_force(&points, &lower, &upper);
+            assert!(close(karnik_mendel(&points, &lower, &upper), expected));
+            assert!(close(
+                enhanced_karnik_mendel(&points, &lower, &upper),
+                expected
+            ));
+            // the order of the points does not matter
+            let reversed = |v: &[f64]| v.iter().rev().cloned().collect::<Vec<f64>>();
+            assert!(close(
+                karnik_mendel(&reversed(&points), &reversed(&lower), &reversed(&upper)),
+                expected
+            ));
+        }
+
+        fn mamdani(type_reducer: TypeReducers) -> IntervalType2MamdaniFIS {
+            let mut x = IntervalType2InputVariable::new("x".to_string(), (0.0, 10.0));
+            let triangle = || Kind::Triangle(Triangle::new(-10.0, 0.0, 10.0));
+            let kind =
+                IntervalType2Kind::Footprint(Footprint::new(triangle(), triangle(), 1.0 / 3.0));
+            x.add_membership(IT2MF::new("low".to_string(), kind));
+            let mut y = IntervalType2OutputVariable::new("y".to_string(), (0.0, 3.0), 3);
+            y.add_membership(IntervalType2MembershipRange::new(
+                "small".to_string(),
+                vec![0.2, 0.5, 0.5],
+                vec![1.0, 1.0, 0.5],
+            ));
+            let mut fis = IntervalType2MamdaniFIS::new(
+                SNorms::Max,
+                TNorms::Min,
+                Implications::Min,
+                Aggregations::Max,
+                type_reducer,
+            );
+            fis.add_input(x);
+            fis.add_output(y);
+            fis.add_rule(Rule::new_and(vec![0, 0], 1.0));
+            fis
+        }
+
+        #[test]
+        fn mamdani_output() {
+            // x = 2.5 fires the rule with [0.25, 0.75], so over the points 0, 1, 2
+            // lower = [0.2, 0.25, 0.25] and upper = [0.75, 0.75, 0.5]
+            // left = (1 * 0.25 + 2 * 0.25) / (0.75 + 0.25 + 0.25) = 0.6
+            // right = (1 * 0.25 + 2 * 0.5) / (0.2 + 0.25 + 0.5) = 25 / 19
+            let expected = (0.6, 25.0 / 19.0);
+            for type_reducer in [
+                TypeReducers::KarnikMendel,
+                TypeReducers::EnhancedKarnikMendel,
+            ] {
+                let fis = mamdani(type_reducer);
+                assert!(close(fis.compute_output_intervals(vec![2.5])[0], expected));
+                let output = fis.compute_outputs(vec![2.5])[0];
+                assert!((output - (expected.0 + expected.1) / 2.0).abs() < 1e-12);
+            }
+        }
+    }
+
+    mod tsukamoto {
+        use crate::fuzzy_inference_systems::TsukamotoFIS;
+        use crate::membership_functions::{
+            Kind, LinearZ, MonotonicKind, Sigmoid, Triangle, TsukamotoMembershipFunction, MF,
+        };
+        use crate::rules::Rule;
+        use crate::s_norms::SNorms;
+        use crate::t_norms::TNorms;
+        use crate::variables::{InputVariable, TsukamotoOutputVariable};
+
+        fn tsukamoto(slope: f64) -> TsukamotoFIS {
+            let mut x = InputVariable::new("x".to_string(), (0.0, 10.0));
+            x.add_membership(MF::new(
+                "low".to_string(),
+                Kind::Triangle(Triangle::new(-10.0, 0.0, 10.0)),
+            ));
+            x.add_membership(MF::new(
+                "high".to_string(),
+                Kind::Triangle(Triangle::new(0.0, 10.0, 20.0)),
+            ));
+            let mut y = TsukamotoOutputVariable::new("y".to_string(), (0.0, 100.0));
+            y.add_membership(TsukamotoMembershipFunction::new(
+                "small".to_string(),
+                MonotonicKind::LinearZ(LinearZ::new(0.0, 100.0)),
+            ));
+            y.add_membership(TsukamotoMembershipFunction::new(
+                "big".to_string(),
+                MonotonicKind::Sigmoid(Sigmoid::new(slope, 50.0)),
+            ));
+            let mut fis = TsukamotoFIS::new(SNorms::Max, TNorms::Min);
+            fis.add_input(x);
+            fis.add_output(y);
+            fis.add_rule(Rule::new_and(vec![0, 0], 1.0));
+            fis.add_rule(Rule::new_and(vec![1, 1], 1.0));
+            fis
+        }
+
+        #[test]
+        fn weighted_average_of_inverses() {
+            let fis = tsukamoto(0.1);
+            // x = 4 fires `small` with 0.6 and `big` with 0.4
+            // small: 100 - 0.6 * 100 = 40, big: 50 - ln(1 / 0.4 - 1) / 0.1
+            let big = 50.0 - 1.5f64.ln() * 10.0;
+            let expected = (0.6 * 40.0 + 0.4 * big) / (0.6 + 0.4);
+            let output = fis.compute_outputs(vec![4.0])[0];
+            assert!((output - expected).abs() < 1e-9, "{}", output);
+        }
+
+        #[test]
+        fn complemented_consequent() {
+            let mut fis = tsukamoto(0.1);
+            fis.add_rule(Rule::new_and(vec![0, -1], 0.5));
+            let rule_outputs = fis.get_rule_outputs(&[0.6, 0.4, 0.3]);
+            // NOT big reaches 0.3 where big reaches 0.7
+            let expected = 50.0 - (1.0f64 / 0.7 - 1.0).ln() * 10.0;
+            assert!((rule_outputs[0][2] - expected).abs() < 1e-9);
+            // rules that do not fire have no output
+            assert!(fis.get_rule_outputs(&[0.0, 1.0, 0.0])[0][0].is_nan());
+        }
+
+        #[test]
+        fn inverses_stay_in_range() {
+            let fis = tsukamoto(0.05);
+            let y = &fis.get_outputs()[0];
+            // 50 + ln(99) / 0.05 is about 142
+            assert_eq!(y.get_inverse(1, 0.99), 100.0);
+            assert_eq!(y.get_inverse(0, 1.0), 0.0);
+        }
+    }
+
+    mod anfis {
+        use crate::anfis::AnfisTrainer;
+        use crate::defuzzifications::TSKDefuzzifiers;
+        use crate::fuzzy_inference_systems::TSKFIS;
+        use crate::membership_functions::{Gaussian, Kind, MF};
+        use crate::rules::Rule;
+        use crate::s_norms::SNorms;
+        use crate::t_norms::TNorms;
+        use crate::variables::{InputVariable, TSKOutputVariable};
+
+        fn tsk() -> TSKFIS {
+            let mut x = InputVariable::new("x".to_string(), (0.0, 10.0));
+            x.add_membership(MF::new(
+                "low".to_string(),
+                Kind::Gaussian(Gaussian::new(3.0, 2.0)),
+            ));
+            x.add_membership(MF::new(
+                "high".to_string(),
+                Kind::Gaussian(Gaussian::new(7.0, 2.0)),
+            ));
+            let mut y = TSKOutputVariable::new("y".to_string());
+            y.add_constant_membership(0.0);
+            y.add_constant_membership(0.0);
+            let mut fis = TSKFIS::new(SNorms::Max, TNorms::Product, TSKDefuzzifiers::Mean);
+            fis.add_input(x);
+            fis.add_output(y);
+            fis.add_rule(Rule::new_and(vec![0, 0], 1.0));
+            fis.add_rule(Rule::new_and(vec![1, 1], 1.0));
+            fis
+        }
+
+        fn rmse(fis: &TSKFIS, data: &[Vec<f64>]) -> f64 {
+            let sum: f64 = data
+                .iter()
+                .map(|row| (fis.compute_outputs(vec![row[0]])[0] - row[1]).powi(2))
+                .sum();
+            (sum / data.len() as f64).sqrt()
+        }
+
+        #[test]
+        fn error_drops() {
+            let data: Vec<Vec<f64>> = (0..=40)
+                .map(|i| i as f64 / 4.0)
+                .map(|x| vec![x, f64::tanh(x - 5.0)])
+                .collect();
+            let trainer = AnfisTrainer::new(50, 0.5, 0.0, 50);
+            let (trained, history) = trainer.train(&tsk(), &data).unwrap();
+            let errors = history.get_training_errors();
+            assert_eq!(errors.len(), 50);
+            assert!(history.get_validation_errors().is_empty());
+            let best = errors[history.get_best_epoch()];
+            assert_eq!(best, errors.iter().cloned().fold(f64::MAX, f64::min));
+            // with a small enough step every epoch improves the fit
+            assert!(errors.windows(2).all(|w| w[1] < w[0]), "{:?}", errors);
+            assert!(best < errors[0] * 0.6, "{:?}", errors);
+            // the returned system is the best one seen
+            assert!((rmse(&trained, &data) - best).abs() < 1e-9);
+            assert!(rmse(&trained, &data) < rmse(&tsk(), &data));
+        }
+
+        #[test]
+        fn validation_and_patience() {
+            let data: Vec<Vec<f64>> = (0..20).map(|i| vec![i as f64 / 2.0, 1.0]).collect();
+            // a constant target is fitted exactly by the first least squares pass
+            let trainer = AnfisTrainer::new(100, 0.1, 0.25, 3);
+            let (_, history) = trainer.train(&tsk(), &data).unwrap();
+            assert_eq!(history.get_best_epoch(), 0);
+            assert_eq!(history.get_training_errors().len(), 4);
+            assert_eq!(history.get_validation_errors().len(), 4);
+            assert!(history.get_validation_errors()[0] < 1e-6);
+        }
+
+        #[test]
+        fn bad_rows() {
+            let trainer = AnfisTrainer::new(10, 0.1, 0.0, 1);
+            assert!(trainer.train(&tsk(), &[vec![1.0]]).is_err());
+            assert!(AnfisTrainer::try_new(10, 0.1, 1.0, 1).is_err());
+        }
+    }
+
+    mod t_norms {
+        use crate::t_norms::TNorms;
+
+        const GRID: [f64; 7] = [0.0, 0.1, 0.25, 0.5, 0.75, 0.9, 1.0];
+        const EPSILON: f64 = 1e-9;
+
+        pub(super) fn t_norms() -> Vec<TNorms> {
+            vec![
+                TNorms::Min,
+                TNorms::Product,
+                TNorms::Lukasiewicz,
+                TNorms::Drastic,
+                TNorms::NilpotentMin,
+                TNorms::Einstein,
+                TNorms::HamacherProduct,
+                TNorms::Yager(0.5),
+                TNorms::Yager(2.0),
+                TNorms::Frank(0.5),
+                TNorms::Frank(1.0),
+                TNorms::Frank(10.0),
+                TNorms::Dombi(0.5),
+                TNorms::Dombi(3.0),
+                TNorms::SchweizerSklar(-2.0),
+                TNorms::SchweizerSklar(0.0),
+                TNorms::SchweizerSklar(2.0),
+                TNorms::AczelAlsina(0.5),
+                TNorms::AczelAlsina(2.0),
+            ]
+        }
+
+        #[test]
+        fn commutativity() {
+            for t in t_norms() {
+                for a in GRID {
+                    for b in GRID {
+                        let (ab, ba) = (t.t_norm(&[a, b]), t.t_norm(&[b, a]));
+                        assert!((ab - ba).abs() < EPSILON, "{:?} {} {}", t, a, b);
+                    }
+                }
+            }
+        }
+
+        #[test]
+        fn associativity() {
+            for t in t_norms() {
+                for a in GRID {
+                    for b in GRID {
+                        for c in GRID {
+                            let left = t.t_norm(&[t.t_norm(&[a, b]), c]);
+                            let right = t.t_norm(&[a, t.t_norm(&[b, c])]);
+                            let all = t.t_norm(&[a, b, c]);
+                            assert!((left - right).abs() < EPSILON, "{:?} {} {} {}", t, a, b, c);
+                            assert!((left - all).abs() < EPSILON, "{:?} {} {} {}", t, a, b, c);
+                        }
+                    }
+                }
+            }
+        }
+
+        #[test]
+        fn monotonicity() {
+            for t in t_norms() {
+                for a in GRID {
+                    for b in GRID.into_iter().filter(|b| *b >= a) {
+                        for c in GRID {
+                            assert!(
+                                t.t_norm(&[a, c]) <= t.t_norm(&[b, c]) + EPSILON,
+                                "{:?} {} {} {}",
+                                t,
+                                a,
+                                b,
+                                c
+                            );
+                        }
+                    }
+                }
+            }
+        }
+
+        #[test]
+        fn identity() {
+            for t in t_norms() {
+                for a in GRID {
+                    assert!((t.t_norm(&[a, 1.0]) - a).abs() < EPSILON, "{:?} {}", t, a);
+                    assert!(t.t_norm(&[a, 0.0]).abs() < EPSILON, "{:?} {}", t, a);
+                }
+            }
+        }
+    }
+
+    mod s_norms {
+        use super::t_norms::t_norms;
+        use crate::complements::Complements;
+        use crate::s_norms::SNorms;
+        use crate::t_norms::TNorms;
+
+        const GRID: [f64; 7] = [0.0, 0.1, 0.25, 0.5, 0.75, 0.9, 1.0];
+        const EPSILON: f64 = 1e-9;
+
+        // Sugeno's complement with lambda = 1, it is involutive
+        fn sugeno(mu: f64) -> f64 {
+            (1.0 - mu) / (1.0 + mu)
+        }
+
+        #[test]
+        fn dual_of_follows_de_morgan() {
+            for t in t_norms() {
+                let s = SNorms::dual_of(&t);
+                for a in GRID {
+                    for b in GRID {
+                        let expected = 1.0 - t.t_norm(&[1.0 - a, 1.0 - b]);
+                        let found = s.s_norm(&[a, b]);
+                        assert!((found - expected).abs() < EPSILON, "{:?} {} {}", s, a, b);
+                    }
+                }
+            }
+        }
+
+        #[test]
+        fn dual_with_follows_de_morgan() {
+            for t in t_norms() {
+                let standard = SNorms::dual_with(&t, Complements::Standard);
+                let s = SNorms::dual_with(&t, Complements::Custom(sugeno));
+                for a in GRID {
+                    for b in GRID {
+                        let expected = 1.0 - t.t_norm(&[1.0 - a, 1.0 - b]);
+                        let found = standard.s_norm(&[a, b]);
+                        assert!((found - expected).abs() < EPSILON, "{:?} {} {}", t, a, b);
+                        let expected = sugeno(t.t_norm(&[sugeno(a), sugeno(b)]));
+                        let found = s.s_norm(&[a, b]);
+                        assert!((found - expected).abs() < EPSILON, "{:?} {} {}", t, a, b);
+                    }
+                }
+            }
+        }
+
+        #[test]
+        fn named_duals() {
+            let s = SNorms::dual_with(&TNorms::Min, Complements::Standard);
+            assert!(matches!(s, SNorms::Max));
+            assert_eq!(s.s_norm(&[0.2, 0.7, 0.4]), 0.7);
+            let s = SNorms::dual_of(&TNorms::Product);
+            assert!((s.s_norm(&[0.5, 0.5, 0.5]) - 0.875).abs() < EPSILON);
+        }
+    }
+
+    mod complements {
+        use crate::complements::Complements;
+        use crate::error::FuzzyError;
+
+        const GRID: [f64; 7] = [0.0, 0.1, 0.25, 0.5, 0.75, 0.9, 1.0];
+        const EPSILON: f64 = 1e-12;
+
+        #[test]
+        fn checked_parameters() {
+            assert!(matches!(
+                Complements::try_new_sugeno(-1.0),
+                Err(FuzzyError::InvalidParameters(_))
+            ));
+            assert!(matches!(
+                Complements::try_new_yager(0.0),
+                Err(FuzzyError::InvalidParameters(_))
+            ));
+            assert!(matches!(
+                Complements::try_new_yager(-2.0),
+                Err(FuzzyError::InvalidParameters(_))
+            ));
+            assert!(matches!(
+                Complements::try_new_sugeno(-0.5),
+                Ok(Complements::Sugeno(lambda)) if lambda == -0.5
+            ));
+            assert!(matches!(Complements::new_yager(2.0), Complements::Yager(w) if w == 2.0));
+        }
+
+        #[test]
+        #[should_panic(expected = "lambda must be greater than -1")]
+        fn sugeno_panics() {
+            Complements::new_sugeno(-3.0);
+        }
+
+        #[test]
+        fn hand_computed() {
+            // (1 - 0.5) / (1 + 2 * 0.5) and (1 - 0.5^2)^(1/2)
+            let sugeno = Complements::new_sugeno(2.0);
+            assert!((sugeno.complement(0.5) - 0.25).abs() < EPSILON);
+            let yager = Complements::new_yager(2.0);
+            assert!((yager.complement(0.5) - 0.75f64.sqrt()).abs() < EPSILON);
+            assert_eq!(
+                sugeno.complement_interval((0.0, 0.5)),
+                (sugeno.complement(0.5), 1.0)
+            );
+        }
+
+        #[test]
+        fn involutive() {
+            let complements = [
+                Complements::Standard,
+                Complements::new_sugeno(-0.5),
+                Complements::new_sugeno(3.0),
+                Complements::new_yager(0.5),
+                Complements::new_yager(2.0),
+            ];
+            for complement in complements {
+                assert_eq!(complement.complement(0.0), 1.0);
+                assert_eq!(complement.complement(1.0), 0.0);
+                for mu in GRID {
+                    let back = complement.complement(complement.complement(mu));
+                    assert!((back - mu).abs() < 1e-9, "{:?} {}", complement, mu);
+                }
+            }
+        }
+    }
+
+    mod implications {
+        use crate::aggregations::Aggregations;
+        use crate::defuzzifications::Defuzzifiers;
+        use crate::fuzzy_inference_systems::MamdaniFIS;
+        use crate::implications::{Implications, Reasoning};
+        use crate::membership_functions::{Kind, Triangle, MF};
+        use crate::membership_ranges::MembershipRange;
+        use crate::rules::Rule;
+        use crate::s_norms::SNorms;
+        use crate::t_norms::TNorms;
+        use crate::variables::{InputVariable, OutputVariable};
+
+        const EPSILON: f64 = 1e-12;
+
+        // x = 4 fires `small` with 0.6 and `big` with 0.4 over the points 0, 1, 2, 3, 4
+        fn implicative(implication: Implications) -> MamdaniFIS {
+            let mut x = InputVariable::new("x".to_string(), (0.0, 10.0));
+            x.add_membership(MF::new(
+                "low".to_string(),
+                Kind::Triangle(Triangle::new(-10.0, 0.0, 10.0)),
+            ));
+            x.add_membership(MF::new(
+                "high".to_string(),
+                Kind::Triangle(Triangle::new(0.0, 10.0, 20.0)),
+            ));
+            let mut y = OutputVariable::new("y".to_string(), (0.0, 5.0), 5);
+            y.add_membership(MembershipRange::new(
+                "small".to_string(),
+                vec![1.0, 1.0, 0.5, 0.0, 0.0],
+            ));
+            y.add_membership(MembershipRange::new(
+                "big".to_string(),
+                vec![0.0, 0.0, 0.5, 1.0, 1.0],
+            ));
+            let mut fis = MamdaniFIS::new(
+                SNorms::Max,
+                TNorms::Min,
+                implication,
+                Aggregations::Max,
+                Defuzzifiers::Centroid,
+            );
+            fis.set_reasoning(Reasoning::Implicative);
+            fis.add_input(x);
+            fis.add_output(y);
+            fis.add_rule(Rule::new_and(vec![0, 0], 1.0));
+            fis.add_rule(Rule::new_and(vec![1, 1], 1.0));
+            fis
+        }
+
+        fn check(implication: Implications, aggregated: [f64; 5], output: f64) {
+            let fis = implicative(implication);
+            let firing = fis.weighed_inputs(fis.connect_inputs(fis.fuzzification(vec![4.0])));
+            let found = fis.aggregation(fis.implication(firing));
+            for (a, b) in found[0].iter().zip(aggregated) {
+                assert!((a - b).abs() < EPSILON, "{:?}", found[0]);
+            }
+            let found = fis.compute_outputs(vec![4.0])[0];
+            assert!((found - output).abs() < EPSILON, "{}", found);
+        }
+
+        #[test]
+        fn kleene_dienes() {
+            // max(0.4, small) = [1, 1, 0.5, 0.4, 0.4], max(0.6, big) = [0.6, 0.6, 0.6, 1, 1]
+            check(
+                Implications::KleeneDienes,
+                [0.6, 0.6, 0.5, 0.4, 0.4],
+                4.4 / 2.5,
+            );
+        }
+
+        #[test]
+        fn lukasiewicz() {
+            // min(1, 0.4 + small) = [1, 1, 0.9, 0.4, 0.4], min(1, 0.6 + big) = [0.6, 0.6, 1, 1, 1]
+            check(
+                Implications::Lukasiewicz,
+                [0.6, 0.6, 0.9, 0.4, 0.4],
+                5.2 / 2.9,
+            );
+        }
+
+        #[test]
+        fn godel() {
+            // 0.6 <= small gives [1, 1, 0.5, 0, 0], 0.4 <= big gives [0, 0, 1, 1, 1]
+            check(Implications::Godel, [0.0, 0.0, 0.5, 0.0, 0.0], 2.0);
+        }
+
+        #[test]
+        fn conjunctive_is_unchanged() {
+            let mut fis = implicative(Implications::Min);
+            fis.set_reasoning(Reasoning::Conjunctive);
+            let firing = fis.weighed_inputs(fis.connect_inputs(fis.fuzzification(vec![4.0])));
+            // max(min(0.6, small), min(0.4, big))
+            assert_eq!(
+                fis.aggregation(fis.implication(firing))[0],
+                vec![0.6, 0.6, 0.5, 0.4, 0.4]
+            );
+        }
+    }
+
+    mod aggregations {
+        use crate::aggregations::Aggregations;
+        use crate::s_norms::SNorms;
+
+        const EPSILON: f64 = 1e-9;
+
+        // three rules on a universe of four points
+        fn implied() -> Vec<Vec<f64>> {
+            vec![
+                vec![0.0, 0.2, 0.6, 1.0],
+                vec![0.5, 0.5, 0.5, 0.0],
+                vec![0.0, 0.4, 0.8, 0.0],
+            ]
+        }
+
+        fn assert_close(found: Vec<f64>, expected: [f64; 4]) {
+            assert_eq!(found.len(), expected.len());
+            for (f, e) in found.iter().zip(expected) {
+                assert!((f - e).abs() < EPSILON, "{:?} != {:?}", found, expected);
+            }
+        }
+
+        #[test]
+        fn max() {
+            assert_close(
+                Aggregations::Max.aggregation(&implied()),
+                [0.5, 0.5, 0.8, 1.0],
+            );
+        }
+
+        #[test]
+        fn min() {
+            assert_close(
+                Aggregations::Min.aggregation(&implied()),
+                [0.0, 0.2, 0.5, 0.0],
+            );
+        }
+
+        #[test]
+        fn sum() {
+            assert_close(
+                Aggregations::Sum.aggregation(&implied()),
+                [0.5, 1.1, 1.9, 1.0],
+            );
+        }
+
+        #[test]
+        fn bounded_sum() {
+            assert_close(
+                Aggregations::BoundedSum.aggregation(&implied()),
+                [0.5, 1.0, 1.0, 1.0],
+            );
+        }
+
+        #[test]
+        fn probabilistic_or() {
+            // 0.2 + 0.5 - 0.1 = 0.6, 0.6 + 0.4 - 0.24 = 0.76
+            // 0.6 + 0.5 - 0.3 = 0.8, 0.8 + 0.8 - 0.64 = 0.96
+            assert_close(
+                Aggregations::ProbabilisticOr.aggregation(&implied()),
+                [0.5, 0.76, 0.96, 1.0],
+            );
+        }
+
+        #[test]
+        fn normalized_sum() {
+            assert_close(
+                Aggregations::NormalizedSum.aggregation(&implied()),
+                [0.5 / 1.9, 1.1 / 1.9, 1.0, 1.0 / 1.9],
+            );
+            // sums below 1.0 are not scaled up
+            assert_close(
+                Aggregations::NormalizedSum.aggregation(&vec![vec![0.1, 0.2, 0.3, 0.4]]),
+                [0.1, 0.2, 0.3, 0.4],
+            );
+        }
+
+        #[test]
+        fn s_norm() {
+            assert_close(
+                Aggregations::SNorm(SNorms::Max).aggregation(&implied()),
+                [0.5, 0.5, 0.8, 1.0],
+            );
+            assert_close(
+                Aggregations::SNorm(SNorms::ProbabilisticSum).aggregation(&implied()),
+                [0.5, 0.76, 0.96, 1.0],
+            );
+            assert_close(
+                Aggregations::SNorm(SNorms::BoundedSum).aggregation(&implied()),
+                [0.5, 1.0, 1.0, 1.0],
+            );
+        }
+
+        #[test]
+        fn single_rule() {
+            let implied = vec![vec![0.0, 0.3, 1.0, 0.3]];
+            for aggregation in [
+                Aggregations::Max,
+                Aggregations::Min,
+                Aggregations::Sum,
+                Aggregations::BoundedSum,
+                Aggregations::ProbabilisticOr,
+                Aggregations::NormalizedSum,
+                Aggregations::SNorm(SNorms::EinsteinSum),
+            ] {
+                assert_close(aggregation.aggregation(&implied), [0.0, 0.3, 1.0, 0.3]);
+            }
+        }
+    }
+
+    mod defuzzifications {
+        use crate::aggregations::Aggregations;
+        use crate::defuzzifications::{
+            bisection_defuzzification, largest_of_maximum_defuzzification,
+            mean_of_maximum_defuzzification, smallest_of_maximum_defuzzification, Defuzzifiers,
+            Fallbacks,
+        };
+        use crate::error::FuzzyError;
+        use crate::fuzzy_inference_systems::MamdaniFIS;
+        use crate::implications::{Implications, Reasoning};
+        use crate::membership_functions::{Kind, Triangle, MF};
+        use crate::membership_ranges::MembershipRange;
+        use crate::rules::Rule;
+        use crate::s_norms::SNorms;
+        use crate::t_norms::TNorms;
+        use crate::validation::Diagnostic;
+        use crate::variables::{InputVariable, OutputVariable};
+
+        const EPSILON: f64 = 1e-12;
+
+        // x = 4 fires `small` with 0.6 and `big` with 0.4 over the points 0, 1, 2, 3, 4
+        fn mamdani(defuzzifier: Defuzzifiers) -> MamdaniFIS {
+            let mut x = InputVariable::new("x".to_string(), (0.0, 10.0));
+            x.add_membership(MF::new(
+                "low".to_string(),
+                Kind::Triangle(Triangle::new(-10.0, 0.0, 10.0)),
+            ));
+            x.add_membership(MF::new(
+                "high".to_string(),
+                Kind::Triangle(Triangle::new(0.0, 10.0, 20.0)),
+            ));
+            x.add_membership(MF::new(
+                "far".to_string(),
+                Kind::Triangle(Triangle::new(20.0, 30.0, 40.0)),
+            ));
+            let mut y = OutputVariable::new("y".to_string(), (0.0, 5.0), 5);
+            y.add_membership(MembershipRange::new(
+                "small".to_string(),
+                vec![1.0, 1.0, 0.5, 0.0, 0.0],
+            ));
+            y.add_membership(MembershipRange::new(
+                "big".to_string(),
+                vec![0.0, 0.0, 0.5, 1.0, 1.0],
+            ));
+            y.add_membership(MembershipRange::new("none".to_string(), vec![0.0; 5]));
+            let mut fis = MamdaniFIS::new(
+                SNorms::Max,
+                TNorms::Min,
+                Implications::Min,
+                Aggregations::Max,
+                defuzzifier,
+            );
+            fis.add_input(x);
+            fis.add_output(y);
+            fis.add_rule(Rule::new_and(vec![0, 0], 1.0));
+            fis.add_rule(Rule::new_and(vec![1, 1], 1.0));
+            fis
+        }
+
+        fn output(defuzzifier: Defuzzifiers) -> f64 {
+            mamdani(defuzzifier).compute_outputs(vec![4.0])[0]
+        }
+
+        #[test]
+        fn maximum() {
+            let universe = [0.0, 1.0, 2.0, 3.0, 4.0];
+            let set = [0.2, 0.6, 0.6, 0.3, 0.6];
+            assert_eq!(mean_of_maximum_defuzzification(&set, &universe), 7.0 / 3.0);
+            assert_eq!(smallest_of_maximum_defuzzification(&set, &universe), 1.0);
+            assert_eq!(largest_of_maximum_defuzzification(&set, &universe), 4.0);
+            // the aggregated set is [0.6, 0.6, 0.5, 0.4, 0.4]
+            assert_eq!(output(Defuzzifiers::MeanOfMaximum), 0.5);
+            assert_eq!(output(Defuzzifiers::LargestOfMaximum), 1.0);
+        }
+
+        #[test]
+        fn height() {
+            // the peaks of the consequents are 0.5 and 3.5, not the peaks of the clipped sets
+            let expected = 0.6 * 0.5 + 0.4 * 3.5;
+            assert!((output(Defuzzifiers::Height) - expected).abs() < EPSILON);
+        }
+
+        #[test]
+        fn weighted_average() {
+            // the centroids of the consequents are 2 / 2.5 and 8 / 2.5
+            let expected = 0.6 * 0.8 + 0.4 * 3.2;
+            assert!((output(Defuzzifiers::WeightedAverage) - expected).abs() < EPSILON);
+        }
+
+        #[test]
+        fn center_of_sums() {
+            // [0.6, 0.6, 0.5, 0, 0] + [0, 0, 0.4, 0.4, 0.4], the overlap at 2 is counted twice
+            let expected = (0.6 + 2.0 * 0.9 + 3.0 * 0.4 + 4.0 * 0.4) / 2.9;
+            assert!((output(Defuzzifiers::CenterOfSums) - expected).abs() < EPSILON);
+        }
+
+        #[test]
+        fn single_set() {
+            let universe = vec![0.0, 1.0, 2.0, 3.0, 4.0];
+            let set = vec![0.0, 0.5, 1.0, 1.0, 0.0];
+            assert_eq!(Defuzzifiers::Height.defuzzify(set.clone(), &universe), 2.5);
+            assert_eq!(
+                Defuzzifiers::WeightedAverage.defuzzify(set.clone(), &universe),
+                Defuzzifiers::Centroid.defuzzify(set, &universe)
+            );
+        }
+
+        #[test]
+        fn bisection_in_the_first_piece() {
+            // the area of 1 - x from 0 to t is 0.25 at t = 1 - sqrt(0.5)
+            let found =
+                bisection_defuzzification(vec![1.0, 0.0, 0.0, 0.0], &vec![0.0, 1.0, 2.0, 3.0]);
+            assert!((found - (1.0 - 0.5f64.sqrt())).abs() < EPSILON);
+            // a constant set is split in the middle
+            let found = bisection_defuzzification(vec![1.0; 5], &vec![0.0, 1.0, 2.0, 3.0, 4.0]);
+            assert!((found - 2.0).abs() < EPSILON);
+        }
+
+        #[test]
+        fn empty_set_fallback() {
+            let defuzzifiers = [
+                Defuzzifiers::Centroid,
+                Defuzzifiers::Bisection,
+                Defuzzifiers::MeanOfMaximum,
+                Defuzzifiers::WeightedAverage,
+                Defuzzifiers::Height,
+                Defuzzifiers::CenterOfSums,
+            ];
+            for defuzzifier in defuzzifiers {
+                let mut fis = mamdani(defuzzifier);
+                // only this rule fires and its consequent is empty
+                fis.add_rule(Rule::new_and(vec![2, 2], 1.0));
+                assert_eq!(fis.compute_outputs(vec![25.0]), vec![2.5]);
+                fis.set_fallback(Fallbacks::Value(7.0));
+                assert_eq!(fis.compute_outputs(vec![25.0]), vec![7.0]);
+            }
+        }
+
+        #[test]
+        fn rule_based_need_conjunctive_reasoning() {
+            for defuzzifier in [
+                Defuzzifiers::WeightedAverage,
+                Defuzzifiers::Height,
+                Defuzzifiers::CenterOfSums,
+            ] {
+                let mut fis = mamdani(defuzzifier);
+                fis.set_reasoning(Reasoning::Implicative);
+                assert!(matches!(
+                    fis.try_compute_outputs(vec![4.0]),
+                    Err(FuzzyError::InvalidParameters(_))
+                ));
+                assert!(fis
+                    .validate()
+                    .iter()
+                    .any(|d| matches!(d, Diagnostic::Error(FuzzyError::InvalidParameters(_)))));
+            }
+            let mut fis = mamdani(Defuzzifiers::Centroid);
+            fis.set_reasoning(Reasoning::Implicative);
+            assert!(fis.try_compute_outputs(vec![4.0]).is_ok());
+        }
+    }
+
+    mod no_activation {
+        use crate::aggregations::Aggregations;
+        use crate::defuzzifications::{Defuzzifiers, Fallbacks, TSKDefuzzifiers};
+        use crate::error::FuzzyError;
+        use crate::fuzzy_inference_systems::{MamdaniFIS, TsukamotoFIS, TSKFIS};
+        use crate::implications::Implications;
+        use crate::membership_functions::{
+            Kind, LinearZ, MonotonicKind, Triangle, TsukamotoMembershipFunction, MF,
+        };
+        use crate::membership_ranges::MembershipRange;
+        use crate::rules::Rule;
+        use crate::s_norms::SNorms;
+        use crate::t_norms::TNorms;
+        use crate::variables::{
+            InputVariable, NoActivationPolicies, OutputVariable, TSKOutputVariable,
+            TsukamotoOutputVariable,
+        };
+
+        const POLICIES: [NoActivationPolicies; 4] = [
+            NoActivationPolicies::Value(3.0),
+            NoActivationPolicies::HoldLast,
+            NoActivationPolicies::MidRange,
+            NoActivationPolicies::Error,
+        ];
+
+        // x = 0 fires the first rule, x = 10 fires none
+        fn input() -> InputVariable {
+            let mut x = InputVariable::new("x".to_string(), (0.0, 10.0));
+            x.add_membership(MF::new(
+                "low".to_string(),
+                Kind::Triangle(Triangle::new(-10.0, 0.0, 10.0)),
+            ));
+            x.add_membership(MF::new(
+                "far".to_string(),
+                Kind::Triangle(Triangle::new(20.0, 30.0, 40.0)),
+            ));
+            x
+        }
+
+        // `small` has its centroid at 1 and the middle of the range is 5
+        fn mamdani(policy: Option<NoActivationPolicies>) -> MamdaniFIS {
+            let mut y = OutputVariable::new("y".to_string(), (0.0, 10.0), 5);
+            y.add_membership(MembershipRange::new(
+                "small".to_string(),
+                vec![1.0, 1.0, 0.0, 0.0, 0.0],
+            ));
+            y.add_membership(MembershipRange::new(
+                "big".to_string(),
+                vec![0.0, 0.0, 0.0, 1.0, 1.0],
+            ));
+            if let Some(policy) = policy {
+                y.set_no_activation_policy(policy);
+            }
+            let mut fis = MamdaniFIS::with_t_norm(
+                TNorms::Min,
+                Implications::Min,
+                Aggregations::Max,
+                Defuzzifiers::Centroid,
+            );
+            fis.add_input(input());
+            fis.add_output(y);
+            fis.add_rule(Rule::new_and(vec![0, 0], 1.0));
+            fis.add_rule(Rule::new_and(vec![1, 1], 1.0));
+            fis
+        }
+
+        // the consequents are 2 and 6, the middle between them is 4
+        fn tsk(policy: Option<NoActivationPolicies>) -> TSKFIS {
+            let mut y = TSKOutputVariable::new("y".to_string());
+            y.add_constant_membership(2.0);
+            y.add_constant_membership(6.0);
+            if let Some(policy) = policy {
+                y.set_no_activation_policy(policy);
+            }
+            let mut fis = TSKFIS::new(SNorms::Max, TNorms::Min, TSKDefuzzifiers::Mean);
+            fis.add_input(input());
+            fis.add_output(y);
+            fis.add_rule(Rule::new_and(vec![0, 0], 1.0));
+            fis.add_rule(Rule::new_and(vec![1, 1], 1.0));
+            fis
+        }
+
+        fn expected(policy: &NoActivationPolicies, middle: f64) -> Result<f64, FuzzyError> {
+            match policy {
+                NoActivationPolicies::Value(value) => Ok(*value),
+                NoActivationPolicies::HoldLast | NoActivationPolicies::MidRange => Ok(middle),
+                NoActivationPolicies::Error => Err(FuzzyError::ZeroFiringStrength),
+            }
+        }
+
+        #[test]
+        fn mamdani_policies() {
+            for policy in POLICIES {
+                let fis = mamdani(Some(policy.clone()));
+                let fired = fis.compute(vec![0.0]);
+                assert_eq!(fired.get_outputs(), &vec![1.0]);
+                assert!(!fired.used_fallback());
+
+                let result = fis.try_compute(vec![10.0]);
+                match expected(&policy, 5.0) {
+                    Ok(value) => {
+                        let result = result.unwrap();
+                        assert_eq!(result.get_outputs(), &vec![value], "{:?}", policy);
+                        assert!(result.is_fallback(0));
+                    }
+                    Err(err) => assert_eq!(result, Err(err)),
+                }
+                // the stages give the same value for an empty set
+                assert_eq!(
+                    fis.try_defuzzification(vec![vec![0.0; 5]]),
+                    expected(&policy, 5.0).map(|value| vec![value])
+                );
+                assert_eq!(
+                    fis.try_rule_defuzzification(vec![0.0, 0.0]),
+                    expected(&policy, 5.0).map(|value| vec![value])
+                );
+            }
+        }
+
+        #[test]
+        fn tsk_policies() {
+            for policy in POLICIES {
+                let fis = tsk(Some(policy.clone()));
+                let fired = fis.compute(vec![0.0]);
+                assert_eq!(fired.get_outputs(), &vec![2.0]);
+                assert!(!fired.used_fallback());
+
+                let result = fis.try_compute(vec![10.0]);
+                match expected(&policy, 4.0) {
+                    Ok(value) => {
+                        let result = result.unwrap();
+                        assert_eq!(result.get_outputs(), &vec![value], "{:?}", policy);
+                        assert!(result.is_fallback(0));
+                    }
+                    Err(err) => assert_eq!(result, Err(err)),
+                }
+            }
+        }
+
+        #[test]
+        fn hold_last() {
+            let fis = mamdani(Some(NoActivationPolicies::HoldLast));
+            let last = fis.compute(vec![0.0]);
+            let held = fis.compute_holding(vec![10.0], last.get_outputs());
+            assert_eq!(held.get_outputs(), &vec![1.0]);
+            assert!(held.is_fallback(0));
+            // compute keeps nothing between evaluations
+            assert_eq!(fis.compute_outputs(vec![10.0]), vec![5.0]);
+
+            let fis = tsk(Some(NoActivationPolicies::HoldLast));
+            let last = fis.compute(vec![0.0]);
+            let held = fis.compute_holding(vec![10.0], last.get_outputs());
+            assert_eq!(held.get_outputs(), &vec![2.0]);
+            assert_eq!(fis.compute_outputs(vec![10.0]), vec![4.0]);
+        }
+
+        #[test]
+        fn defaults() {
+            // without a policy Mamdani uses the fallback of the system and TSK MidRange
+            let mut fis = mamdani(None);
+            assert_eq!(fis.compute_outputs(vec![10.0]), vec![5.0]);
+            fis.set_fallback(Fallbacks::Value(7.0));
+            assert_eq!(fis.compute_outputs(vec![10.0]), vec![7.0]);
+            assert_eq!(fis.defuzzification(vec![vec![0.0; 5]]), vec![7.0]);
+            // the policy of the output comes first
+            let mut fis = mamdani(Some(NoActivationPolicies::MidRange));
+            fis.set_fallback(Fallbacks::Value(7.0));
+            assert_eq!(fis.compute_outputs(vec![10.0]), vec![5.0]);
+
+            assert_eq!(tsk(None).compute_outputs(vec![10.0]), vec![4.0]);
+        }
+
+        #[test]
+        fn tsukamoto() {
+            let tsukamoto = |policy: Option<NoActivationPolicies>| {
+                let mut y = TsukamotoOutputVariable::new("y".to_string(), (0.0, 100.0));
+                y.add_membership(TsukamotoMembershipFunction::new(
+                    "small".to_string(),
+                    MonotonicKind::LinearZ(LinearZ::new(0.0, 100.0)),
+                ));
+                if let Some(policy) = policy {
+                    y.set_no_activation_policy(policy);
+                }
+                let mut fis = TsukamotoFIS::new(SNorms::Max, TNorms::Min);
+                fis.add_input(input());
+                fis.add_output(y);
+                fis.add_rule(Rule::new_and(vec![0, 0], 1.0));
+                fis
+            };
+            let fis = tsukamoto(None);
+            let result = fis.compute(vec![10.0]);
+            assert_eq!(result.get_outputs(), &vec![50.0]);
+            assert!(result.is_fallback(0));
+            assert!(!fis.compute(vec![0.0]).used_fallback());
+            assert_eq!(
+                tsukamoto(Some(NoActivationPolicies::Error)).try_compute_outputs(vec![10.0]),
+                Err(FuzzyError::ZeroFiringStrength)
+            );
+        }
+    }
+
+    mod output_modes {
+        use crate::aggregations::Aggregations;
+        use crate::defuzzifications::Defuzzifiers;
+        use crate::error::FuzzyError;
+        use crate::fuzzy_inference_systems::MamdaniFIS;
+        use crate::implications::Implications;
+        use crate::membership_functions::{Gaussian, Kind, Triangle, MF};
+        use crate::membership_ranges::MembershipRange;
+        use crate::rules::Rule;
+        use crate::s_norms::SNorms;
+        use crate::t_norms::TNorms;
+        use crate::variables::{InputVariable, OutputModes, OutputVariable};
+
+        const INPUTS: [f64; 5] = [0.5, 2.5, 4.0, 6.5, 9.0];
+
+        fn mamdani(defuzzifier: Defuzzifiers, n: i32, mode: OutputModes) -> MamdaniFIS {
+            let mut x = InputVariable::new("x".to_string(), (0.0, 10.0));
+            x.add_membership(MF::new(
+                "low".to_string(),
+                Kind::Triangle(Triangle::new(-5.0, 0.0, 5.0)),
+            ));
+            x.add_membership(MF::new(
+                "high".to_string(),
+                Kind::Gaussian(Gaussian::new(10.0, 3.0)),
+            ));
+            let mut y = OutputVariable::new("y".to_string(), (0.0, 1.0), n);
+            let universe = y.get_universe().clone();
+            y.add_membership(MembershipRange::new_trapezoid(
+                &universe,
+                "small".to_string(),
+                0.0,
+                0.1,
+                0.2,
+                0.45,
+            ));
+            y.add_membership(MembershipRange::new_triangle(
+                &universe,
+                "big".to_string(),
+                0.3,
+                0.8,
+                1.0,
+            ));
+            y.set_mode(mode);
+            let mut fis = MamdaniFIS::new(
+                SNorms::Max,
+                TNorms::Min,
+                Implications::Min,
+                Aggregations::Max,
+                defuzzifier,
+            );
+            fis.add_input(x);
+            fis.add_output(y);
+            fis.add_rule(Rule::new_and(vec![0, 0], 1.0));
+            fis.add_rule(Rule::new_and(vec![1, 1], 1.0));
+            fis
+        }
+
+        // a continuous output matches a sampled one on a very fine universe
+        fn assert_close(continuous: MamdaniFIS, fine: MamdaniFIS) {
+            for x in INPUTS {
+                let found = continuous.compute_outputs(vec![x])[0];
+                let expected = fine.compute_outputs(vec![x])[0];
+                assert!((found - expected).abs() < 1e-4, "{} != {}", found, expected);
+            }
+        }
+
+        #[test]
+        fn continuous_centroid() {
+            assert_close(
+                mamdani(Defuzzifiers::Centroid, 101, OutputModes::Continuous),
+                mamdani(Defuzzifiers::Centroid, 200_001, OutputModes::Sampled),
+            );
+        }
+
+        #[test]
+        fn continuous_bisection() {
+            assert_close(
+                mamdani(Defuzzifiers::Bisection, 101, OutputModes::Continuous),
+                mamdani(Defuzzifiers::Bisection, 200_001, OutputModes::Sampled),
+            );
+        }
+
+        #[test]
+        fn resolution_does_not_matter() {
+            for x in INPUTS {
+                let coarse = mamdani(Defuzzifiers::Centroid, 11, OutputModes::Continuous);
+                let fine = mamdani(Defuzzifiers::Centroid, 1001, OutputModes::Continuous);
+                assert_eq!(
+                    coarse.compute_outputs(vec![x]),
+                    fine.compute_outputs(vec![x])
+                );
+            }
+        }
+
+        #[test]
+        fn continuous_needs_an_integrating_defuzzifier() {
+            let fis = mamdani(Defuzzifiers::MeanOfMaximum, 101, OutputModes::Continuous);
+            assert!(matches!(
+                fis.try_compute_outputs(vec![2.5]),
+                Err(FuzzyError::InvalidParameters(_))
+            ));
+        }
+    }
+
+    mod sampling {
+        use crate::error::FuzzyError;
+        use crate::membership_functions::{Custom, GetDegree, Kind, Triangle, MF};
+        use crate::membership_ranges::MembershipRange;
+        use crate::variables::OutputVariable;
+
+        fn custom() -> Kind {
+            Kind::Custom(Custom::new(
+                "parabola".to_string(),
+                vec![5.0, 5.0],
+                |x, p| (1.0 - ((x - p[0]) / p[1]).powi(2)).max(0.0),
+            ))
+        }
+
+        #[test]
+        fn from_kind() {
+            let universe = [0.0, 2.5, 5.0, 7.5, 10.0];
+            let mr = MembershipRange::from_kind(
+                &universe,
+                "mid".to_string(),
+                Kind::Triangle(Triangle::new(0.0, 5.0, 10.0)),
+            );
+            assert_eq!(mr.get_mu(), &vec![0.0, 0.5, 1.0, 0.5, 0.0]);
+            assert!(mr.get_kind().is_some());
+
+            let mr = MembershipRange::from_kind(&universe, "parabola".to_string(), custom());
+            assert_eq!(mr.get_mu(), &vec![0.0, 0.75, 1.0, 0.75, 0.0]);
+        }
+
+        #[test]
+        fn from_mf() {
+            let universe = [0.0, 2.5, 5.0, 7.5, 10.0];
+            let mf = MF::new("parabola".to_string(), custom());
+            let mr = MembershipRange::from_mf(&mf, &universe);
+            assert_eq!(mr.get_name(), "parabola");
+            for (x, mu) in universe.iter().zip(mr.get_mu()) {
+                assert_eq!(*mu, mf.get_degree(*x));
+            }
+        }
+
+        #[test]
+        fn resample() {
+            let mut mr = MembershipRange::from_kind(
+                &[0.0, 5.0, 10.0],
+                "mid".to_string(),
+                Kind::Triangle(Triangle::new(0.0, 5.0, 10.0)),
+            );
+            assert!(mr.resample(&[2.5, 5.0]));
+            assert_eq!(mr.get_mu(), &vec![0.5, 1.0]);
+
+            // values without a shape can't be sampled again
+            let mut mr = MembershipRange::new("values".to_string(), vec![0.0, 1.0, 0.0]);
+            assert!(!mr.resample(&[2.5, 5.0]));
+            assert_eq!(mr.get_mu(), &vec![0.0, 1.0, 0.0]);
+        }
+
+        #[test]
+        fn set_resolution() {
+            let mut y = OutputVariable::new("y".to_string(), (0.0, 10.0), 5);
+            y.add_mf(&MF::new(
+                "mid".to_string(),
+                Kind::Triangle(Triangle::new(0.0, 5.0, 10.0)),
+            ));
+            y.add_mf(&MF::new("parabola".to_string(), custom()));
+            y.set_resolution(40);
+            assert_eq!(y.get_universe().len(), 40);
+            for mr in y.get_memberships() {
+                let kind = mr.get_kind().unwrap();
+                assert_eq!(mr.get_mu().len(), 40);
+                for (x, mu) in y.get_universe().iter().zip(mr.get_mu()) {
+                    assert_eq!(*mu, kind.get_degree(*x));
+                }
+            }
+            assert!(matches!(
+                y.try_set_resolution(0),
+                Err(FuzzyError::InvalidParameters(_))
+            ));
+        }
+
+        #[test]
+        fn set_resolution_needs_shapes() {
+            let mut y = OutputVariable::new("y".to_string(), (0.0, 10.0), 5);
+            y.add_mf(&MF::new(
+                "mid".to_string(),
+                Kind::Triangle(Triangle::new(0.0, 5.0, 10.0)),
+            ));
+            y.add_membership(MembershipRange::new(
+                "values".to_string(),
+                vec![1.0, 0.5, 0.0, 0.0, 0.0],
+            ));
+            assert_eq!(
+                y.try_set_resolution(40),
+                Err(FuzzyError::MissingShape {
+                    variable: "y".to_string(),
+                    term: "values".to_string(),
+                })
+            );
+            // nothing is changed
+            assert_eq!(y.get_universe().len(), 5);
+            assert_eq!(y.get_mu(0).len(), 5);
+        }
+    }
+
+    mod relations {
+        use crate::aggregations::Aggregations;
+        use crate::defuzzifications::{Defuzzifiers, Fallbacks};
+        use crate::error::FuzzyError;
+        use crate::fuzzy_inference_systems::MamdaniFIS;
+        use crate::implications::Implications;
+        use crate::membership_functions::{Kind, Triangle, MF};
+        use crate::s_norms::SNorms;
+        use crate::t_norms::TNorms;
+        use crate::validation::Diagnostic;
+        use crate::variables::{InputVariable, OutputModes, OutputVariable};
+
+        // x = 4 is `low` with 0.6 and w = 8 is `low` with 0.2, `z` is not used by any rule
+        fn mamdani(defuzzifier: Defuzzifiers, mode: OutputModes) -> MamdaniFIS {
+            let mut fis = MamdaniFIS::new(
+                SNorms::Max,
+                TNorms::Min,
+                Implications::Min,
+                Aggregations::Max,
+                defuzzifier,
+            );
+            for name in ["x", "w"] {
+                let mut input = InputVariable::new(name.to_string(), (0.0, 10.0));
+                input.add_membership(MF::new(
+                    "low".to_string(),
+                    Kind::Triangle(Triangle::new(0.0, 0.0, 10.0)),
+                ));
+                fis.add_input(input);
+            }
+            for name in ["y", "z"] {
+                let mut output = OutputVariable::new(name.to_string(), (0.0, 10.0), 101);
+                output.add_mf(&MF::new(
+                    "small".to_string(),
+                    Kind::Triangle(Triangle::new(0.0, 2.0, 4.0)),
+                ));
+                output.set_mode(mode);
+                fis.add_output(output);
+            }
+            fis.set_fallback(Fallbacks::Value(7.0));
+            fis
+        }
+
+        fn firing(text: &str) -> f64 {
+            let mut fis = mamdani(Defuzzifiers::Centroid, OutputModes::Sampled);
+            fis.add_text_rule(text).unwrap();
+            let fuzzified = fis.fuzzification(vec![4.0, 8.0]);
+            fis.weighed_inputs(fis.connect_inputs(fuzzified))[0]
+        }
+
+        #[test]
+        fn input_relations() {
+            let close = |a: f64, b: f64| (a - b).abs() < 1e-12;
+            assert!(close(firing("IF x IS low THEN y IS small"), 0.6));
+            assert!(close(firing("IF x IS NOT low THEN y IS small"), 0.4));
+            assert!(close(firing("IF x IS VERY low THEN y IS small"), 0.36));
+            assert!(close(
+                firing("IF x IS SOMEWHAT low THEN y IS small"),
+                0.6f64.sqrt()
+            ));
+            assert!(close(
+                firing("IF x IS low AND w IS low THEN y IS small"),
+                0.2
+            ));
+            assert!(close(firing("IF ANY THEN y IS small"), 1.0));
+        }
+
+        #[test]
+        fn unused_outputs_use_the_fallback() {
+            for (defuzzifier, mode) in [
+                (Defuzzifiers::Centroid, OutputModes::Sampled),
+                (Defuzzifiers::Centroid, OutputModes::Continuous),
+                (Defuzzifiers::CenterOfSums, OutputModes::Sampled),
+                (Defuzzifiers::WeightedAverage, OutputModes::Sampled),
+            ] {
+                let mut fis = mamdani(defuzzifier, mode);
+                fis.add_text_rule("IF x IS low THEN y IS small").unwrap();
+                let result = fis.compute(vec![4.0, 8.0]);
+                assert!(!result.is_fallback(0));
+                assert!(result.is_fallback(1));
+                assert_eq!(result.get_outputs()[1], 7.0);
+            }
+        }
+
+        #[test]
+        fn unused_outputs_are_errors() {
+            let mut fis = mamdani(Defuzzifiers::Centroid, OutputModes::Sampled);
+            fis.add_text_rule("IF x IS low THEN y IS small").unwrap();
+            let unused = Diagnostic::Error(FuzzyError::UnusedOutput {
+                variable: "z".to_string(),
+            });
+            assert!(fis.validate().contains(&unused));
+        }
+    }
+
+    mod expressions {
+        use crate::aggregations::Aggregations;
+        use crate::complements::Complements;
+        use crate::defuzzifications::{Defuzzifiers, TSKDefuzzifiers};
+        use crate::fuzzy_inference_systems::{MamdaniFIS, TSKFIS};
+        use crate::implications::Implications;
+        use crate::membership_functions::{Kind, Triangle, MF};
+        use crate::membership_ranges::MembershipRange;
+        use crate::rules::{Expression, Relation, Rule};
+        use crate::s_norms::SNorms;
+        use crate::t_norms::TNorms;
+        use crate::variables::{InputVariable, OutputVariable, TSKOutputVariable};
+
+        // x = 4 is `low` with 0.6 and `high` with 0.4, w = 8 is `low` with 0.2 and `high` with 0.8
+        fn inputs() -> Vec<InputVariable> {
+            let mut inputs = Vec::new();
+            for name in ["x", "w"] {
+                let mut input = InputVariable::new(name.to_string(), (0.0, 10.0));
+                input.add_membership(MF::new(
+                    "low".to_string(),
+                    Kind::Triangle(Triangle::new(0.0, 0.0, 10.0)),
+                ));
+                input.add_membership(MF::new(
+                    "high".to_string(),
+                    Kind::Triangle(Triangle::new(0.0, 10.0, 10.0)),
+                ));
+                inputs.push(input);
+            }
+            inputs
+        }
+
+        const RULES: [(&str, f64); 3] = [
+            // max(0.6, 0.8) * (1 - 0.2), the system t-norm would give 0.8
+            (
+                "IF (x IS low OR w IS high) AND[PRODUCT] NOT w IS low THEN y IS small",
+                0.64,
+            ),
+            // 0.6 + 0.2 - 0.6 * 0.2 with 0.6 = 1 - min(0.4, 0.8)
+            (
+                "IF NOT (x IS high AND w IS high) OR[PROBABILISTIC_SUM] w IS low THEN y IS small",
+                0.68,
+            ),
+            // min(0.6, max(0.2, 0.4))
+            (
+                "IF x IS low AND (w IS low OR x IS high) THEN y IS small",
+                0.4,
+            ),
+        ];
+
+        fn close(a: f64, b: f64) -> bool {
+            (a - b).abs() < 1e-12
+        }
+
+        #[test]
+        fn mamdani_firing() {
+            let mut fis = MamdaniFIS::new(
+                SNorms::Max,
+                TNorms::Min,
+                Implications::Min,
+                Aggregations::Max,
+                Defuzzifiers::Centroid,
+            );
+            for input in inputs() {
+                fis.add_input(input);
+            }
+            let mut y = OutputVariable::new("y".to_string(), (0.0, 10.0), 11);
+            let universe = y.get_universe().clone();
+            y.add_membership(MembershipRange::new_triangle(
+                &universe,
+                "small".to_string(),
+                0.0,
+                2.0,
+                4.0,
+            ));
+            fis.add_output(y);
+            for (text, _) in RULES {
+                fis.add_text_rule(text).unwrap();
+            }
+            let fuzzified = fis.fuzzification(vec![4.0, 8.0]);
+            let firing = fis.weighed_inputs(fis.connect_inputs(fuzzified));
+            for (mu, (_, expected)) in firing.into_iter().zip(RULES) {
+                assert!(close(mu, expected));
+            }
+        }
+
+        #[test]
+        fn tsk_firing() {
+            let mut fis = TSKFIS::new(SNorms::Max, TNorms::Min, TSKDefuzzifiers::Mean);
+            for input in inputs() {
+                fis.add_input(input);
+            }
+            let mut y = TSKOutputVariable::new("y".to_string());
+            y.add_constant_membership(1.0);
+            fis.add_output(y);
+            for (text, _) in RULES {
+                fis.add_text_rule(&text.replace("small", "y1")).unwrap();
+            }
+            let fuzzified = fis.fuzzification(vec![4.0, 8.0]);
+            let firing = fis.weighed_inputs(fis.connect_inputs(fuzzified));
+            for (mu, (_, expected)) in firing.into_iter().zip(RULES) {
+                assert!(close(mu, expected));
+            }
+        }
+
+        #[test]
+        fn round_trip() {
+            let inputs = inputs();
+            let mut y = OutputVariable::new("y".to_string(), (0.0, 10.0), 11);
+            y.add_membership(MembershipRange::new("small".to_string(), vec![1.0; 11]));
+            let outputs = vec![y];
+            for (text, _) in RULES {
+                let rule = Rule::parse(text, &inputs, &outputs).unwrap();
+                assert_eq!(rule.to_text(&inputs, &outputs), text);
+            }
+            let text =
+                "IF x IS low OR[YAGER 2] x IS high AND[DOMBI 0.5] w IS VERY low THEN y IS small";
+            let rule = Rule::parse(text, &inputs, &outputs).unwrap();
+            let printed = rule.to_text(&inputs, &outputs);
+            assert_eq!(
+                printed,
+                "IF x IS low OR[YAGER 2] (x IS high AND[DOMBI 0.5] w IS VERY low) THEN y IS small"
+            );
+            let again = Rule::parse(&printed, &inputs, &outputs).unwrap();
+            assert_eq!(again.to_text(&inputs, &outputs), printed);
+        }
+
+        #[test]
+        fn evaluate() {
+            // max(0.2, 0.5 * (1 - 0.4))
+            let expression = Expression::or(vec![
+                Expression::is(0, Relation::Term(0)),
+                Expression::And(
+                    vec![
+                        Expression::is(1, Relation::Term(1)),
+                        Expression::negate(Expression::is(0, Relation::Term(2))),
+                    ],
+                    Some(TNorms::Product),
+                ),
+            ]);
+            let mu = expression.evaluate(
+                &mut vec![0.2, 0.5, 0.4].into_iter(),
+                &SNorms::Max,
+                &TNorms::Min,
+                &Complements::Standard,
+            );
+            assert!(close(mu, 0.3));
+        }
+
+        #[test]
+        #[should_panic(expected = "a degree for every leaf")]
+        fn missing_degrees() {
+            let expression = Expression::and(vec![
+                Expression::is(0, Relation::Term(0)),
+                Expression::is(1, Relation::Term(0)),
+            ]);
+            expression.evaluate(
+                &mut vec![0.5].into_iter(),
+                &SNorms::Max,
+                &TNorms::Min,
+                &Complements::Standard,
+            );
+        }
+    }
+
+    mod tsk_rules {
+        use crate::defuzzifications::TSKDefuzzifiers;
+        use crate::error::FuzzyError;
+        use crate::fuzzy_inference_systems::{IntervalType2TSKFIS, TSKFIS};
+        use crate::membership_functions::{
+            Footprint, IntervalType2Kind, Kind, TSKMembershipFunction, Triangle, IT2MF, MF,
+        };
+        use crate::rules::{OutputRelation, Relation, TSKRule};
+        use crate::s_norms::SNorms;
+        use crate::t_norms::TNorms;
+        use crate::type_reductions::TypeReducers;
+        use crate::variables::{InputVariable, IntervalType2InputVariable, TSKOutputVariable};
+
+        // x = 4 is `low` with 0.6 and `high` with 0.4
+        fn triangles() -> [(&'static str, Kind); 2] {
+            [
+                ("low", Kind::Triangle(Triangle::new(-10.0, 0.0, 10.0))),
+                ("high", Kind::Triangle(Triangle::new(0.0, 10.0, 20.0))),
+            ]
+        }
+
+        // `y` is 2 for the first rule and 6 for the second, `z` is 10
+        fn outputs() -> Vec<TSKOutputVariable> {
+            let mut y = TSKOutputVariable::new("y".to_string());
+            y.add_constant_membership(2.0);
+            y.add_constant_membership(6.0);
+            let mut z = TSKOutputVariable::new("z".to_string());
+            z.add_constant_membership(10.0);
+            vec![y, z]
+        }
+
+        fn tsk() -> TSKFIS {
+            let mut fis = TSKFIS::new(SNorms::Max, TNorms::Min, TSKDefuzzifiers::Mean);
+            let mut x = InputVariable::new("x".to_string(), (0.0, 10.0));
+            for (name, kind) in triangles() {
+                x.add_membership(MF::new(name.to_string(), kind));
+            }
+            fis.add_input(x);
+            for output in outputs() {
+                fis.add_output(output);
+            }
+            fis
+        }
+
+        fn close(a: f64, b: f64) -> bool {
+            (a - b).abs() < 1e-12
+        }
+
+        #[test]
+        fn inline_consequents() {
+            let mut fis = tsk();
+            fis.add_tsk_rule(TSKRule::new_and(
+                vec![0],
+                vec![
+                    TSKMembershipFunction::Constant(2.0).into(),
+                    TSKMembershipFunction::Custom(|x: &Vec<f64>| x[0] + 1.0).into(),
+                ],
+                1.0,
+            ));
+            fis.add_tsk_rule(TSKRule::new_and(
+                vec![1],
+                vec![
+                    TSKMembershipFunction::Linear(vec![2.0]).into(),
+                    OutputRelation::Term(Relation::Term(0)),
+                ],
+                1.0,
+            ));
+            // y = 0.6 * 2 + 0.4 * 8, z = 0.6 * 5 + 0.4 * 10
+            let outputs = fis.compute_outputs(vec![4.0]);
+            assert!(close(outputs[0], 4.4));
+            assert!(close(outputs[1], 7.0));
+        }
+
+        #[test]
+        fn dont_care_outputs() {
+            let mut fis = tsk();
+            fis.add_text_rule("IF x IS low THEN y IS y1").unwrap();
+            fis.add_text_rule("IF x IS high THEN y IS y2 AND z IS z1")
+                .unwrap();
+            assert_eq!(
+                fis.get_output_rules(0)[1],
+                OutputRelation::Term(Relation::Any)
+            );
+            assert_eq!(fis.get_mu(&vec![4.0])[1], vec![None, Some(10.0)]);
+            // `z` only has the second rule, so it is 10 and not 0.4 * 10
+            let outputs = fis.compute_outputs(vec![4.0]);
+            assert!(close(outputs[0], 0.6 * 2.0 + 0.4 * 6.0));
+            assert!(close(outputs[1], 10.0));
+            // at 0 the only rule of `z` doesn't fire
+            let result = fis.compute(vec![0.0]);
+            assert!(!result.is_fallback(0));
+            assert!(result.is_fallback(1));
+        }
+
+        #[test]
+        fn interval_dont_care_outputs() {
+            let mut fis =
+                IntervalType2TSKFIS::new(SNorms::Max, TNorms::Min, TypeReducers::KarnikMendel);
+            let mut x = IntervalType2InputVariable::new("x".to_string(), (0.0, 10.0));
+            for (name, kind) in triangles() {
+                let footprint = Footprint::new(kind.clone(), kind, 1.0);
+                x.add_membership(IT2MF::new(
+                    name.to_string(),
+                    IntervalType2Kind::Footprint(footprint),
+                ));
+            }
+            fis.add_input(x);
+            for output in outputs() {
+                fis.add_output(output);
+            }
+            fis.add_text_rule("IF x IS low THEN y IS y1").unwrap();
+            fis.add_text_rule("IF x IS high THEN y IS y2 AND z IS z1")
+                .unwrap();
+            assert_eq!(fis.get_mu(&vec![4.0])[1], vec![None, Some(10.0)]);
+            let outputs = fis.compute_outputs(vec![4.0]);
+            assert!(close(outputs[0], 0.6 * 2.0 + 0.4 * 6.0));
+            assert!(close(outputs[1], 10.0));
+            assert_eq!(
+                fis.try_compute_outputs(vec![0.0]),
+                Err(FuzzyError::ZeroFiringStrength)
+            );
+        }
+    }
+
+    mod tsk_consequents {
+        use crate::defuzzifications::TSKDefuzzifiers;
+        use crate::error::FuzzyError;
+        use crate::fuzzy_inference_systems::TSKFIS;
+        use crate::membership_functions::{
+            Kind, Polynomial, Quadratic, TSKMembershipFunction, Triangle, MF,
+        };
+        use crate::rules::Rule;
+        use crate::s_norms::SNorms;
+        use crate::t_norms::TNorms;
+        use crate::validation::Diagnostic;
+        use crate::variables::{InputVariable, TSKOutputVariable};
+
+        fn quadratic() -> Quadratic {
+            Quadratic::new(vec![vec![1.0, 0.0], vec![2.0, 1.0]], vec![1.0, 1.0], 1.0)
+        }
+
+        #[test]
+        fn values() {
+            let x = vec![2.0, 3.0];
+            // 2 + 2 * 3 + 3
+            let affine = TSKMembershipFunction::Affine(vec![1.0, 2.0, 3.0]);
+            assert_eq!(affine.get_mu(&x), 11.0);
+            // x^2 + 2xy + y^2 + x + y + 1
+            assert_eq!(quadratic().get_mu(&x), 31.0);
+            // 2x^2 + xy + 3
+            let polynomial = Polynomial::new(2, 2, vec![2.0, 1.0, 0.0, 0.0, 0.0, 3.0]);
+            assert_eq!(polynomial.get_mu(&x), 17.0);
+            assert_eq!(
+                Polynomial::monomials(2, 2),
+                vec![vec![0, 0], vec![0, 1], vec![1, 1], vec![0], vec![1], vec![]]
+            );
+            // degree 1 is affine
+            let linear = Polynomial::new(2, 1, vec![1.0, 2.0, 3.0]);
+            assert_eq!(linear.get_mu(&x), 11.0);
+        }
+
+        #[test]
+        fn shapes() {
+            assert!(Quadratic::try_new(vec![vec![1.0, 0.0]], vec![1.0, 1.0], 1.0).is_err());
+            assert!(Polynomial::try_new(2, 2, vec![1.0; 5]).is_err());
+        }
+
+        #[test]
+        fn consequents_of_an_output_agree() {
+            let mut y = TSKOutputVariable::new("y".to_string());
+            y.add_constant_membership(1.0);
+            y.add_linear_membership(vec![1.0, 1.0]);
+            assert_eq!(y.get_input_size(), Some(2));
+            assert_eq!(
+                y.try_add_affine_membership(vec![1.0, 1.0]),
+                Err(FuzzyError::ConsequentInputs {
+                    variable: "y".to_string(),
+                    term: "y3".to_string(),
+                    expected: 2,
+                    found: 1,
+                })
+            );
+            assert!(y
+                .try_add_membership(TSKMembershipFunction::Quadratic(quadratic()))
+                .is_ok());
+            let polynomial = Polynomial::new(3, 1, vec![1.0; 4]);
+            assert!(y
+                .try_add_membership(TSKMembershipFunction::Polynomial(polynomial))
+                .is_err());
+            assert!(y.try_add_affine_membership(Vec::new()).is_err());
+            assert_eq!(y.get_memberships().len(), 3);
+        }
+
+        #[test]
+        #[should_panic(expected = "Term `y2` of `y` is made for 2 inputs but 1 are expected")]
+        fn add_affine_membership_panics() {
+            let mut y = TSKOutputVariable::new("y".to_string());
+            y.add_affine_membership(vec![1.0, 0.0]);
+            y.add_affine_membership(vec![1.0, 1.0, 0.0]);
+        }
+
+        #[test]
+        fn consequents_fit_the_inputs() {
+            let mut fis = TSKFIS::new(SNorms::Max, TNorms::Min, TSKDefuzzifiers::Mean);
+            let mut x = InputVariable::new("x".to_string(), (0.0, 10.0));
+            x.add_membership(MF::new(
+                "low".to_string(),
+                Kind::Triangle(Triangle::new(-10.0, 0.0, 10.0)),
+            ));
+            fis.add_input(x);
+            let mut y = TSKOutputVariable::new("y".to_string());
+            y.add_affine_membership(vec![1.0, 1.0, 0.0]);
+            fis.add_output(y);
+            fis.add_rule(Rule::new_and(vec![0, 0], 1.0));
+            let err = FuzzyError::ConsequentInputs {
+                variable: "y".to_string(),
+                term: "y1".to_string(),
+                expected: 1,
+                found: 2,
+            };
+            assert_eq!(fis.try_compute_outputs(vec![4.0]), Err(err.clone()));
+            assert!(fis.validate().contains(&Diagnostic::Error(err)));
+        }
+    }
+
+    mod tsk_defuzzifications {
+        use crate::defuzzifications::TSKDefuzzifiers;
+        use crate::fuzzy_inference_systems::TSKFIS;
+        use crate::membership_functions::{Kind, Triangle, MF};
+        use crate::rules::Rule;
+        use crate::s_norms::SNorms;
+        use crate::t_norms::TNorms;
+        use crate::variables::{InputVariable, TSKOutputVariable};
+
+        // x = 4 fires the rules with 0.6 and 0.4 * 0.5, both outputs have the consequents 2 and 6
+        fn tsk(
+            defuzzification: TSKDefuzzifiers,
+            y_defuzzification: Option<TSKDefuzzifiers>,
+        ) -> TSKFIS {
+            let mut fis = TSKFIS::new(SNorms::Max, TNorms::Min, defuzzification);
+            let mut x = InputVariable::new("x".to_string(), (0.0, 10.0));
+            x.add_membership(MF::new(
+                "low".to_string(),
+                Kind::Triangle(Triangle::new(-10.0, 0.0, 10.0)),
+            ));
+            x.add_membership(MF::new(
+                "high".to_string(),
+                Kind::Triangle(Triangle::new(0.0, 10.0, 20.0)),
+            ));
+            fis.add_input(x);
+            for name in ["y", "z"] {
+                let mut output = TSKOutputVariable::new(name.to_string());
+                output.add_constant_membership(2.0);
+                output.add_constant_membership(6.0);
+                if let (Some(own), "y") = (&y_defuzzification, name) {
+                    output.set_defuzzification(own.clone());
+                }
+                fis.add_output(output);
+            }
+            fis.add_rule(Rule::new_and(vec![0, 0, 0], 1.0));
+            fis.add_rule(Rule::new_and(vec![1, 1, 1], 0.5));
+            fis
+        }
+
+        fn close(a: f64, b: f64) -> bool {
+            (a - b).abs() < 1e-9
+        }
+
+        #[test]
+        fn mean() {
+            // (0.6 * 2 + 0.2 * 6) / 0.8
+            let outputs = tsk(TSKDefuzzifiers::Mean, None).compute_outputs(vec![4.0]);
+            assert!(close(outputs[0], 3.0));
+        }
+
+        #[test]
+        fn sum() {
+            // 0.6 * 2 + 0.2 * 6
+            let outputs = tsk(TSKDefuzzifiers::Sum, None).compute_outputs(vec![4.0]);
+            assert!(close(outputs[0], 2.4));
+            assert!(close(outputs[1], 2.4));
+        }
+
+        #[test]
+        fn softmax() {
+            // (2 e^0.6 + 6 e^0.2) / (e^0.6 + e^0.2)
+            let outputs = tsk(TSKDefuzzifiers::Softmax, None).compute_outputs(vec![4.0]);
+            assert!(close(outputs[0], 3.605249359550192));
+        }
+
+        #[test]
+        fn own_defuzzification() {
+            let fis = tsk(TSKDefuzzifiers::Mean, Some(TSKDefuzzifiers::Sum));
+            assert!(matches!(
+                fis.get_outputs()[0].get_defuzzification_method(),
+                Some(TSKDefuzzifiers::Sum)
+            ));
+            assert!(fis.get_outputs()[1].get_defuzzification_method().is_none());
+            let outputs = fis.compute_outputs(vec![4.0]);
+            assert!(close(outputs[0], 2.4));
+            assert!(close(outputs[1], 3.0));
+        }
+    }
+
+    mod trace {
+        use crate::aggregations::Aggregations;
+        use crate::defuzzifications::{Defuzzifiers, TSKDefuzzifiers};
+        use crate::fuzzy_inference_systems::{MamdaniFIS, TSKFIS};
+        use crate::implications::Implications;
+        use crate::membership_functions::{Kind, Triangle, MF};
+        use crate::membership_ranges::MembershipRange;
+        use crate::s_norms::SNorms;
+        use crate::t_norms::TNorms;
+        use crate::trace::OutputStage;
+        use crate::variables::{
+            InputVariable, NoActivationPolicies, OutputModes, OutputVariable, TSKOutputVariable,
+        };
+
+        // x = 4 is `low` with 0.6 and `high` with 0.4
+        fn input() -> InputVariable {
+            let mut x = InputVariable::new("x".to_string(), (0.0, 10.0));
+            x.add_membership(MF::new(
+                "low".to_string(),
+                Kind::Triangle(Triangle::new(-10.0, 0.0, 10.0)),
+            ));
+            x.add_membership(MF::new(
+                "high".to_string(),
+                Kind::Triangle(Triangle::new(0.0, 10.0, 20.0)),
+            ));
+            x
+        }
+
+        fn mamdani(mode: OutputModes) -> MamdaniFIS {
+            let mut fis = MamdaniFIS::new(
+                SNorms::Max,
+                TNorms::Min,
+                Implications::Min,
+                Aggregations::Max,
+                Defuzzifiers::Centroid,
+            );
+            fis.add_input(input());
+            let mut y = OutputVariable::new("y".to_string(), (0.0, 10.0), 10);
+            let universe = y.get_universe().clone();
+            for (term, b) in [("small", 2.0), ("big", 8.0)] {
+                y.add_membership(MembershipRange::new_triangle(
+                    &universe,
+                    term.to_string(),
+                    b - 2.0,
+                    b,
+                    b + 2.0,
+                ));
+            }
+            y.set_mode(mode);
+            fis.add_output(y);
+            fis.add_text_rule("IF x IS low THEN y IS small").unwrap();
+            fis.add_text_rule("IF x IS high THEN y IS big WITH 0.5")
+                .unwrap();
+            fis
+        }
+
+        fn close(a: f64, b: f64) -> bool {
+            (a - b).abs() < 1e-12
+        }
+
+        #[test]
+        fn mamdani_stages() {
+            let fis = mamdani(OutputModes::Sampled);
+            let trace = fis.compute_outputs_traced(vec![4.0]);
+            assert_eq!(trace.get_values(), fis.compute_outputs(vec![4.0]));
+
+            let input = &trace.get_inputs()[0];
+            assert_eq!(input.get_value(), 4.0);
+            assert_eq!(input.get_degrees()[0].0, "low");
+            assert!(close(input.get_degrees()[0].1, 0.6));
+            assert!(close(input.get_degrees()[1].1, 0.4));
+
+            let rules = trace.get_rules();
+            assert_eq!(rules[1].get_text(), "IF x IS high THEN y IS big WITH 0.5");
+            assert!(close(rules[1].get_firing(), 0.4));
+            assert!(close(rules[1].get_weighted(), 0.2));
+
+            let output = &trace.get_outputs()[0];
+            assert!(!output.is_fallback());
+            match output.get_stage() {
+                OutputStage::Sets {
+                    universe,
+                    implied,
+                    aggregated,
+                    continuous,
+                } => {
+                    assert_eq!(universe.len(), 10);
+                    assert!(!continuous);
+                    // min implication cuts the terms at 0.6 and 0.2
+                    let small = implied[0].as_ref().unwrap();
+                    let big = implied[1].as_ref().unwrap();
+                    assert!(close(small[2], 0.6));
+                    assert!(close(small[1], 0.5));
+                    assert!(close(big[8], 0.2));
+                    let max: Vec<f64> = small.iter().zip(big).map(|(a, b)| a.max(*b)).collect();
+                    assert_eq!(aggregated, &max);
+                }
+                stage => panic!("expected sets, found {:?}", stage),
+            }
+        }
+
+        #[test]
+        fn table() {
+            let text = mamdani(OutputModes::Sampled)
+                .compute_outputs_traced(vec![4.0])
+                .to_string();
+            let lines: Vec<&str> = text.lines().collect();
+            assert_eq!(
+                &lines[..7],
+                &[
+                    "inputs",
+                    "  x = 4",
+                    "    low   0.6000",
+                    "    high  0.4000",
+                    "rules",
+                    "  #    firing  weighted       y  rule",
+                    "  0    0.6000    0.6000  0.6000  IF x IS low THEN y IS small",
+                ]
+            );
+            assert_eq!(
+                lines[7],
+                "  1    0.4000    0.2000  0.2000  IF x IS high THEN y IS big WITH 0.5"
+            );
+            assert_eq!(lines[8], "outputs");
+            assert!(lines[9].starts_with("  y = "));
+        }
+
+        #[test]
+        fn continuous_sets_are_marked() {
+            let fis = mamdani(OutputModes::Continuous);
+            let trace = fis.compute_outputs_traced(vec![4.0]);
+            assert_eq!(trace.get_values(), fis.compute_outputs(vec![4.0]));
+            assert!(matches!(
+                trace.get_outputs()[0].get_stage(),
+                OutputStage::Sets {
+                    continuous: true,
+                    ..
+                }
+            ));
+            assert!(trace
+                .to_string()
+                .contains("(continuous, the sets are sampled)"));
+        }
+
+        fn tsk() -> TSKFIS {
+            let mut fis = TSKFIS::new(SNorms::Max, TNorms::Min, TSKDefuzzifiers::Mean);
+            fis.add_input(input());
+            let mut y = TSKOutputVariable::new("y".to_string());
+            y.add_constant_membership(2.0);
+            y.add_constant_membership(6.0);
+            y.set_no_activation_policy(NoActivationPolicies::HoldLast);
+            fis.add_output(y);
+            let mut z = TSKOutputVariable::new("z".to_string());
+            z.add_constant_membership(10.0);
+            fis.add_output(z);
+            fis.add_text_rule("IF x IS low THEN y IS y1").unwrap();
+            fis.add_text_rule("IF x IS high THEN y IS y2 AND z IS z1")
+                .unwrap();
+            fis
+        }
+
+        #[test]
+        fn tsk_consequents() {
+            let trace = tsk().compute_outputs_traced(vec![4.0]);
+            assert_eq!(
+                trace.get_outputs()[1].get_stage(),
+                &OutputStage::Consequents(vec![None, Some(10.0)])
+            );
+            let text = trace.to_string();
+            assert!(
+                text.contains("  0    0.6000    0.6000  2.0000       -  IF x IS low THEN y IS y1")
+            );
+            assert!(text.contains("  z = 10"));
+        }
+
+        #[test]
+        fn held_outputs() {
+            let fis = tsk();
+            // nothing fires at -20, `y` holds the last value and `z` is the middle of its consequents
+            let trace = fis.compute_traced_holding(vec![-20.0], &[7.0, 1.0]);
+            assert_eq!(trace.get_values(), vec![7.0, 10.0]);
+            assert!(trace.get_outputs()[0].is_fallback());
+            assert!(trace.to_string().contains("  y = 7 (fallback)"));
+            // tracing keeps no state, without a last value `y` is the middle of 2 and 6
+            let trace = fis.compute_outputs_traced(vec![-20.0]);
+            assert_eq!(trace.get_values(), vec![4.0, 10.0]);
+        }
+    }
+}
@@ -1,4 +1,5 @@
 // pub mod membership_functions;
+use crate::error::{check, FuzzyError};
 
 pub trait GetDegree {
     fn get_degree(&self, x: f64) -> f64;
@@ -6,17 +7,21 @@ pub trait GetDegree {
 
 #[allow(dead_code)]
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Kind {
     Triangle(Triangle),
     Trapezoid(Trapezoid),
     LinearZ(LinearZ),
     LinearS(LinearS),
+    SplineZ(SplineZ),
+    SplineS(SplineS),
     StepDown(StepDown),
     StepUp(StepUp),
     Gaussian(Gaussian),
     DoubleGaussian(DoubleGaussian),
     Bell(Bell),
     Normal(Gaussian),
+    #[cfg_attr(feature = "serde", serde(skip))]
     Custom(Custom),
 }
 impl GetDegree for Kind {
@@ -26,13 +31,35 @@ impl GetDegree for Kind {
             Self::Trapezoid(mf) => mf.get_degree(x),
             Self::LinearZ(mf) => mf.get_degree(x),
             Self::LinearS(mf) => mf.get_degree(x),
+            Self::SplineZ(mf) => mf.get_degree(x),
+            Self::SplineS(mf) => mf.get_degree(x),
             Self::StepUp(mf) => mf.get_degree(x),
             Self::StepDown(mf) => mf.get_degree(x),
             Self::Gaussian(mf) => mf.get_degree(x),
             Self::Custom(mf) => mf.get_degree(x),
             Self::Bell(mf) => mf.get_degree(x),
             Self::DoubleGaussian(mf) => mf.get_degree(x),
-            _ => 0.0,
+            Self::Normal(mf) => mf.get_degree(x),
+        }
+    }
+}
+
+impl Kind {
+    pub fn get_parameters(&self) -> Vec<f64> {
+        match self {
+            Self::Triangle(mf) => mf.get_parameters(),
+            Self::Trapezoid(mf) => mf.get_parameters(),
+            Self::LinearZ(mf) => mf.get_parameters(),
+            Self::LinearS(mf) => mf.get_parameters(),
+            Self::SplineZ(mf) => mf.get_parameters(),
+            Self::SplineS(mf) => mf.get_parameters(),
+            Self::StepUp(mf) => mf.get_parameters(),
+            Self::StepDown(mf) => mf.get_parameters(),
+            Self::Gaussian(mf) => mf.get_parameters(),
+            Self::Normal(mf) => mf.get_parameters(),
+            Self::Custom(mf) => mf.get_parameters(),
+            Self::Bell(mf) => mf.get_parameters(),
+            Self::DoubleGaussian(mf) => mf.get_parameters(),
         }
     }
 }
@@ -40,6 +67,7 @@ impl GetDegree for Kind {
 pub type MFKind = Kind;
 
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct MembershipFunction {
     name: String,
     kind: Kind,
@@ -60,9 +88,13 @@ impl MembershipFunction {
     pub fn get_name(&self) -> &String {
         &self.name
     }
+    pub fn get_kind(&self) -> &Kind {
+        &self.kind
+    }
 }
 
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Triangle {
     a: f64,
     b: f64,
@@ -71,27 +103,37 @@ pub struct Triangle {
 
 impl Triangle {
     pub fn new(a: f64, b: f64, c: f64) -> Self {
-        assert!(a <= b, "a must be less than or equal to b");
-        assert!(b <= c, "b must be less than or equal to c");
-        Self { a, b, c }
+        Self::try_new(a, b, c).unwrap_or_else(|err| panic!("{}", err))
+    }
+
+    pub fn try_new(a: f64, b: f64, c: f64) -> Result<Self, FuzzyError> {
+        check(a <= b, "a must be less than or equal to b")?;
+        check(b <= c, "b must be less than or equal to c")?;
+        Ok(Self { a, b, c })
+    }
+
+    pub fn get_parameters(&self) -> Vec<f64> {
+        vec![self.a, self.b, self.c]
     }
 }
 
 impl GetDegree for Triangle {
+    // checks the peak on its own so a == b or b == c still reaches 1
     fn get_degree(&self, x: f64) -> f64 {
-        if x < self.a {
+        if x < self.a || x > self.c {
             0.0
         } else if x < self.b {
             (x - self.a) / (self.b - self.a)
-        } else if x < self.c {
+        } else if x > self.b {
             (self.c - x) / (self.c - self.b)
         } else {
-            0.0
+            1.0
         }
     }
 }
 
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Trapezoid {
     a: f64,
     b: f64,
@@ -101,25 +143,31 @@ pub struct Trapezoid {
 
 impl Trapezoid {
     pub fn new(a: f64, b: f64, c: f64, d: f64) -> Self {
-        assert!(a <= b, "a must be less than b");
-        assert!(b <= c, "b must be less than c");
-        assert!(c <= d, "c must be less than d");
-        Self { a, b, c, d }
+        Self::try_new(a, b, c, d).unwrap_or_else(|err| panic!("{}", err))
+    }
+
+    pub fn try_new(a: f64, b: f64, c: f64, d: f64) -> Result<Self, FuzzyError> {
+        check(a <= b, "a must be less than b")?;
+        check(b <= c, "b must be less than c")?;
+        check(c <= d, "c must be less than d")?;
+        Ok(Self { a, b, c, d })
+    }
+
+    pub fn get_parameters(&self) -> Vec<f64> {
+        vec![self.a, self.b, self.c, self.d]
     }
 }
 
 impl GetDegree for Trapezoid {
     fn get_degree(&self, x: f64) -> f64 {
-        if x <= self.a {
+        if x < self.a || x > self.d {
             0.0
-        } else if x <= self.b {
+        } else if x < self.b {
             (x - self.a) / (self.b - self.a)
         } else if x <= self.c {
             1.0
-        } else if x <= self.d {
-            (self.d - x) / (self.d - self.c)
         } else {
-            0.0
+            (self.d - x) / (self.d - self.c)
         }
     }
 }
@@ -142,6 +190,9 @@ impl Custom {
     pub fn get_name(&self) -> String {
         self.name.clone()
     }
+    pub fn get_parameters(&self) -> Vec<f64> {
+        self.parameters.clone()
+    }
 }
 
 impl GetDegree for Custom {
@@ -150,6 +201,7 @@ impl GetDegree for Custom {
     }
 }
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct LinearS {
     a: f64,
     b: f64,
@@ -157,8 +209,16 @@ pub struct LinearS {
 
 impl LinearS {
     pub fn new(a: f64, b: f64) -> Self {
-        assert!(a < b, "a must be grater that b");
-        Self { a, b }
+        Self::try_new(a, b).unwrap_or_else(|err| panic!("{}", err))
+    }
+
+    pub fn try_new(a: f64, b: f64) -> Result<Self, FuzzyError> {
+        check(a < b, "a must be less than b")?;
+        Ok(Self { a, b })
+    }
+
+    pub fn get_parameters(&self) -> Vec<f64> {
+        vec![self.a, self.b]
     }
 }
 
@@ -175,6 +235,7 @@ impl GetDegree for LinearS {
 }
 
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct LinearZ {
     a: f64,
     b: f64,
@@ -182,8 +243,16 @@ pub struct LinearZ {
 
 impl LinearZ {
     pub fn new(a: f64, b: f64) -> Self {
-        assert!(a < b, "a must be grater that b");
-        Self { a, b }
+        Self::try_new(a, b).unwrap_or_else(|err| panic!("{}", err))
+    }
+
+    pub fn try_new(a: f64, b: f64) -> Result<Self, FuzzyError> {
+        check(a < b, "a must be less than b")?;
+        Ok(Self { a, b })
+    }
+
+    pub fn get_parameters(&self) -> Vec<f64> {
+        vec![self.a, self.b]
     }
 }
 
@@ -192,14 +261,114 @@ impl GetDegree for LinearZ {
         if x < self.a {
             return 1.0;
         } else if x < self.b {
-            return (self.a - x) / (self.a - self.b);
+            return (self.b - x) / (self.b - self.a);
         } else {
             return 0.0;
         }
     }
 }
 
+// MATLAB's `smf`, two quadratic pieces that meet at the middle of a and b
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SplineS {
+    a: f64,
+    b: f64,
+}
+
+impl SplineS {
+    pub fn new(a: f64, b: f64) -> Self {
+        Self::try_new(a, b).unwrap_or_else(|err| panic!("{}", err))
+    }
+
+    pub fn try_new(a: f64, b: f64) -> Result<Self, FuzzyError> {
+        check(a < b, "a must be less than b")?;
+        Ok(Self { a, b })
+    }
+
+    pub fn get_parameters(&self) -> Vec<f64> {
+        vec![self.a, self.b]
+    }
+}
+
+impl GetDegree for SplineS {
+    fn get_degree(&self, x: f64) -> f64 {
+        let width = self.b - self.a;
+        if x <= self.a {
+            0.0
+        } else if x <= (self.a + self.b) / 2.0 {
+            2.0 * ((x - self.a) / width).powi(2)
+        } else if x <= self.b {
+            1.0 - 2.0 * ((x - self.b) / width).powi(2)
+        } else {
+            1.0
+        }
+    }
+}
+
+// MATLAB's `zmf`, the mirror of `SplineS`
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SplineZ {
+    a: f64,
+    b: f64,
+}
+
+impl SplineZ {
+    pub fn new(a: f64, b: f64) -> Self {
+        Self::try_new(a, b).unwrap_or_else(|err| panic!("{}", err))
+    }
+
+    pub fn try_new(a: f64, b: f64) -> Result<Self, FuzzyError> {
+        check(a < b, "a must be less than b")?;
+        Ok(Self { a, b })
+    }
+
+    pub fn get_parameters(&self) -> Vec<f64> {
+        vec![self.a, self.b]
+    }
+}
+
+impl GetDegree for SplineZ {
+    fn get_degree(&self, x: f64) -> f64 {
+        1.0 - SplineS {
+            a: self.a,
+            b: self.b,
+        }
+        .get_degree(x)
+    }
+}
+
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Sigmoid {
+    slope: f64,
+    center: f64,
+}
+
+impl Sigmoid {
+    pub fn new(slope: f64, center: f64) -> Self {
+        Self::try_new(slope, center).unwrap_or_else(|err| panic!("{}", err))
+    }
+
+    pub fn try_new(slope: f64, center: f64) -> Result<Self, FuzzyError> {
+        check(slope != 0.0, "slope must not be 0")?;
+        Ok(Self { slope, center })
+    }
+
+    pub fn get_parameters(&self) -> Vec<f64> {
+        vec![self.slope, self.center]
+    }
+}
+
+impl GetDegree for Sigmoid {
+    fn get_degree(&self, x: f64) -> f64 {
+        1.0 / (1.0 + f64::exp(-self.slope * (x - self.center)))
+    }
+}
+
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct StepDown {
     a: f64,
 }
@@ -208,6 +377,10 @@ impl StepDown {
     pub fn new(a: f64) -> Self {
         Self { a }
     }
+
+    pub fn get_parameters(&self) -> Vec<f64> {
+        vec![self.a]
+    }
 }
 
 impl GetDegree for StepDown {
@@ -220,6 +393,7 @@ impl GetDegree for StepDown {
 }
 
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct StepUp {
     a: f64,
 }
@@ -228,6 +402,10 @@ impl StepUp {
     pub fn new(a: f64) -> Self {
         Self { a }
     }
+
+    pub fn get_parameters(&self) -> Vec<f64> {
+        vec![self.a]
+    }
 }
 
 impl GetDegree for StepUp {
@@ -235,11 +413,12 @@ impl GetDegree for StepUp {
         if x > self.a {
             return 1.0;
         }
-        1.0
+        0.0
     }
 }
 
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Gaussian {
     mean: f64,
     variance: f64,
@@ -247,8 +426,16 @@ pub struct Gaussian {
 
 impl Gaussian {
     pub fn new(mean: f64, variance: f64) -> Self {
-        assert!(variance > 0.0);
-        Self { mean, variance }
+        Self::try_new(mean, variance).unwrap_or_else(|err| panic!("{}", err))
+    }
+
+    pub fn try_new(mean: f64, variance: f64) -> Result<Self, FuzzyError> {
+        check(variance > 0.0, "variance must be greater than 0")?;
+        Ok(Self { mean, variance })
+    }
+
+    pub fn get_parameters(&self) -> Vec<f64> {
+        vec![self.mean, self.variance]
     }
 }
 
@@ -259,6 +446,7 @@ impl GetDegree for Gaussian {
 }
 
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct DoubleGaussian {
     mean1: f64,
     variance1: f64,
@@ -268,15 +456,28 @@ pub struct DoubleGaussian {
 
 impl DoubleGaussian {
     pub fn new(mean1: f64, variance1: f64, mean2: f64, variance2: f64) -> Self {
-        assert!(mean1 <= mean2, "mean1 must be less than mean2");
-        assert!(variance1 > 0.0);
-        assert!(variance2 > 0.0);
-        Self {
+        Self::try_new(mean1, variance1, mean2, variance2).unwrap_or_else(|err| panic!("{}", err))
+    }
+
+    pub fn try_new(
+        mean1: f64,
+        variance1: f64,
+        mean2: f64,
+        variance2: f64,
+    ) -> Result<Self, FuzzyError> {
+        check(mean1 <= mean2, "mean1 must be less than mean2")?;
+        check(variance1 > 0.0, "variance1 must be greater than 0")?;
+        check(variance2 > 0.0, "variance2 must be greater than 0")?;
+        Ok(Self {
             mean1,
             variance1,
             mean2,
             variance2,
-        }
+        })
+    }
+
+    pub fn get_parameters(&self) -> Vec<f64> {
+        vec![self.mean1, self.variance1, self.mean2, self.variance2]
     }
 }
 
@@ -293,6 +494,7 @@ impl GetDegree for DoubleGaussian {
 }
 
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Bell {
     width: f64,
     shape: f64,
@@ -301,13 +503,21 @@ pub struct Bell {
 
 impl Bell {
     pub fn new(width: f64, shape: f64, center: f64) -> Self {
-        assert!(width > 0.0);
-        assert!(shape > 0.0);
-        Self {
+        Self::try_new(width, shape, center).unwrap_or_else(|err| panic!("{}", err))
+    }
+
+    pub fn try_new(width: f64, shape: f64, center: f64) -> Result<Self, FuzzyError> {
+        check(width > 0.0, "width must be greater than 0")?;
+        check(shape > 0.0, "shape must be greater than 0")?;
+        Ok(Self {
             width,
             shape,
             center,
-        }
+        })
+    }
+
+    pub fn get_parameters(&self) -> Vec<f64> {
+        vec![self.width, self.shape, self.center]
     }
 }
 
@@ -317,13 +527,58 @@ impl GetDegree for Bell {
     }
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum TSKMembershipFunction {
     Constant(f64),
     Linear(Vec<f64>),
+    // one coefficient for every input and the constant last, like MATLAB's `linear`
+    Affine(Vec<f64>),
+    Quadratic(Quadratic),
+    Polynomial(Polynomial),
+    #[cfg_attr(feature = "serde", serde(skip))]
     Custom(fn(&Vec<f64>) -> f64),
 }
 
+// custom consequents are the same when they use the same function
+impl PartialEq for TSKMembershipFunction {
+    fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (Self::Constant(a), Self::Constant(b)) => a == b,
+            (Self::Linear(a), Self::Linear(b)) => a == b,
+            (Self::Affine(a), Self::Affine(b)) => a == b,
+            (Self::Quadratic(a), Self::Quadratic(b)) => a == b,
+            (Self::Polynomial(a), Self::Polynomial(b)) => a == b,
+            (Self::Custom(a), Self::Custom(b)) => std::ptr::fn_addr_eq(*a, *b),
+            _ => false,
+        }
+    }
+}
+
+impl TSKMembershipFunction {
+    pub fn get_mu(&self, input_vec: &Vec<f64>) -> f64 {
+        match self {
+            Self::Constant(c) => *c,
+            Self::Linear(coefficients) => linear_membership(coefficients, input_vec),
+            Self::Affine(coefficients) => affine_membership(coefficients, input_vec),
+            Self::Quadratic(mf) => mf.get_mu(input_vec),
+            Self::Polynomial(mf) => mf.get_mu(input_vec),
+            Self::Custom(fun) => fun(input_vec),
+        }
+    }
+
+    // the number of inputs it is made for, None when it takes any number
+    pub fn get_input_size(&self) -> Option<usize> {
+        match self {
+            Self::Linear(coefficients) => Some(coefficients.len()),
+            Self::Affine(coefficients) => Some(coefficients.len().saturating_sub(1)),
+            Self::Quadratic(mf) => Some(mf.input_size),
+            Self::Polynomial(mf) => Some(mf.input_size),
+            Self::Constant(_) | Self::Custom(_) => None,
+        }
+    }
+}
+
 pub fn linear_membership(coefficients: &Vec<f64>, input_vec: &Vec<f64>) -> f64 {
     coefficients
         .iter()
@@ -331,3 +586,436 @@ pub fn linear_membership(coefficients: &Vec<f64>, input_vec: &Vec<f64>) -> f64 {
         .map(|(c, x)| c * x)
         .sum()
 }
+
+pub fn affine_membership(coefficients: &[f64], input_vec: &[f64]) -> f64 {
+    match coefficients.split_last() {
+        Some((constant, coefficients)) => {
+            coefficients
+                .iter()
+                .zip(input_vec)
+                .map(|(c, x)| c * x)
+                .sum::<f64>()
+                + constant
+        }
+        None => 0.0,
+    }
+}
+
+// x'Ax + b'x + c, kept as one list: the rows of A, then b and c
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Quadratic {
+    input_size: usize,
+    coefficients: Vec<f64>,
+}
+
+impl Quadratic {
+    pub fn new(a: Vec<Vec<f64>>, b: Vec<f64>, c: f64) -> Self {
+        Self::try_new(a, b, c).unwrap_or_else(|err| panic!("{}", err))
+    }
+
+    pub fn try_new(a: Vec<Vec<f64>>, b: Vec<f64>, c: f64) -> Result<Self, FuzzyError> {
+        let input_size = b.len();
+        check(
+            a.len() == input_size && a.iter().all(|row| row.len() == input_size),
+            "a must have one row and one column for every coefficient of b",
+        )?;
+        let mut coefficients: Vec<f64> = a.into_iter().flatten().collect();
+        coefficients.extend(b);
+        coefficients.push(c);
+        Ok(Self {
+            input_size,
+            coefficients,
+        })
+    }
+
+    pub fn get_parameters(&self) -> Vec<f64> {
+        self.coefficients.clone()
+    }
+
+    pub(crate) fn parameters_mut(&mut self) -> &mut [f64] {
+        &mut self.coefficients
+    }
+
+    // the value is linear in the coefficients, these are their factors
+    pub fn get_basis(&self, input_vec: &[f64]) -> Vec<f64> {
+        let x = |i: usize| input_vec.get(i).copied().unwrap_or(0.0);
+        let n = self.input_size;
+        let mut basis: Vec<f64> = (0..n * n).map(|k| x(k / n) * x(k % n)).collect();
+        basis.extend((0..n).map(x));
+        basis.push(1.0);
+        basis
+    }
+
+    pub fn get_mu(&self, input_vec: &[f64]) -> f64 {
+        dot(&self.coefficients, &self.get_basis(input_vec))
+    }
+}
+
+// Every product of the inputs up to `degree`. The highest degree comes first and the
+// constant last, so a polynomial of degree 1 has the same coefficients as `Affine`.
+// Inside a degree the products are sorted by their inputs, e.g. x0x0, x0x1, x1x1.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Polynomial {
+    input_size: usize,
+    degree: usize,
+    coefficients: Vec<f64>,
+}
+
+impl Polynomial {
+    pub fn new(input_size: usize, degree: usize, coefficients: Vec<f64>) -> Self {
+        Self::try_new(input_size, degree, coefficients).unwrap_or_else(|err| panic!("{}", err))
+    }
+
+    pub fn try_new(
+        input_size: usize,
+        degree: usize,
+        coefficients: Vec<f64>,
+    ) -> Result<Self, FuzzyError> {
+        let expected = Self::monomials(input_size, degree).len();
+        check(
+            coefficients.len() == expected,
+            &format!(
+                "a polynomial of degree {} with {} inputs needs {} coefficients, found {}",
+                degree,
+                input_size,
+                expected,
+                coefficients.len()
+            ),
+        )?;
+        Ok(Self {
+            input_size,
+            degree,
+            coefficients,
+        })
+    }
+
+    // the inputs multiplied by every coefficient, in the order of the coefficients
+    pub fn monomials(input_size: usize, degree: usize) -> Vec<Vec<usize>> {
+        let mut monomials = Vec::new();
+        for d in (1..=degree).rev() {
+            let mut current = vec![0; d];
+            loop {
+                monomials.push(current.clone());
+                // the next non-decreasing list of inputs
+                match current.iter().rposition(|i| i + 1 < input_size) {
+                    Some(k) => {
+                        let next = current[k] + 1;
+                        current[k..].iter_mut().for_each(|i| *i = next);
+                    }
+                    None => break,
+                }
+            }
+            if input_size == 0 {
+                monomials.pop();
+            }
+        }
+        monomials.push(Vec::new());
+        monomials
+    }
+
+    pub fn get_degree(&self) -> usize {
+        self.degree
+    }
+
+    pub fn get_parameters(&self) -> Vec<f64> {
+        self.coefficients.clone()
+    }
+
+    pub(crate) fn parameters_mut(&mut self) -> &mut [f64] {
+        &mut self.coefficients
+    }
+
+    // the value is linear in the coefficients, these are their factors
+    pub fn get_basis(&self, input_vec: &[f64]) -> Vec<f64> {
+        Self::monomials(self.input_size, self.degree)
+            .into_iter()
+            .map(|inputs| {
+                inputs
+                    .into_iter()
+                    .map(|i| input_vec.get(i).copied().unwrap_or(0.0))
+                    .product()
+            })
+            .collect()
+    }
+
+    pub fn get_mu(&self, input_vec: &[f64]) -> f64 {
+        dot(&self.coefficients, &self.get_basis(input_vec))
+    }
+}
+
+fn dot(a: &[f64], b: &[f64]) -> f64 {
+    a.iter().zip(b).map(|(a, b)| a * b).sum()
+}
+
+pub trait GetInterval {
+    // returns (lower, upper)
+    fn get_interval(&self, x: f64) -> (f64, f64);
+}
+
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum IntervalType2Kind {
+    Footprint(Footprint),
+    UncertainMean(UncertainMean),
+    UncertainSigma(UncertainSigma),
+}
+
+impl GetInterval for IntervalType2Kind {
+    fn get_interval(&self, x: f64) -> (f64, f64) {
+        match self {
+            Self::Footprint(mf) => mf.get_interval(x),
+            Self::UncertainMean(mf) => mf.get_interval(x),
+            Self::UncertainSigma(mf) => mf.get_interval(x),
+        }
+    }
+}
+
+impl IntervalType2Kind {
+    pub fn get_parameters(&self) -> Vec<f64> {
+        match self {
+            Self::Footprint(mf) => mf.get_parameters(),
+            Self::UncertainMean(mf) => mf.get_parameters(),
+            Self::UncertainSigma(mf) => mf.get_parameters(),
+        }
+    }
+}
+
+pub type IT2Kind = IntervalType2Kind;
+
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct IntervalType2MembershipFunction {
+    name: String,
+    kind: IntervalType2Kind,
+}
+
+pub type IT2MF = IntervalType2MembershipFunction;
+
+impl GetInterval for IntervalType2MembershipFunction {
+    fn get_interval(&self, x: f64) -> (f64, f64) {
+        self.kind.get_interval(x)
+    }
+}
+
+impl IntervalType2MembershipFunction {
+    pub fn new(name: String, kind: IntervalType2Kind) -> Self {
+        Self { name, kind }
+    }
+    pub fn get_name(&self) -> &String {
+        &self.name
+    }
+    pub fn get_kind(&self) -> &IntervalType2Kind {
+        &self.kind
+    }
+}
+
+// Footprint of uncertainty between any two type-1 shapes, the lower one is scaled by `height`
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Footprint {
+    upper: Kind,
+    lower: Kind,
+    height: f64,
+}
+
+impl Footprint {
+    pub fn new(upper: Kind, lower: Kind, height: f64) -> Self {
+        Self::try_new(upper, lower, height).unwrap_or_else(|err| panic!("{}", err))
+    }
+
+    pub fn try_new(upper: Kind, lower: Kind, height: f64) -> Result<Self, FuzzyError> {
+        check(
+            height > 0.0 && height <= 1.0,
+            "height must be between 0.0 and 1.0",
+        )?;
+        Ok(Self {
+            upper,
+            lower,
+            height,
+        })
+    }
+
+    pub fn get_upper(&self) -> &Kind {
+        &self.upper
+    }
+
+    pub fn get_lower(&self) -> &Kind {
+        &self.lower
+    }
+
+    pub fn get_height(&self) -> f64 {
+        self.height
+    }
+
+    pub fn get_parameters(&self) -> Vec<f64> {
+        let mut parameters = self.upper.get_parameters();
+        parameters.extend(self.lower.get_parameters());
+        parameters.push(self.height);
+        parameters
+    }
+}
+
+impl GetInterval for Footprint {
+    fn get_interval(&self, x: f64) -> (f64, f64) {
+        let upper = self.upper.get_degree(x);
+        let lower = self.height * self.lower.get_degree(x);
+        (lower.min(upper), upper)
+    }
+}
+
+// Gaussian whose mean is somewhere between mean1 and mean2
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct UncertainMean {
+    mean1: f64,
+    mean2: f64,
+    variance: f64,
+}
+
+impl UncertainMean {
+    pub fn new(mean1: f64, mean2: f64, variance: f64) -> Self {
+        Self::try_new(mean1, mean2, variance).unwrap_or_else(|err| panic!("{}", err))
+    }
+
+    pub fn try_new(mean1: f64, mean2: f64, variance: f64) -> Result<Self, FuzzyError> {
+        check(mean1 <= mean2, "mean1 must be less than mean2")?;
+        check(variance > 0.0, "variance must be greater than 0")?;
+        Ok(Self {
+            mean1,
+            mean2,
+            variance,
+        })
+    }
+
+    pub fn get_parameters(&self) -> Vec<f64> {
+        vec![self.mean1, self.mean2, self.variance]
+    }
+}
+
+impl GetInterval for UncertainMean {
+    fn get_interval(&self, x: f64) -> (f64, f64) {
+        let left = Gaussian::new(self.mean1, self.variance).get_degree(x);
+        let right = Gaussian::new(self.mean2, self.variance).get_degree(x);
+        let upper = if x < self.mean1 {
+            left
+        } else if x <= self.mean2 {
+            1.0
+        } else {
+            right
+        };
+        (left.min(right), upper)
+    }
+}
+
+// Gaussian whose variance is somewhere between variance1 and variance2
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct UncertainSigma {
+    mean: f64,
+    variance1: f64,
+    variance2: f64,
+}
+
+impl UncertainSigma {
+    pub fn new(mean: f64, variance1: f64, variance2: f64) -> Self {
+        Self::try_new(mean, variance1, variance2).unwrap_or_else(|err| panic!("{}", err))
+    }
+
+    pub fn try_new(mean: f64, variance1: f64, variance2: f64) -> Result<Self, FuzzyError> {
+        check(variance1 > 0.0, "variance1 must be greater than 0")?;
+        check(
+            variance1 <= variance2,
+            "variance1 must be less than variance2",
+        )?;
+        Ok(Self {
+            mean,
+            variance1,
+            variance2,
+        })
+    }
+
+    pub fn get_parameters(&self) -> Vec<f64> {
+        vec![self.mean, self.variance1, self.variance2]
+    }
+}
+
+impl GetInterval for UncertainSigma {
+    fn get_interval(&self, x: f64) -> (f64, f64) {
+        (
+            Gaussian::new(self.mean, self.variance1).get_degree(x),
+            Gaussian::new(self.mean, self.variance2).get_degree(x),
+        )
+    }
+}
+
+// Monotonic functions can be inverted, used for Tsukamoto consequents
+pub trait GetInverse {
+    fn get_inverse(&self, mu: f64) -> f64;
+}
+
+impl GetInverse for LinearS {
+    fn get_inverse(&self, mu: f64) -> f64 {
+        self.a + mu * (self.b - self.a)
+    }
+}
+
+impl GetInverse for LinearZ {
+    fn get_inverse(&self, mu: f64) -> f64 {
+        self.b - mu * (self.b - self.a)
+    }
+}
+
+impl GetInverse for Sigmoid {
+    fn get_inverse(&self, mu: f64) -> f64 {
+        self.center - f64::ln(1.0 / mu - 1.0) / self.slope
+    }
+}
+
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum MonotonicKind {
+    LinearS(LinearS),
+    LinearZ(LinearZ),
+    Sigmoid(Sigmoid),
+    // the inverse function itself
+    #[cfg_attr(feature = "serde", serde(skip))]
+    Custom(fn(f64) -> f64),
+}
+
+impl GetInverse for MonotonicKind {
+    fn get_inverse(&self, mu: f64) -> f64 {
+        match self {
+            Self::LinearS(mf) => mf.get_inverse(mu),
+            Self::LinearZ(mf) => mf.get_inverse(mu),
+            Self::Sigmoid(mf) => mf.get_inverse(mu),
+            Self::Custom(inverse) => inverse(mu),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct TsukamotoMembershipFunction {
+    name: String,
+    kind: MonotonicKind,
+}
+
+impl GetInverse for TsukamotoMembershipFunction {
+    fn get_inverse(&self, mu: f64) -> f64 {
+        self.kind.get_inverse(mu)
+    }
+}
+
+impl TsukamotoMembershipFunction {
+    pub fn new(name: String, kind: MonotonicKind) -> Self {
+        Self { name, kind }
+    }
+    pub fn get_name(&self) -> &String {
+        &self.name
+    }
+    pub fn get_kind(&self) -> &MonotonicKind {
+        &self.kind
+    }
+}
@@ -1,9 +1,16 @@
 // use std::ops::{Add, Div, Mul, Neg, Sub};
+use crate::error::{check, FuzzyError};
+use crate::membership_functions::{
+    Bell, DoubleGaussian, Gaussian, GetDegree, GetInterval, IntervalType2Kind, Kind, LinearS,
+    LinearZ, MembershipFunction, SplineS, SplineZ, StepDown, StepUp, Trapezoid, Triangle,
+};
 
 #[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct MembershipRange {
     name: String,
     mu: Vec<f64>,
+    kind: Option<Kind>,
 }
 /*
 #[derive(Debug)]
@@ -16,167 +23,186 @@ pub enum MembershipRangeKind {
 */
 impl MembershipRange {
     pub fn new(name: String, mu: Vec<f64>) -> Self {
-        Self { name, mu }
+        Self {
+            name,
+            mu,
+            kind: None,
+        }
     }
-    pub fn new_triangle(universe: &Vec<f64>, name: String, a: f64, b: f64, c: f64) -> Self {
-        assert!(a < b, "a must be less than b");
-        assert!(b < c, "b must be less that c");
-        let mut mu: Vec<f64> = Vec::new();
-        for x in universe.into_iter() {
-            let data: f64;
-            if *x <= a {
-                data = 0.0;
-            } else if *x <= b {
-                data = (*x - a) / (b - a);
-            } else if *x <= c {
-                data = (c - *x) / (c - b);
-            } else {
-                data = 0.0;
-            }
-            mu.push(data);
+
+    pub fn from_kind(universe: &[f64], name: String, kind: Kind) -> Self {
+        let mu = universe.iter().map(|x| kind.get_degree(*x)).collect();
+        Self {
+            name,
+            mu,
+            kind: Some(kind),
         }
-        Self { name, mu }
     }
-    pub fn new_trapezoid(
-        universe: &Vec<f64>,
+
+    pub fn from_mf(mf: &MembershipFunction, universe: &[f64]) -> Self {
+        Self::from_kind(universe, mf.get_name().clone(), mf.get_kind().clone())
+    }
+
+    pub fn new_triangle(universe: &[f64], name: String, a: f64, b: f64, c: f64) -> Self {
+        Self::try_new_triangle(universe, name, a, b, c).unwrap_or_else(|err| panic!("{}", err))
+    }
+
+    pub fn try_new_triangle(
+        universe: &[f64],
+        name: String,
+        a: f64,
+        b: f64,
+        c: f64,
+    ) -> Result<Self, FuzzyError> {
+        let kind = Kind::Triangle(Triangle::try_new(a, b, c)?);
+        Ok(Self::from_kind(universe, name, kind))
+    }
+
+    pub fn new_trapezoid(universe: &[f64], name: String, a: f64, b: f64, c: f64, d: f64) -> Self {
+        Self::try_new_trapezoid(universe, name, a, b, c, d).unwrap_or_else(|err| panic!("{}", err))
+    }
+
+    pub fn try_new_trapezoid(
+        universe: &[f64],
         name: String,
         a: f64,
         b: f64,
         c: f64,
         d: f64,
-    ) -> Self {
-        // assert!(universe[0]<a);
-        assert!(a <= b, "a must be less than b");
-        assert!(b <= c, "b must be less than c");
-        assert!(c <= d, "c must be less than d");
-        let mut mu: Vec<f64> = Vec::new();
-        let mut data: f64;
-        for value in universe.into_iter() {
-            if *value <= a {
-                data = 0.0;
-            } else if *value <= b {
-                data = (*value - a) / (b - a);
-            } else if *value <= c {
-                data = 1.0;
-            } else if *value <= d {
-                data = (d - *value) / (d - c);
-            } else {
-                data = 0.0;
-            }
-            mu.push(data);
-        }
-        Self { name, mu }
+    ) -> Result<Self, FuzzyError> {
+        let kind = Kind::Trapezoid(Trapezoid::try_new(a, b, c, d)?);
+        Ok(Self::from_kind(universe, name, kind))
     }
 
-    pub fn new_linearz(universe: &Vec<f64>, name: String, a: f64, b: f64) -> Self {
-        assert!(a < b);
-        let mut mu: Vec<f64> = Vec::new();
-        let mut data: f64;
-        for x in universe.into_iter() {
-            if *x < a {
-                data = 1.0;
-            } else if *x < b {
-                data = (a - *x) / (a - b);
-            } else {
-                data = 0.0;
-            }
-            mu.push(data);
-        }
-        Self { name, mu }
+    pub fn new_linearz(universe: &[f64], name: String, a: f64, b: f64) -> Self {
+        Self::try_new_linearz(universe, name, a, b).unwrap_or_else(|err| panic!("{}", err))
     }
 
-    pub fn new_linears(universe: &Vec<f64>, name: String, a: f64, b: f64) -> Self {
-        assert!(a < b);
-        let mut mu: Vec<f64> = Vec::new();
-        let mut data: f64;
-        for x in universe.into_iter() {
-            if *x < a {
-                data = 0.0;
-            } else if *x < b {
-                data = (*x - a) / (b - a);
-            } else {
-                data = 1.0;
-            }
-            mu.push(data);
-        }
-        Self { name, mu }
+    pub fn try_new_linearz(
+        universe: &[f64],
+        name: String,
+        a: f64,
+        b: f64,
+    ) -> Result<Self, FuzzyError> {
+        let kind = Kind::LinearZ(LinearZ::try_new(a, b)?);
+        Ok(Self::from_kind(universe, name, kind))
     }
 
-    pub fn new_step_down(universe: &Vec<f64>, name: String, a: f64) -> Self {
-        let mut mu: Vec<f64> = Vec::new();
-        for x in universe.into_iter() {
-            let mut data: f64 = 1.0;
-            if *x > a {
-                data = 0.0;
-            }
-            mu.push(data);
-        }
-        Self { name, mu }
+    pub fn new_linears(universe: &[f64], name: String, a: f64, b: f64) -> Self {
+        Self::try_new_linears(universe, name, a, b).unwrap_or_else(|err| panic!("{}", err))
     }
 
-    pub fn new_step_up(universe: &Vec<f64>, name: String, a: f64) -> Self {
-        let mut mu: Vec<f64> = Vec::new();
-        for x in universe.into_iter() {
-            let mut data: f64 = 0.0;
-            if *x > a {
-                data = 1.0;
-            }
-            mu.push(data);
-        }
-        Self { name, mu }
+    pub fn try_new_linears(
+        universe: &[f64],
+        name: String,
+        a: f64,
+        b: f64,
+    ) -> Result<Self, FuzzyError> {
+        let kind = Kind::LinearS(LinearS::try_new(a, b)?);
+        Ok(Self::from_kind(universe, name, kind))
     }
 
-    pub fn new_gaussian(universe: &Vec<f64>, name: String, mean: f64, variance: f64) -> Self {
-        let mut mu: Vec<f64> = Vec::new();
-        assert!(variance > 0.0);
-        for x in universe.into_iter() {
-            let data: f64 = f64::exp(-0.5 * f64::powi((*x - mean) / variance, 2));
-            mu.push(data);
-        }
-        Self { name, mu }
+    pub fn new_splinez(universe: &[f64], name: String, a: f64, b: f64) -> Self {
+        Self::try_new_splinez(universe, name, a, b).unwrap_or_else(|err| panic!("{}", err))
+    }
+
+    pub fn try_new_splinez(
+        universe: &[f64],
+        name: String,
+        a: f64,
+        b: f64,
+    ) -> Result<Self, FuzzyError> {
+        let kind = Kind::SplineZ(SplineZ::try_new(a, b)?);
+        Ok(Self::from_kind(universe, name, kind))
+    }
+
+    pub fn new_splines(universe: &[f64], name: String, a: f64, b: f64) -> Self {
+        Self::try_new_splines(universe, name, a, b).unwrap_or_else(|err| panic!("{}", err))
+    }
+
+    pub fn try_new_splines(
+        universe: &[f64],
+        name: String,
+        a: f64,
+        b: f64,
+    ) -> Result<Self, FuzzyError> {
+        let kind = Kind::SplineS(SplineS::try_new(a, b)?);
+        Ok(Self::from_kind(universe, name, kind))
+    }
+
+    pub fn new_step_down(universe: &[f64], name: String, a: f64) -> Self {
+        Self::from_kind(universe, name, Kind::StepDown(StepDown::new(a)))
+    }
+
+    pub fn new_step_up(universe: &[f64], name: String, a: f64) -> Self {
+        Self::from_kind(universe, name, Kind::StepUp(StepUp::new(a)))
+    }
+
+    pub fn new_gaussian(universe: &[f64], name: String, mean: f64, variance: f64) -> Self {
+        Self::try_new_gaussian(universe, name, mean, variance)
+            .unwrap_or_else(|err| panic!("{}", err))
+    }
+
+    pub fn try_new_gaussian(
+        universe: &[f64],
+        name: String,
+        mean: f64,
+        variance: f64,
+    ) -> Result<Self, FuzzyError> {
+        let kind = Kind::Gaussian(Gaussian::try_new(mean, variance)?);
+        Ok(Self::from_kind(universe, name, kind))
     }
 
     pub fn new_double_gaussian(
-        universe: &Vec<f64>,
+        universe: &[f64],
         name: String,
         mean1: f64,
         variance1: f64,
         mean2: f64,
         variance2: f64,
     ) -> Self {
-        assert!(mean1 <= mean2, "mean1 must be less than mean2");
-        assert!(variance1 > 0.0);
-        assert!(variance2 > 0.0);
-        let mut mu: Vec<f64> = Vec::new();
-        for x in universe.into_iter() {
-            let data: f64;
-            if *x < mean1 {
-                data = f64::exp(-0.5 * f64::powi((*x - mean1) / variance1, 2));
-            } else if *x < mean2 {
-                data = 1.0;
-            } else {
-                data = f64::exp(-0.5 * f64::powi((*x - mean2) / variance2, 2));
-            }
-            mu.push(data);
-        }
-        Self { name, mu }
+        Self::try_new_double_gaussian(universe, name, mean1, variance1, mean2, variance2)
+            .unwrap_or_else(|err| panic!("{}", err))
     }
 
-    pub fn new_bell(
-        universe: &Vec<f64>,
+    pub fn try_new_double_gaussian(
+        universe: &[f64],
+        name: String,
+        mean1: f64,
+        variance1: f64,
+        mean2: f64,
+        variance2: f64,
+    ) -> Result<Self, FuzzyError> {
+        let kind =
+            Kind::DoubleGaussian(DoubleGaussian::try_new(mean1, variance1, mean2, variance2)?);
+        Ok(Self::from_kind(universe, name, kind))
+    }
+
+    pub fn new_bell(universe: &[f64], name: String, width: f64, shape: f64, center: f64) -> Self {
+        Self::try_new_bell(universe, name, width, shape, center)
+            .unwrap_or_else(|err| panic!("{}", err))
+    }
+
+    pub fn try_new_bell(
+        universe: &[f64],
         name: String,
         width: f64,
         shape: f64,
         center: f64,
-    ) -> Self {
-        let mut mu: Vec<f64> = Vec::new();
-        assert!(width > 0.0);
-        assert!(shape > 0.0);
-        for x in universe.into_iter() {
-            let data = 1.0 / (1.0 + f64::powf(f64::abs((*x - center) / width), 2.0 * shape));
-            mu.push(data);
+    ) -> Result<Self, FuzzyError> {
+        let kind = Kind::Bell(Bell::try_new(width, shape, center)?);
+        Ok(Self::from_kind(universe, name, kind))
+    }
+
+    // samples the shape again, false when the range was built from raw values
+    pub fn resample(&mut self, universe: &[f64]) -> bool {
+        match &self.kind {
+            Some(kind) => {
+                self.mu = universe.iter().map(|x| kind.get_degree(*x)).collect();
+                true
+            }
+            None => false,
         }
-        Self { name, mu }
     }
 
     pub fn get_mu(&self) -> &Vec<f64> {
@@ -186,6 +212,10 @@ impl MembershipRange {
     pub fn get_name(&self) -> String {
         self.name.clone()
     }
+
+    pub fn get_kind(&self) -> Option<&Kind> {
+        self.kind.as_ref()
+    }
 }
 
 impl IntoIterator for MembershipRange {
@@ -196,6 +226,66 @@ impl IntoIterator for MembershipRange {
     }
 }
 
+#[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct IntervalType2MembershipRange {
+    name: String,
+    lower: Vec<f64>,
+    upper: Vec<f64>,
+    kind: Option<IntervalType2Kind>,
+}
+
+impl IntervalType2MembershipRange {
+    pub fn new(name: String, lower: Vec<f64>, upper: Vec<f64>) -> Self {
+        Self::try_new(name, lower, upper).unwrap_or_else(|err| panic!("{}", err))
+    }
+
+    pub fn try_new(name: String, lower: Vec<f64>, upper: Vec<f64>) -> Result<Self, FuzzyError> {
+        check(
+            lower.len() == upper.len(),
+            "lower and upper must have the same length",
+        )?;
+        check(
+            lower.iter().zip(&upper).all(|(l, u)| l <= u),
+            "lower must not be above upper",
+        )?;
+        Ok(Self {
+            name,
+            lower,
+            upper,
+            kind: None,
+        })
+    }
+
+    pub fn from_kind(universe: &[f64], name: String, kind: IntervalType2Kind) -> Self {
+        let (lower, upper) = universe.iter().map(|x| kind.get_interval(*x)).unzip();
+        Self {
+            name,
+            lower,
+            upper,
+            kind: Some(kind),
+        }
+    }
+
+    pub fn get_lower(&self) -> &Vec<f64> {
+        &self.lower
+    }
+
+    pub fn get_upper(&self) -> &Vec<f64> {
+        &self.upper
+    }
+
+    pub fn get_name(&self) -> String {
+        self.name.clone()
+    }
+
+    pub fn get_kind(&self) -> Option<&IntervalType2Kind> {
+        self.kind.as_ref()
+    }
+}
+
+pub type IT2MR = IntervalType2MembershipRange;
+
 /*
 impl Add for MembershipRange {
     type Output = Self;
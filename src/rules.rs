@@ -1,37 +1,397 @@
-#[derive(Debug)]
+use std::fmt;
+
+use crate::complements::Complements;
+use crate::error::FuzzyError;
+use crate::hedges::Hedges;
+use crate::membership_functions::TSKMembershipFunction;
+use crate::s_norms::SNorms;
+use crate::t_norms::TNorms;
+use crate::variables::{NamedTerms, TSKOutputVariable};
+
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Kind {
     OR,
     AND,
 }
 
-#[derive(Debug)]
+impl Kind {
+    // a rule whose inputs are all don't care always fires
+    pub fn connect(&self, s_norm: &SNorms, t_norm: &TNorms, degrees: &[f64]) -> f64 {
+        if degrees.is_empty() {
+            return 1.0;
+        }
+        match self {
+            Self::OR => s_norm.s_norm(degrees),
+            Self::AND => t_norm.t_norm(degrees),
+        }
+    }
+}
+
+// What a rule says about one variable. An input that is `Any` is left out of the rule and
+// an output that is `Any` is not changed by it.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Relation {
+    Term(usize),
+    Not(usize),
+    Any,
+    Hedged(Hedges, usize),
+}
+
+// the old encoding, a negative index is the complement of that term
+impl From<i32> for Relation {
+    fn from(index: i32) -> Self {
+        if index < 0 {
+            Self::Not(index.unsigned_abs() as usize)
+        } else {
+            Self::Term(index as usize)
+        }
+    }
+}
+
+impl Relation {
+    pub fn get_term(&self) -> Option<usize> {
+        match self {
+            Self::Term(term) | Self::Not(term) | Self::Hedged(_, term) => Some(*term),
+            Self::Any => None,
+        }
+    }
+
+    pub fn is_any(&self) -> bool {
+        *self == Self::Any
+    }
+
+    // `mu` is the degree of the term itself
+    pub fn degree(&self, mu: f64, complement: &Complements) -> f64 {
+        match self {
+            Self::Term(_) => mu,
+            Self::Not(_) => complement.complement(mu),
+            Self::Hedged(hedge, _) => hedge.hedge(mu),
+            Self::Any => 1.0,
+        }
+    }
+
+    pub fn interval(&self, interval: (f64, f64), complement: &Complements) -> (f64, f64) {
+        match self {
+            Self::Term(_) => interval,
+            Self::Not(_) => complement.complement_interval(interval),
+            Self::Hedged(hedge, _) => hedge.hedge_interval(interval),
+            Self::Any => (1.0, 1.0),
+        }
+    }
+}
+
+// An antecedent built from the terms of the inputs. `And` and `Or` use the t-norm and
+// s-norm of the system unless they have their own.
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Expression {
+    Is(usize, Relation),
+    And(Vec<Expression>, Option<TNorms>),
+    Or(Vec<Expression>, Option<SNorms>),
+    Not(Box<Expression>),
+}
+
+impl Expression {
+    pub fn is<R: Into<Relation>>(input: usize, relation: R) -> Self {
+        Self::Is(input, relation.into())
+    }
+
+    pub fn and(expressions: Vec<Expression>) -> Self {
+        Self::And(expressions, None)
+    }
+
+    pub fn or(expressions: Vec<Expression>) -> Self {
+        Self::Or(expressions, None)
+    }
+
+    pub fn negate(expression: Expression) -> Self {
+        Self::Not(Box::new(expression))
+    }
+
+    // the (input, relation) of every leaf, from left to right
+    pub fn get_leaves(&self) -> Vec<(usize, &Relation)> {
+        match self {
+            Self::Is(input, relation) => vec![(*input, relation)],
+            Self::And(expressions, _) | Self::Or(expressions, _) => {
+                expressions.iter().flat_map(|e| e.get_leaves()).collect()
+            }
+            Self::Not(expression) => expression.get_leaves(),
+        }
+    }
+
+    // `degrees` gives the degree of every leaf in the order of `get_leaves`, it panics when
+    // there are fewer degrees than leaves
+    pub fn evaluate(
+        &self,
+        degrees: &mut impl Iterator<Item = f64>,
+        s_norm: &SNorms,
+        t_norm: &TNorms,
+        complement: &Complements,
+    ) -> f64 {
+        let mut intervals = degrees.map(|mu| (mu, mu));
+        self.evaluate_interval(&mut intervals, s_norm, t_norm, complement)
+            .0
+    }
+
+    pub fn evaluate_interval(
+        &self,
+        degrees: &mut impl Iterator<Item = (f64, f64)>,
+        s_norm: &SNorms,
+        t_norm: &TNorms,
+        complement: &Complements,
+    ) -> (f64, f64) {
+        let mut children = |expressions: &[Expression]| -> (Vec<f64>, Vec<f64>) {
+            expressions
+                .iter()
+                .map(|e| e.evaluate_interval(degrees, s_norm, t_norm, complement))
+                .unzip()
+        };
+        match self {
+            Self::Is(..) => degrees.next().expect("a degree for every leaf"),
+            Self::And(expressions, own) => {
+                let t_norm = own.as_ref().unwrap_or(t_norm);
+                let (lower, upper) = children(expressions);
+                (t_norm.t_norm(&lower), t_norm.t_norm(&upper))
+            }
+            Self::Or(expressions, own) => {
+                let s_norm = own.as_ref().unwrap_or(s_norm);
+                let (lower, upper) = children(expressions);
+                (s_norm.s_norm(&lower), s_norm.s_norm(&upper))
+            }
+            Self::Not(expression) => complement.complement_interval(
+                expression.evaluate_interval(degrees, s_norm, t_norm, complement),
+            ),
+        }
+    }
+
+    // the relations of a rule with one connective, when the expression can be written that way
+    pub fn flatten(&self, input_size: usize) -> Option<(Kind, Vec<Relation>)> {
+        let (kind, expressions) = match self {
+            Self::And(expressions, None) => (Kind::AND, &expressions[..]),
+            Self::Or(expressions, None) => (Kind::OR, &expressions[..]),
+            Self::And(..) | Self::Or(..) => return None,
+            leaf => (Kind::AND, std::slice::from_ref(leaf)),
+        };
+        let mut relations = vec![Relation::Any; input_size];
+        for expression in expressions {
+            let (input, relation) = match expression {
+                Self::Is(input, relation) => (*input, relation.clone()),
+                Self::Not(leaf) => match &**leaf {
+                    Self::Is(input, Relation::Term(term)) => (*input, Relation::Not(*term)),
+                    Self::Is(input, Relation::Not(term)) => (*input, Relation::Term(*term)),
+                    _ => return None,
+                },
+                _ => return None,
+            };
+            if input >= input_size || !relations[input].is_any() {
+                return None;
+            }
+            relations[input] = relation;
+        }
+        Some((kind, relations))
+    }
+
+    pub fn to_text<I: NamedTerms>(&self, inputs: &[I]) -> String {
+        let child = |expression: &Expression| match expression {
+            Self::And(..) | Self::Or(..) => format!("({})", expression.to_text(inputs)),
+            _ => expression.to_text(inputs),
+        };
+        let join = |expressions: &[Expression], connective: String| {
+            expressions
+                .iter()
+                .map(child)
+                .collect::<Vec<String>>()
+                .join(&connective)
+        };
+        match self {
+            Self::Is(input, relation) => match inputs.get(*input) {
+                Some(variable) => clause_text(variable, relation)
+                    .unwrap_or_else(|| format!("{} IS ANY", variable.variable_name())),
+                None => format!("input {} IS {:?}", input, relation),
+            },
+            Self::And(expressions, None) => join(expressions, " AND ".to_string()),
+            Self::And(expressions, Some(t_norm)) => {
+                join(expressions, format!(" AND[{}] ", t_norm_text(t_norm)))
+            }
+            Self::Or(expressions, None) => join(expressions, " OR ".to_string()),
+            Self::Or(expressions, Some(s_norm)) => {
+                join(expressions, format!(" OR[{}] ", s_norm_text(s_norm)))
+            }
+            Self::Not(expression) => format!("NOT {}", child(expression)),
+        }
+    }
+}
+
+// `x IS NOT term`, don't care terms have no text
+fn clause_text(variable: &dyn NamedTerms, relation: &Relation) -> Option<String> {
+    let index = relation.get_term()?;
+    let term = variable
+        .term_names()
+        .get(index)
+        .cloned()
+        .unwrap_or_else(|| index.to_string());
+    let modifier = match relation {
+        Relation::Not(_) => "NOT ".to_string(),
+        Relation::Hedged(hedge, _) => format!("{} ", hedge.get_keyword()),
+        _ => String::new(),
+    };
+    Some(format!(
+        "{} IS {}{}",
+        variable.variable_name(),
+        modifier,
+        term
+    ))
+}
+
+// The operator of `AND[...]` and `OR[...]`, parameters follow the name, e.g. `OR[YAGER 2]`.
+// Custom and dual operators are written as `CUSTOM` and can't be parsed back.
+fn t_norm_text(t_norm: &TNorms) -> String {
+    match t_norm {
+        TNorms::Min => "MIN".to_string(),
+        TNorms::Product => "PRODUCT".to_string(),
+        TNorms::Lukasiewicz => "LUKASIEWICZ".to_string(),
+        TNorms::Drastic => "DRASTIC".to_string(),
+        TNorms::NilpotentMin => "NILPOTENT_MIN".to_string(),
+        TNorms::Einstein => "EINSTEIN".to_string(),
+        TNorms::HamacherProduct => "HAMACHER".to_string(),
+        TNorms::Yager(p) => format!("YAGER {}", p),
+        TNorms::Frank(s) => format!("FRANK {}", s),
+        TNorms::Dombi(lambda) => format!("DOMBI {}", lambda),
+        TNorms::SchweizerSklar(p) => format!("SCHWEIZER_SKLAR {}", p),
+        TNorms::AczelAlsina(lambda) => format!("ACZEL_ALSINA {}", lambda),
+        TNorms::Custom(_) => "CUSTOM".to_string(),
+    }
+}
+
+fn t_norm_from_text(name: &str, parameter: Option<f64>) -> Option<TNorms> {
+    match (name.to_uppercase().as_str(), parameter) {
+        ("MIN", None) => Some(TNorms::Min),
+        ("PRODUCT", None) => Some(TNorms::Product),
+        ("LUKASIEWICZ", None) => Some(TNorms::Lukasiewicz),
+        ("DRASTIC", None) => Some(TNorms::Drastic),
+        ("NILPOTENT_MIN", None) => Some(TNorms::NilpotentMin),
+        ("EINSTEIN", None) => Some(TNorms::Einstein),
+        ("HAMACHER", None) => Some(TNorms::HamacherProduct),
+        ("YAGER", Some(p)) => Some(TNorms::Yager(p)),
+        ("FRANK", Some(s)) => Some(TNorms::Frank(s)),
+        ("DOMBI", Some(lambda)) => Some(TNorms::Dombi(lambda)),
+        ("SCHWEIZER_SKLAR", Some(p)) => Some(TNorms::SchweizerSklar(p)),
+        ("ACZEL_ALSINA", Some(lambda)) => Some(TNorms::AczelAlsina(lambda)),
+        _ => None,
+    }
+}
+
+fn s_norm_text(s_norm: &SNorms) -> String {
+    match s_norm {
+        SNorms::Max => "MAX".to_string(),
+        SNorms::ProbabilisticSum => "PROBABILISTIC_SUM".to_string(),
+        SNorms::BoundedSum => "BOUNDED_SUM".to_string(),
+        SNorms::DrasticSum => "DRASTIC_SUM".to_string(),
+        SNorms::EinsteinSum => "EINSTEIN_SUM".to_string(),
+        SNorms::HamacherSum => "HAMACHER_SUM".to_string(),
+        SNorms::Yager(p) => format!("YAGER {}", p),
+        SNorms::Frank(s) => format!("FRANK {}", s),
+        SNorms::Dombi(lambda) => format!("DOMBI {}", lambda),
+        SNorms::Dual(..) | SNorms::Custom(_) => "CUSTOM".to_string(),
+    }
+}
+
+fn s_norm_from_text(name: &str, parameter: Option<f64>) -> Option<SNorms> {
+    match (name.to_uppercase().as_str(), parameter) {
+        ("MAX", None) => Some(SNorms::Max),
+        ("PROBABILISTIC_SUM", None) => Some(SNorms::ProbabilisticSum),
+        ("BOUNDED_SUM", None) => Some(SNorms::BoundedSum),
+        ("DRASTIC_SUM", None) => Some(SNorms::DrasticSum),
+        ("EINSTEIN_SUM", None) => Some(SNorms::EinsteinSum),
+        ("HAMACHER_SUM", None) => Some(SNorms::HamacherSum),
+        ("YAGER", Some(p)) => Some(SNorms::Yager(p)),
+        ("FRANK", Some(s)) => Some(SNorms::Frank(s)),
+        ("DOMBI", Some(lambda)) => Some(SNorms::Dombi(lambda)),
+        _ => None,
+    }
+}
+
+fn check_weight(weight: f64) -> Result<(), FuzzyError> {
+    if (0.0..=1.0).contains(&weight) {
+        Ok(())
+    } else {
+        Err(FuzzyError::WeightOutOfRange(weight))
+    }
+}
+
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Rule {
-    relations: Vec<i32>,
+    relations: Vec<Relation>,
     weight: f64,
     method: Kind,
+    // when it is set, `relations` only holds the outputs
+    #[cfg_attr(feature = "serde", serde(default))]
+    antecedent: Option<Expression>,
 }
 
 impl Rule {
-    pub fn new_or(relations: Vec<i32>, weight: f64) -> Self {
-        assert!(weight <= 1.0, "Weight must be less or equal to 1.0");
-        assert!(weight >= 0.0, "Weight must be less or equal to 1.0");
-        Self {
-            relations,
+    pub fn new_or<R: Into<Relation>>(relations: Vec<R>, weight: f64) -> Self {
+        Self::try_new_or(relations, weight).unwrap_or_else(|err| panic!("{}", err))
+    }
+
+    pub fn try_new_or<R: Into<Relation>>(
+        relations: Vec<R>,
+        weight: f64,
+    ) -> Result<Self, FuzzyError> {
+        check_weight(weight)?;
+        Ok(Self {
+            relations: relations.into_iter().map(Into::into).collect(),
             weight,
             method: Kind::OR,
-        }
+            antecedent: None,
+        })
     }
 
-    pub fn new_and(relations: Vec<i32>, weight: f64) -> Self {
-        assert!(weight <= 1.0, "Weight must be less or equal to 1.0");
-        assert!(weight >= 0.0, "Weight must be less or equal to 1.0");
-        Self {
-            relations,
+    pub fn new_and<R: Into<Relation>>(relations: Vec<R>, weight: f64) -> Self {
+        Self::try_new_and(relations, weight).unwrap_or_else(|err| panic!("{}", err))
+    }
+
+    pub fn try_new_and<R: Into<Relation>>(
+        relations: Vec<R>,
+        weight: f64,
+    ) -> Result<Self, FuzzyError> {
+        check_weight(weight)?;
+        Ok(Self {
+            relations: relations.into_iter().map(Into::into).collect(),
             weight,
             method: Kind::AND,
-        }
+            antecedent: None,
+        })
+    }
+
+    // `consequent` has one relation for every output
+    pub fn new_expression<R: Into<Relation>>(
+        antecedent: Expression,
+        consequent: Vec<R>,
+        weight: f64,
+    ) -> Self {
+        Self::try_new_expression(antecedent, consequent, weight)
+            .unwrap_or_else(|err| panic!("{}", err))
     }
-    pub fn get_rules(&self) -> &[i32] {
+
+    pub fn try_new_expression<R: Into<Relation>>(
+        antecedent: Expression,
+        consequent: Vec<R>,
+        weight: f64,
+    ) -> Result<Self, FuzzyError> {
+        check_weight(weight)?;
+        Ok(Self {
+            relations: consequent.into_iter().map(Into::into).collect(),
+            weight,
+            method: Kind::AND,
+            antecedent: Some(antecedent),
+        })
+    }
+
+    pub fn get_rules(&self) -> &[Relation] {
         &self.relations[..]
     }
 
@@ -43,74 +403,552 @@ impl Rule {
         self.weight
     }
 
-    pub fn get_input_rules(&self, input_size: usize) -> &[i32] {
-        &self.relations[..input_size]
+    pub fn get_antecedent(&self) -> Option<&Expression> {
+        self.antecedent.as_ref()
+    }
+
+    // empty for rules with an expression, see `get_conditions`
+    pub fn get_input_rules(&self, input_size: usize) -> &[Relation] {
+        match self.antecedent {
+            Some(_) => &[],
+            None => &self.relations[..input_size],
+        }
     }
 
-    pub fn get_output_rules(&self, input_size: usize) -> &[i32] {
-        &self.relations[input_size..]
+    pub fn get_output_rules(&self, input_size: usize) -> &[Relation] {
+        match self.antecedent {
+            Some(_) => &self.relations,
+            None => &self.relations[input_size..],
+        }
+    }
+
+    // (input, relation) of every condition of the rule
+    pub fn get_conditions(&self, input_size: usize) -> Vec<(usize, &Relation)> {
+        match &self.antecedent {
+            Some(antecedent) => antecedent.get_leaves(),
+            None => self.relations[..input_size].iter().enumerate().collect(),
+        }
+    }
+
+    pub fn parse<I: NamedTerms, O: NamedTerms>(
+        text: &str,
+        inputs: &[I],
+        outputs: &[O],
+    ) -> Result<Self, RuleParseError> {
+        RuleParser::new(text).rule(inputs, outputs)
+    }
+
+    pub fn to_text<I: NamedTerms, O: NamedTerms>(&self, inputs: &[I], outputs: &[O]) -> String {
+        let consequent: Vec<String> = outputs
+            .iter()
+            .zip(self.get_output_rules(inputs.len()))
+            .filter_map(|(output, relation)| clause_text(output, relation))
+            .collect();
+        self.text(inputs, consequent)
+    }
+
+    fn text<I: NamedTerms>(&self, inputs: &[I], consequent: Vec<String>) -> String {
+        let antecedent = match &self.antecedent {
+            Some(antecedent) => antecedent.to_text(inputs),
+            None => {
+                let connective = match self.method {
+                    Kind::AND => " AND ",
+                    Kind::OR => " OR ",
+                };
+                // don't care terms are left out
+                let clauses: Vec<String> = inputs
+                    .iter()
+                    .zip(self.get_input_rules(inputs.len()))
+                    .filter_map(|(input, relation)| clause_text(input, relation))
+                    .collect();
+                match clauses.is_empty() {
+                    true => "ANY".to_string(),
+                    false => clauses.join(connective),
+                }
+            }
+        };
+        let mut text = format!("IF {} THEN {}", antecedent, consequent.join(", "));
+        if self.weight != 1.0 {
+            text += &format!(" WITH {}", self.weight);
+        }
+        text
     }
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone, PartialEq)]
+pub struct RuleParseError {
+    pub column: usize,
+    pub message: String,
+}
+
+impl fmt::Display for RuleParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "column {}: {}", self.column, self.message)
+    }
+}
+
+impl std::error::Error for RuleParseError {}
+
+struct RuleParser<'a> {
+    tokens: Vec<(usize, &'a str)>,
+    position: usize,
+    end: usize,
+}
+
+impl<'a> RuleParser<'a> {
+    fn new(text: &'a str) -> Self {
+        let mut tokens = Vec::new();
+        let mut start: Option<usize> = None;
+        for (i, c) in text.char_indices() {
+            let symbol = matches!(c, ',' | '(' | ')' | '[' | ']');
+            if c.is_whitespace() || symbol {
+                if let Some(s) = start.take() {
+                    tokens.push((s, &text[s..i]));
+                }
+                if symbol {
+                    tokens.push((i, &text[i..i + 1]));
+                }
+            } else if start.is_none() {
+                start = Some(i);
+            }
+        }
+        if let Some(s) = start {
+            tokens.push((s, &text[s..]));
+        }
+        let column = |i: usize| text[..i].chars().count() + 1;
+        Self {
+            tokens: tokens.into_iter().map(|(i, t)| (column(i), t)).collect(),
+            position: 0,
+            end: text.chars().count() + 1,
+        }
+    }
+
+    fn error<T>(&self, message: impl Into<String>) -> Result<T, RuleParseError> {
+        let column = match self.tokens.get(self.position) {
+            Some((column, _)) => *column,
+            None => self.end,
+        };
+        Err(RuleParseError {
+            column,
+            message: message.into(),
+        })
+    }
+
+    fn peek(&self) -> Option<&'a str> {
+        self.tokens.get(self.position).map(|(_, token)| *token)
+    }
+
+    fn is_keyword(&self, keyword: &str) -> bool {
+        self.peek()
+            .is_some_and(|token| token.eq_ignore_ascii_case(keyword))
+    }
+
+    fn keyword(&mut self, keyword: &str) -> Result<(), RuleParseError> {
+        if self.is_keyword(keyword) {
+            self.position += 1;
+            Ok(())
+        } else {
+            self.error(format!("expected `{}`", keyword))
+        }
+    }
+
+    fn name(&mut self) -> Result<&'a str, RuleParseError> {
+        match self.peek() {
+            Some(",") | Some("(") | Some(")") | Some("[") | Some("]") | None => {
+                self.error("expected a name")
+            }
+            Some(name) => {
+                self.position += 1;
+                Ok(name)
+            }
+        }
+    }
+
+    // `x IS [NOT] [hedge] term`
+    fn term<V: NamedTerms>(
+        &mut self,
+        variables: &[V],
+    ) -> Result<(usize, Relation), RuleParseError> {
+        let name = self.name()?;
+        let index = match variables.iter().position(|v| v.variable_name() == name) {
+            Some(index) => index,
+            None => {
+                self.position -= 1;
+                return self.error(format!("unknown variable `{}`", name));
+            }
+        };
+        self.keyword("IS")?;
+        let complement = self.is_keyword("NOT");
+        if complement {
+            self.position += 1;
+        }
+        let hedge = self.peek().and_then(Hedges::from_keyword);
+        if hedge.is_some() {
+            if complement {
+                return self.error("NOT of a hedged term is not supported");
+            }
+            self.position += 1;
+        }
+        let term = self.name()?;
+        let term = match variables[index].term_names().iter().position(|t| t == term) {
+            Some(term) => match hedge {
+                Some(hedge) => Relation::Hedged(hedge, term),
+                None if complement => Relation::Not(term),
+                None => Relation::Term(term),
+            },
+            None => {
+                self.position -= 1;
+                return self.error(format!("`{}` is not a term of `{}`", term, name));
+            }
+        };
+        Ok((index, term))
+    }
+
+    fn clause<V: NamedTerms>(
+        &mut self,
+        variables: &[V],
+        relations: &mut [Option<Relation>],
+    ) -> Result<(), RuleParseError> {
+        let start = self.position;
+        let (index, relation) = self.term(variables)?;
+        if relations[index].is_some() {
+            self.position = start;
+            return self.error(format!(
+                "`{}` is used twice",
+                variables[index].variable_name()
+            ));
+        }
+        relations[index] = Some(relation);
+        Ok(())
+    }
+
+    // the `[name parameter]` after a connective
+    fn operator<T>(
+        &mut self,
+        from_text: fn(&str, Option<f64>) -> Option<T>,
+        kind: &str,
+    ) -> Result<Option<T>, RuleParseError> {
+        if !self.is_keyword("[") {
+            return Ok(None);
+        }
+        self.position += 1;
+        let start = self.position;
+        let name = self.name()?;
+        let parameter = match self.peek().map(|token| token.parse::<f64>()) {
+            Some(Ok(parameter)) => {
+                self.position += 1;
+                Some(parameter)
+            }
+            _ => None,
+        };
+        match from_text(name, parameter) {
+            Some(operator) => {
+                self.keyword("]")?;
+                Ok(Some(operator))
+            }
+            None => {
+                self.position = start;
+                self.error(format!("`{}` is not a {}", name, kind))
+            }
+        }
+    }
+
+    // OR binds weaker than AND, NOT binds stronger
+    fn or_expression<I: NamedTerms>(&mut self, inputs: &[I]) -> Result<Expression, RuleParseError> {
+        let mut expressions = vec![self.and_expression(inputs)?];
+        let mut s_norm = None;
+        while self.is_keyword("OR") {
+            let start = self.position;
+            self.position += 1;
+            let own = self.operator(s_norm_from_text, "s-norm")?;
+            if expressions.len() > 1
+                && own.as_ref().map(s_norm_text) != s_norm.as_ref().map(s_norm_text)
+            {
+                self.position = start;
+                return self.error("mixed OR operators need parentheses");
+            }
+            s_norm = own;
+            expressions.push(self.and_expression(inputs)?);
+        }
+        Ok(match expressions.len() {
+            1 => expressions.remove(0),
+            _ => Expression::Or(expressions, s_norm),
+        })
+    }
+
+    fn and_expression<I: NamedTerms>(
+        &mut self,
+        inputs: &[I],
+    ) -> Result<Expression, RuleParseError> {
+        let mut expressions = vec![self.unary_expression(inputs)?];
+        let mut t_norm = None;
+        while self.is_keyword("AND") {
+            let start = self.position;
+            self.position += 1;
+            let own = self.operator(t_norm_from_text, "t-norm")?;
+            if expressions.len() > 1
+                && own.as_ref().map(t_norm_text) != t_norm.as_ref().map(t_norm_text)
+            {
+                self.position = start;
+                return self.error("mixed AND operators need parentheses");
+            }
+            t_norm = own;
+            expressions.push(self.unary_expression(inputs)?);
+        }
+        Ok(match expressions.len() {
+            1 => expressions.remove(0),
+            _ => Expression::And(expressions, t_norm),
+        })
+    }
+
+    fn unary_expression<I: NamedTerms>(
+        &mut self,
+        inputs: &[I],
+    ) -> Result<Expression, RuleParseError> {
+        if self.is_keyword("NOT") {
+            self.position += 1;
+            return Ok(Expression::negate(self.unary_expression(inputs)?));
+        }
+        if self.is_keyword("(") {
+            self.position += 1;
+            let expression = self.or_expression(inputs)?;
+            self.keyword(")")?;
+            return Ok(expression);
+        }
+        let (input, relation) = self.term(inputs)?;
+        Ok(Expression::Is(input, relation))
+    }
+
+    fn rule<I: NamedTerms, O: NamedTerms>(
+        &mut self,
+        inputs: &[I],
+        outputs: &[O],
+    ) -> Result<Rule, RuleParseError> {
+        self.keyword("IF")?;
+        // `IF ANY THEN ...` always fires
+        let antecedent = match self.is_keyword("ANY") {
+            true => {
+                self.position += 1;
+                Expression::and(Vec::new())
+            }
+            false => self.or_expression(inputs)?,
+        };
+        self.keyword("THEN")?;
+        let mut consequent = vec![None; outputs.len()];
+        loop {
+            self.clause(outputs, &mut consequent)?;
+            if self.is_keyword("AND") || self.is_keyword(",") {
+                self.position += 1;
+            } else {
+                break;
+            }
+        }
+        let mut weight = 1.0;
+        if self.is_keyword("WITH") {
+            self.position += 1;
+            weight = match self.peek().map(|token| token.parse::<f64>()) {
+                Some(Ok(weight)) if (0.0..=1.0).contains(&weight) => weight,
+                _ => return self.error("expected a weight between 0.0 and 1.0"),
+            };
+            self.position += 1;
+        }
+        if self.peek().is_some() {
+            return self.error("unexpected text after the rule");
+        }
+
+        // outputs that are not mentioned are don't care
+        let consequent: Vec<Relation> = consequent
+            .into_iter()
+            .map(|relation| relation.unwrap_or(Relation::Any))
+            .collect();
+        // rules with one connective keep the simple form
+        Ok(match antecedent.flatten(inputs.len()) {
+            Some((kind, mut relations)) => {
+                relations.extend(consequent);
+                match kind {
+                    Kind::OR => Rule::new_or(relations, weight),
+                    Kind::AND => Rule::new_and(relations, weight),
+                }
+            }
+            None => Rule::new_expression(antecedent, consequent, weight),
+        })
+    }
+}
+
+// A TSK consequent, either a term of the output or a function given in the rule itself.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum OutputRelation {
-    Constant,
-    Linear,
-    Custom,
+    Term(Relation),
+    Function(TSKMembershipFunction),
 }
 
-#[derive(Debug)]
+impl From<Relation> for OutputRelation {
+    fn from(relation: Relation) -> Self {
+        Self::Term(relation)
+    }
+}
+
+impl From<TSKMembershipFunction> for OutputRelation {
+    fn from(function: TSKMembershipFunction) -> Self {
+        Self::Function(function)
+    }
+}
+
+impl OutputRelation {
+    pub fn get_term(&self) -> Option<usize> {
+        match self {
+            Self::Term(relation) => relation.get_term(),
+            Self::Function(_) => None,
+        }
+    }
+
+    // None when the rule doesn't care about the output
+    pub fn get_mu(&self, output: &TSKOutputVariable, input_vec: &Vec<f64>) -> Option<f64> {
+        match self {
+            Self::Term(relation) => Some(output.get_mu(relation.get_term()?, input_vec)),
+            Self::Function(function) => Some(function.get_mu(input_vec)),
+        }
+    }
+
+    fn to_text(&self, output: &TSKOutputVariable) -> Option<String> {
+        let name = output.variable_name();
+        match self {
+            Self::Term(relation) => clause_text(output, relation),
+            Self::Function(TSKMembershipFunction::Constant(c)) => {
+                Some(format!("{} IS {}", name, c))
+            }
+            Self::Function(TSKMembershipFunction::Custom(_)) => Some(format!("{} IS CUSTOM", name)),
+            Self::Function(function) => Some(format!("{} IS {:?}", name, function)),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct TSKRule {
-    input_relations: Vec<i32>,
+    // the inputs of the rule, it has no outputs
+    premise: Rule,
     output_relations: Vec<OutputRelation>,
-    weight: f64,
-    method: Kind,
 }
 
 impl TSKRule {
-    pub fn new_or(
-        input_relations: Vec<i32>,
+    pub fn new_or<R: Into<Relation>>(
+        input_relations: Vec<R>,
         output_relations: Vec<OutputRelation>,
         weight: f64,
     ) -> Self {
-        assert!(weight <= 1.0, "Weight must be less or equal to 1.0");
-        assert!(weight >= 0.0, "Weight must be less or equal to 1.0");
-        Self {
-            input_relations,
+        Self::try_new_or(input_relations, output_relations, weight)
+            .unwrap_or_else(|err| panic!("{}", err))
+    }
+
+    pub fn try_new_or<R: Into<Relation>>(
+        input_relations: Vec<R>,
+        output_relations: Vec<OutputRelation>,
+        weight: f64,
+    ) -> Result<Self, FuzzyError> {
+        Ok(Self {
+            premise: Rule::try_new_or(input_relations, weight)?,
             output_relations,
-            weight,
-            method: Kind::OR,
-        }
+        })
     }
 
-    pub fn new_and(
-        input_relations: Vec<i32>,
+    pub fn new_and<R: Into<Relation>>(
+        input_relations: Vec<R>,
         output_relations: Vec<OutputRelation>,
         weight: f64,
     ) -> Self {
-        assert!(weight <= 1.0, "Weight must be less or equal to 1.0");
-        assert!(weight >= 0.0, "Weight must be less or equal to 1.0");
-        Self {
-            input_relations,
+        Self::try_new_and(input_relations, output_relations, weight)
+            .unwrap_or_else(|err| panic!("{}", err))
+    }
+
+    pub fn try_new_and<R: Into<Relation>>(
+        input_relations: Vec<R>,
+        output_relations: Vec<OutputRelation>,
+        weight: f64,
+    ) -> Result<Self, FuzzyError> {
+        Ok(Self {
+            premise: Rule::try_new_and(input_relations, weight)?,
             output_relations,
-            weight,
-            method: Kind::AND,
+        })
+    }
+
+    pub fn new_expression(
+        antecedent: Expression,
+        output_relations: Vec<OutputRelation>,
+        weight: f64,
+    ) -> Self {
+        Self::try_new_expression(antecedent, output_relations, weight)
+            .unwrap_or_else(|err| panic!("{}", err))
+    }
+
+    pub fn try_new_expression(
+        antecedent: Expression,
+        output_relations: Vec<OutputRelation>,
+        weight: f64,
+    ) -> Result<Self, FuzzyError> {
+        Ok(Self {
+            premise: Rule::try_new_expression(antecedent, Vec::<Relation>::new(), weight)?,
+            output_relations,
+        })
+    }
+
+    // the outputs of `rule` become terms of the TSK outputs
+    pub fn from_rule(rule: Rule, input_size: usize) -> Self {
+        let mut relations = rule.relations;
+        let consequent = match rule.antecedent {
+            Some(_) => std::mem::take(&mut relations),
+            None => relations.split_off(input_size.min(relations.len())),
+        };
+        Self {
+            premise: Rule { relations, ..rule },
+            output_relations: consequent.into_iter().map(OutputRelation::Term).collect(),
         }
     }
 
     pub fn get_kind(&self) -> &Kind {
-        &self.method
+        self.premise.get_kind()
     }
 
     pub fn get_weight(&self) -> f64 {
-        self.weight
+        self.premise.get_weight()
+    }
+
+    pub fn get_antecedent(&self) -> Option<&Expression> {
+        self.premise.get_antecedent()
     }
 
-    pub fn get_input_rules(&self) -> &[i32] {
-        &self.input_relations
+    // empty for rules with an expression, see `get_conditions`
+    pub fn get_input_rules(&self) -> &[Relation] {
+        self.premise.get_rules()
     }
 
     pub fn get_output_rules(&self) -> &[OutputRelation] {
         &self.output_relations
     }
+
+    pub fn get_conditions(&self) -> Vec<(usize, &Relation)> {
+        self.premise.get_conditions(self.premise.relations.len())
+    }
+
+    pub fn to_text<I: NamedTerms>(&self, inputs: &[I], outputs: &[TSKOutputVariable]) -> String {
+        let consequent: Vec<String> = outputs
+            .iter()
+            .zip(&self.output_relations)
+            .filter_map(|(output, relation)| relation.to_text(output))
+            .collect();
+        self.premise.text(inputs, consequent)
+    }
+}
+
+impl AsRef<Rule> for TSKRule {
+    fn as_ref(&self) -> &Rule {
+        &self.premise
+    }
+}
+
+impl AsRef<Rule> for Rule {
+    fn as_ref(&self) -> &Rule {
+        self
+    }
 }
@@ -1,6 +1,24 @@
-#[derive(Debug)]
+use crate::complements::Complements;
+use crate::t_norms::TNorms;
+
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum SNorms {
     Max,
+    ProbabilisticSum,
+    BoundedSum,
+    DrasticSum,
+    EinsteinSum,
+    HamacherSum,
+    // p > 0
+    Yager(f64),
+    // s > 0
+    Frank(f64),
+    // lambda > 0
+    Dombi(f64),
+    // N(T(N(a), N(b), ...)) for a t-norm T and an involutive complement N
+    Dual(TNorms, Complements),
+    #[cfg_attr(feature = "serde", serde(skip))]
     Custom(fn(&[f64]) -> f64),
 }
 
@@ -8,9 +26,41 @@ impl SNorms {
     pub fn s_norm(&self, fuzzified: &[f64]) -> f64 {
         match self {
             Self::Max => max(fuzzified),
+            Self::ProbabilisticSum => pairwise(fuzzified, probabilistic_sum),
+            Self::BoundedSum => bounded_sum(fuzzified),
+            Self::DrasticSum => drastic_sum(fuzzified),
+            Self::EinsteinSum => pairwise(fuzzified, einstein_sum),
+            Self::HamacherSum => pairwise(fuzzified, hamacher_sum),
+            Self::Yager(p) => yager(fuzzified, *p),
+            Self::Frank(s) => dual(&TNorms::Frank(*s), &Complements::Standard, fuzzified),
+            Self::Dombi(lambda) => dombi(fuzzified, *lambda),
+            Self::Dual(t_norm, complement) => dual(t_norm, complement, fuzzified),
             Self::Custom(c) => c(fuzzified),
         }
     }
+
+    // the s-norm paired with `t_norm` by the standard complement
+    pub fn dual_of(t_norm: &TNorms) -> Self {
+        match t_norm {
+            TNorms::Min => Self::Max,
+            TNorms::Product => Self::ProbabilisticSum,
+            TNorms::Lukasiewicz => Self::BoundedSum,
+            TNorms::Drastic => Self::DrasticSum,
+            TNorms::Einstein => Self::EinsteinSum,
+            TNorms::HamacherProduct => Self::HamacherSum,
+            TNorms::Yager(p) => Self::Yager(*p),
+            TNorms::Frank(s) => Self::Frank(*s),
+            TNorms::Dombi(lambda) => Self::Dombi(*lambda),
+            t_norm => Self::Dual(t_norm.clone(), Complements::Standard),
+        }
+    }
+
+    pub fn dual_with(t_norm: &TNorms, complement: Complements) -> Self {
+        match complement {
+            Complements::Standard => Self::dual_of(t_norm),
+            complement => Self::Dual(t_norm.clone(), complement),
+        }
+    }
 }
 
 fn max(fuzzified: &[f64]) -> f64 {
@@ -21,3 +71,68 @@ fn max(fuzzified: &[f64]) -> f64 {
         .unwrap()
         .to_owned()
 }
+
+// the binary operator is applied from left to right
+fn pairwise(fuzzified: &[f64], operator: fn(f64, f64) -> f64) -> f64 {
+    assert_ne!(fuzzified.len(), 0);
+    fuzzified.iter().copied().reduce(operator).unwrap()
+}
+
+fn dual(t_norm: &TNorms, complement: &Complements, fuzzified: &[f64]) -> f64 {
+    let complemented: Vec<f64> = fuzzified
+        .iter()
+        .map(|mu| complement.complement(*mu))
+        .collect();
+    complement.complement(t_norm.t_norm(&complemented))
+}
+
+fn probabilistic_sum(a: f64, b: f64) -> f64 {
+    a + b - a * b
+}
+
+fn bounded_sum(fuzzified: &[f64]) -> f64 {
+    assert_ne!(fuzzified.len(), 0);
+    fuzzified.iter().sum::<f64>().min(1.0)
+}
+
+fn drastic_sum(fuzzified: &[f64]) -> f64 {
+    assert_ne!(fuzzified.len(), 0);
+    if fuzzified.iter().filter(|mu| **mu > 0.0).count() <= 1 {
+        max(fuzzified)
+    } else {
+        1.0
+    }
+}
+
+fn einstein_sum(a: f64, b: f64) -> f64 {
+    (a + b) / (1.0 + a * b)
+}
+
+fn hamacher_sum(a: f64, b: f64) -> f64 {
+    if a == 1.0 || b == 1.0 {
+        1.0
+    } else {
+        (a + b - 2.0 * a * b) / (1.0 - a * b)
+    }
+}
+
+fn yager(fuzzified: &[f64], p: f64) -> f64 {
+    assert_ne!(fuzzified.len(), 0);
+    let sum: f64 = fuzzified.iter().map(|mu| mu.powf(p)).sum();
+    sum.powf(1.0 / p).min(1.0)
+}
+
+fn dombi(fuzzified: &[f64], lambda: f64) -> f64 {
+    assert_ne!(fuzzified.len(), 0);
+    if fuzzified.contains(&1.0) {
+        return 1.0;
+    }
+    if fuzzified.iter().all(|mu| *mu == 0.0) {
+        return 0.0;
+    }
+    let sum: f64 = fuzzified
+        .iter()
+        .map(|mu| (mu / (1.0 - mu)).powf(lambda))
+        .sum();
+    1.0 / (1.0 + sum.powf(-1.0 / lambda))
+}
@@ -0,0 +1,164 @@
+use serde::de::Error;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::aggregations::Aggregations;
+use crate::complements::Complements;
+use crate::defuzzifications::{Defuzzifiers, Fallbacks, TSKDefuzzifiers};
+use crate::fuzzy_inference_systems::{MamdaniFIS, TSKFIS};
+use crate::implications::{Implications, Reasoning};
+use crate::rules::{Rule, TSKRule};
+use crate::s_norms::SNorms;
+use crate::t_norms::TNorms;
+use crate::variables::{InputVariable, OutputVariable, TSKOutputVariable};
+
+// Bump this whenever the serialized layout of a system changes.
+pub const SCHEMA_VERSION: u32 = 3;
+
+#[derive(Serialize)]
+struct MamdaniSystemRef<'a> {
+    version: u32,
+    s_norm: &'a SNorms,
+    t_norm: &'a TNorms,
+    complement: &'a Complements,
+    implication: &'a Implications,
+    aggregation: &'a Aggregations,
+    reasoning: Reasoning,
+    defuzzifier: &'a Defuzzifiers,
+    fallback: &'a Fallbacks,
+    rules: &'a Vec<Rule>,
+    inputs: &'a Vec<InputVariable>,
+    outputs: &'a Vec<OutputVariable>,
+}
+
+#[derive(Deserialize)]
+struct MamdaniSystem {
+    version: u32,
+    s_norm: SNorms,
+    t_norm: TNorms,
+    // older files do not have it
+    #[serde(default)]
+    complement: Complements,
+    implication: Implications,
+    aggregation: Aggregations,
+    #[serde(default)]
+    reasoning: Reasoning,
+    defuzzifier: Defuzzifiers,
+    #[serde(default)]
+    fallback: Fallbacks,
+    rules: Vec<Rule>,
+    inputs: Vec<InputVariable>,
+    outputs: Vec<OutputVariable>,
+}
+
+#[derive(Serialize)]
+struct TSKSystemRef<'a> {
+    version: u32,
+    s_norm: &'a SNorms,
+    t_norm: &'a TNorms,
+    complement: &'a Complements,
+    defuzzification: &'a TSKDefuzzifiers,
+    rules: &'a Vec<TSKRule>,
+    inputs: &'a Vec<InputVariable>,
+    outputs: &'a Vec<TSKOutputVariable>,
+}
+
+#[derive(Deserialize)]
+struct TSKSystem {
+    version: u32,
+    s_norm: SNorms,
+    t_norm: TNorms,
+    // older files do not have it
+    #[serde(default)]
+    complement: Complements,
+    defuzzification: TSKDefuzzifiers,
+    rules: Vec<TSKRule>,
+    inputs: Vec<InputVariable>,
+    outputs: Vec<TSKOutputVariable>,
+}
+
+fn check_version<E: Error>(version: u32) -> Result<(), E> {
+    if version == SCHEMA_VERSION {
+        Ok(())
+    } else {
+        Err(E::custom(format!(
+            "unsupported schema version {}, expected {}",
+            version, SCHEMA_VERSION
+        )))
+    }
+}
+
+impl Serialize for MamdaniFIS {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        MamdaniSystemRef {
+            version: SCHEMA_VERSION,
+            s_norm: self.get_s_norm_method(),
+            t_norm: self.get_t_norm_method(),
+            complement: self.get_complement_method(),
+            implication: self.get_implication_method(),
+            aggregation: self.get_aggregation_method(),
+            reasoning: self.get_reasoning_method(),
+            defuzzifier: self.get_defuzzifier_method(),
+            fallback: self.get_fallback_method(),
+            rules: self.get_rule_base(),
+            inputs: self.get_inputs(),
+            outputs: self.get_outputs(),
+        }
+        .serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for MamdaniFIS {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let fis = MamdaniSystem::deserialize(deserializer)?;
+        check_version(fis.version)?;
+        let mut mamdani = MamdaniFIS::new_all(
+            fis.s_norm,
+            fis.t_norm,
+            fis.implication,
+            fis.aggregation,
+            fis.defuzzifier,
+            fis.rules,
+            fis.inputs,
+            fis.outputs,
+        );
+        mamdani.set_complement(fis.complement);
+        mamdani.set_reasoning(fis.reasoning);
+        mamdani.set_fallback(fis.fallback);
+        Ok(mamdani)
+    }
+}
+
+impl Serialize for TSKFIS {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        TSKSystemRef {
+            version: SCHEMA_VERSION,
+            s_norm: self.get_s_norm_method(),
+            t_norm: self.get_t_norm_method(),
+            complement: self.get_complement_method(),
+            defuzzification: self.get_defuzzification_method(),
+            rules: self.get_rule_base(),
+            inputs: self.get_inputs(),
+            outputs: self.get_outputs(),
+        }
+        .serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for TSKFIS {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let fis = TSKSystem::deserialize(deserializer)?;
+        check_version(fis.version)?;
+        let mut tsk = TSKFIS::new(fis.s_norm, fis.t_norm, fis.defuzzification);
+        tsk.set_complement(fis.complement);
+        for input in fis.inputs {
+            tsk.add_input(input);
+        }
+        for output in fis.outputs {
+            tsk.add_output(output);
+        }
+        for rule in fis.rules {
+            tsk.add_tsk_rule(rule);
+        }
+        Ok(tsk)
+    }
+}
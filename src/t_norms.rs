@@ -1,7 +1,24 @@
-#[derive(Debug)]
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum TNorms {
     Min,
     Product,
+    Lukasiewicz,
+    Drastic,
+    NilpotentMin,
+    Einstein,
+    HamacherProduct,
+    // p > 0
+    Yager(f64),
+    // s > 0
+    Frank(f64),
+    // lambda > 0
+    Dombi(f64),
+    // any p, 0.0 is the product
+    SchweizerSklar(f64),
+    // lambda > 0
+    AczelAlsina(f64),
+    #[cfg_attr(feature = "serde", serde(skip))]
     Custom(fn(&[f64]) -> f64),
 }
 
@@ -10,6 +27,16 @@ impl TNorms {
         match self {
             Self::Min => min(fuzzified),
             Self::Product => product(fuzzified),
+            Self::Lukasiewicz => lukasiewicz(fuzzified),
+            Self::Drastic => drastic(fuzzified),
+            Self::NilpotentMin => pairwise(fuzzified, nilpotent_min),
+            Self::Einstein => pairwise(fuzzified, einstein),
+            Self::HamacherProduct => pairwise(fuzzified, hamacher_product),
+            Self::Yager(p) => yager(fuzzified, *p),
+            Self::Frank(s) => frank(fuzzified, *s),
+            Self::Dombi(lambda) => dombi(fuzzified, *lambda),
+            Self::SchweizerSklar(p) => schweizer_sklar(fuzzified, *p),
+            Self::AczelAlsina(lambda) => aczel_alsina(fuzzified, *lambda),
             Self::Custom(c) => c(fuzzified),
             // _ => 0.0,
         }
@@ -29,3 +56,91 @@ fn product(fuzzified: &[f64]) -> f64 {
     assert_ne!(fuzzified.len(), 0);
     fuzzified.iter().product()
 }
+
+// the binary operator is applied from left to right
+fn pairwise(fuzzified: &[f64], operator: fn(f64, f64) -> f64) -> f64 {
+    assert_ne!(fuzzified.len(), 0);
+    fuzzified.iter().copied().reduce(operator).unwrap()
+}
+
+fn lukasiewicz(fuzzified: &[f64]) -> f64 {
+    assert_ne!(fuzzified.len(), 0);
+    let n = fuzzified.len() as f64;
+    (fuzzified.iter().sum::<f64>() - (n - 1.0)).max(0.0)
+}
+
+fn drastic(fuzzified: &[f64]) -> f64 {
+    assert_ne!(fuzzified.len(), 0);
+    if fuzzified.iter().filter(|mu| **mu < 1.0).count() <= 1 {
+        min(fuzzified)
+    } else {
+        0.0
+    }
+}
+
+fn nilpotent_min(a: f64, b: f64) -> f64 {
+    if a + b > 1.0 {
+        a.min(b)
+    } else {
+        0.0
+    }
+}
+
+fn einstein(a: f64, b: f64) -> f64 {
+    a * b / (2.0 - (a + b - a * b))
+}
+
+fn hamacher_product(a: f64, b: f64) -> f64 {
+    if a == 0.0 && b == 0.0 {
+        0.0
+    } else {
+        a * b / (a + b - a * b)
+    }
+}
+
+fn yager(fuzzified: &[f64], p: f64) -> f64 {
+    assert_ne!(fuzzified.len(), 0);
+    let sum: f64 = fuzzified.iter().map(|mu| (1.0 - mu).powf(p)).sum();
+    (1.0 - sum.powf(1.0 / p)).max(0.0)
+}
+
+fn frank(fuzzified: &[f64], s: f64) -> f64 {
+    assert_ne!(fuzzified.len(), 0);
+    if s == 1.0 {
+        return product(fuzzified);
+    }
+    let n = fuzzified.len() as i32;
+    let numerator: f64 = fuzzified.iter().map(|mu| s.powf(*mu) - 1.0).product();
+    (1.0 + numerator / (s - 1.0).powi(n - 1)).ln() / s.ln()
+}
+
+fn dombi(fuzzified: &[f64], lambda: f64) -> f64 {
+    assert_ne!(fuzzified.len(), 0);
+    if fuzzified.contains(&0.0) {
+        return 0.0;
+    }
+    let sum: f64 = fuzzified
+        .iter()
+        .map(|mu| ((1.0 - mu) / mu).powf(lambda))
+        .sum();
+    1.0 / (1.0 + sum.powf(1.0 / lambda))
+}
+
+fn schweizer_sklar(fuzzified: &[f64], p: f64) -> f64 {
+    assert_ne!(fuzzified.len(), 0);
+    if p == 0.0 {
+        return product(fuzzified);
+    }
+    if p < 0.0 && fuzzified.contains(&0.0) {
+        return 0.0;
+    }
+    let n = fuzzified.len() as f64;
+    let sum: f64 = fuzzified.iter().map(|mu| mu.powf(p)).sum();
+    (sum - (n - 1.0)).max(0.0).powf(1.0 / p)
+}
+
+fn aczel_alsina(fuzzified: &[f64], lambda: f64) -> f64 {
+    assert_ne!(fuzzified.len(), 0);
+    let sum: f64 = fuzzified.iter().map(|mu| (-mu.ln()).powf(lambda)).sum();
+    (-sum.powf(1.0 / lambda)).exp()
+}
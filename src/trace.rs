@@ -0,0 +1,233 @@
+use std::fmt;
+
+use crate::variables::InputVariable;
+
+// Every stage of one evaluation of a system, see `compute_outputs_traced`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct InferenceTrace {
+    inputs: Vec<InputTrace>,
+    rules: Vec<RuleTrace>,
+    outputs: Vec<OutputTrace>,
+}
+
+impl InferenceTrace {
+    pub(crate) fn new(
+        inputs: Vec<InputTrace>,
+        rules: Vec<RuleTrace>,
+        outputs: Vec<OutputTrace>,
+    ) -> Self {
+        Self {
+            inputs,
+            rules,
+            outputs,
+        }
+    }
+
+    pub fn get_inputs(&self) -> &Vec<InputTrace> {
+        &self.inputs
+    }
+
+    pub fn get_rules(&self) -> &Vec<RuleTrace> {
+        &self.rules
+    }
+
+    pub fn get_outputs(&self) -> &Vec<OutputTrace> {
+        &self.outputs
+    }
+
+    // the crisp outputs, the same as `compute_outputs`
+    pub fn get_values(&self) -> Vec<f64> {
+        self.outputs.iter().map(|output| output.value).collect()
+    }
+}
+
+// the degree of every term of an input
+#[derive(Debug, Clone, PartialEq)]
+pub struct InputTrace {
+    name: String,
+    value: f64,
+    degrees: Vec<(String, f64)>,
+}
+
+impl InputTrace {
+    pub(crate) fn new(input: &InputVariable, value: f64) -> Self {
+        Self {
+            name: input.get_name().clone(),
+            value,
+            degrees: input
+                .get_memberships()
+                .iter()
+                .enumerate()
+                .map(|(i, mf)| (mf.get_name().clone(), input.fuzzify(i, value)))
+                .collect(),
+        }
+    }
+
+    pub fn get_name(&self) -> &String {
+        &self.name
+    }
+
+    pub fn get_value(&self) -> f64 {
+        self.value
+    }
+
+    pub fn get_degrees(&self) -> &Vec<(String, f64)> {
+        &self.degrees
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct RuleTrace {
+    text: String,
+    firing: f64,
+    weighted: f64,
+}
+
+impl RuleTrace {
+    pub(crate) fn new(text: String, firing: f64, weighted: f64) -> Self {
+        Self {
+            text,
+            firing,
+            weighted,
+        }
+    }
+
+    pub fn get_text(&self) -> &String {
+        &self.text
+    }
+
+    // before the weight of the rule
+    pub fn get_firing(&self) -> f64 {
+        self.firing
+    }
+
+    pub fn get_weighted(&self) -> f64 {
+        self.weighted
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum OutputStage {
+    // Mamdani, the implied set of every rule (None if it does not care about the output)
+    // and their aggregation, all of them on the universe of the output. The value of a
+    // continuous output is integrated from the shapes, so for it the sets are only samples.
+    Sets {
+        universe: Vec<f64>,
+        implied: Vec<Option<Vec<f64>>>,
+        aggregated: Vec<f64>,
+        continuous: bool,
+    },
+    // TSK, the consequent of every rule (None if it does not care about the output)
+    Consequents(Vec<Option<f64>>),
+}
+
+impl OutputStage {
+    // what the rule gives to the output: the height of its set or its consequent
+    fn rule_value(&self, rule: usize) -> Option<f64> {
+        match self {
+            Self::Sets { implied, .. } => implied[rule]
+                .as_ref()
+                .map(|set| set.iter().cloned().fold(0.0, f64::max)),
+            Self::Consequents(values) => values[rule],
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct OutputTrace {
+    name: String,
+    stage: OutputStage,
+    value: f64,
+    fallback: bool,
+}
+
+impl OutputTrace {
+    pub(crate) fn new(name: String, stage: OutputStage, value: f64, fallback: bool) -> Self {
+        Self {
+            name,
+            stage,
+            value,
+            fallback,
+        }
+    }
+
+    pub fn get_name(&self) -> &String {
+        &self.name
+    }
+
+    pub fn get_stage(&self) -> &OutputStage {
+        &self.stage
+    }
+
+    pub fn get_value(&self) -> f64 {
+        self.value
+    }
+
+    pub fn is_fallback(&self) -> bool {
+        self.fallback
+    }
+}
+
+// The rule table has a column for every output, with the height of the implied set of the
+// rule (Mamdani) or its consequent (TSK).
+impl fmt::Display for InferenceTrace {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "inputs")?;
+        for input in &self.inputs {
+            writeln!(f, "  {} = {}", input.name, input.value)?;
+            let width = input.degrees.iter().map(|(term, _)| term.len()).max();
+            for (term, degree) in &input.degrees {
+                writeln!(f, "    {:<w$}  {:.4}", term, degree, w = width.unwrap_or(0))?;
+            }
+        }
+
+        writeln!(f, "rules")?;
+        let number = self.rules.len().to_string().len().max(1);
+        let widths: Vec<usize> = self.outputs.iter().map(|o| o.name.len().max(6)).collect();
+        write!(
+            f,
+            "  {:>w$}  {:>8}  {:>8}",
+            "#",
+            "firing",
+            "weighted",
+            w = number
+        )?;
+        for (output, width) in self.outputs.iter().zip(&widths) {
+            write!(f, "  {:>w$}", output.name, w = width)?;
+        }
+        writeln!(f, "  rule")?;
+        for (i, rule) in self.rules.iter().enumerate() {
+            write!(
+                f,
+                "  {:>w$}  {:>8.4}  {:>8.4}",
+                i,
+                rule.firing,
+                rule.weighted,
+                w = number
+            )?;
+            for (output, width) in self.outputs.iter().zip(&widths) {
+                match output.stage.rule_value(i) {
+                    Some(value) => write!(f, "  {:>w$.4}", value, w = width)?,
+                    None => write!(f, "  {:>w$}", "-", w = width)?,
+                }
+            }
+            writeln!(f, "  {}", rule.text)?;
+        }
+
+        writeln!(f, "outputs")?;
+        for output in &self.outputs {
+            write!(f, "  {} = {}", output.name, output.value)?;
+            if output.fallback {
+                write!(f, " (fallback)")?;
+            }
+            if let OutputStage::Sets {
+                continuous: true, ..
+            } = output.stage
+            {
+                write!(f, " (continuous, the sets are sampled)")?;
+            }
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+}
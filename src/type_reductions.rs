@@ -0,0 +1,207 @@
+pub type TypeReducer = fn(&[f64], &[f64], &[f64]) -> (f64, f64);
+
+// Type reducers turn an interval type-2 set, given as points with a lower and an upper
+// degree, into the interval (left, right) of its centroid.
+#[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum TypeReducers {
+    KarnikMendel,
+    EnhancedKarnikMendel,
+    NieTan,
+    WuMendel,
+    BegianMelekMendel {
+        m: f64,
+        n: f64,
+    },
+    #[cfg_attr(feature = "serde", serde(skip))]
+    Custom(TypeReducer),
+}
+
+impl TypeReducers {
+    pub fn reduce(&self, points: &[f64], lower: &[f64], upper: &[f64]) -> (f64, f64) {
+        match self {
+            Self::KarnikMendel => karnik_mendel(points, lower, upper),
+            Self::EnhancedKarnikMendel => enhanced_karnik_mendel(points, lower, upper),
+            Self::NieTan => nie_tan(points, lower, upper),
+            Self::WuMendel => wu_mendel(points, lower, upper),
+            Self::BegianMelekMendel { m, n } => begian_melek_mendel(points, lower, upper, *m, *n),
+            Self::Custom(f) => f(points, lower, upper),
+        }
+    }
+
+    pub fn defuzzify(&self, points: &[f64], lower: &[f64], upper: &[f64]) -> f64 {
+        let (left, right) = self.reduce(points, lower, upper);
+        (left + right) / 2.0
+    }
+}
+
+fn sorted(points: &[f64], lower: &[f64], upper: &[f64]) -> (Vec<f64>, Vec<f64>, Vec<f64>) {
+    let mut order: Vec<usize> = (0..points.len()).collect();
+    order.sort_by(|a, b| points[*a].partial_cmp(&points[*b]).unwrap());
+    (
+        order.iter().map(|i| points[*i]).collect(),
+        order.iter().map(|i| lower[*i]).collect(),
+        order.iter().map(|i| upper[*i]).collect(),
+    )
+}
+
+fn weighted_mean(points: &[f64], weights: &[f64]) -> f64 {
+    let numerator: f64 = points.iter().zip(weights).map(|(x, w)| x * w).sum();
+    let denominator: f64 = weights.iter().sum();
+    numerator / denominator
+}
+
+// points up to `switch` use `first`, the rest use `second`
+fn switched_mean(points: &[f64], first: &[f64], second: &[f64], switch: usize) -> f64 {
+    let weights: Vec<f64> = (0..points.len())
+        .map(|i| if i <= switch { first[i] } else { second[i] })
+        .collect();
+    weighted_mean(points, &weights)
+}
+
+fn switch_point(points: &[f64], y: f64) -> usize {
+    points
+        .iter()
+        .rposition(|x| *x <= y)
+        .unwrap_or(0)
+        .min(points.len().saturating_sub(2))
+}
+
+fn karnik_mendel_bound(points: &[f64], first: &[f64], second: &[f64]) -> f64 {
+    let middle: Vec<f64> = first
+        .iter()
+        .zip(second)
+        .map(|(a, b)| (a + b) / 2.0)
+        .collect();
+    let mut y = weighted_mean(points, &middle);
+    for _ in 0..=points.len() {
+        let next = switched_mean(points, first, second, switch_point(points, y));
+        if next.is_nan() || (next - y).abs() <= f64::EPSILON * y.abs().max(1.0) {
+            break;
+        }
+        y = next;
+    }
+    y
+}
+
+pub fn karnik_mendel(points: &[f64], lower: &[f64], upper: &[f64]) -> (f64, f64) {
+    let (points, lower, upper) = sorted(points, lower, upper);
+    (
+        karnik_mendel_bound(&points, &upper, &lower),
+        karnik_mendel_bound(&points, &lower, &upper),
+    )
+}
+
+fn enhanced_karnik_mendel_bound(
+    points: &[f64],
+    first: &[f64],
+    second: &[f64],
+    start: f64,
+) -> Option<f64> {
+    let n = points.len();
+    let mut k = ((n as f64 / start).round() as usize).clamp(1, n) - 1;
+    let weights: Vec<f64> = (0..n)
+        .map(|i| if i <= k { first[i] } else { second[i] })
+        .collect();
+    let mut a: f64 = points.iter().zip(&weights).map(|(x, w)| x * w).sum();
+    let mut b: f64 = weights.iter().sum();
+    for _ in 0..=n {
+        if b <= 0.0 {
+            return None;
+        }
+        let y = a / b;
+        let next = switch_point(points, y);
+        if next == k {
+            return Some(y);
+        }
+        let sign = if next > k { 1.0 } else { -1.0 };
+        for i in (k.min(next) + 1)..=k.max(next) {
+            a += sign * points[i] * (first[i] - second[i]);
+            b += sign * (first[i] - second[i]);
+        }
+        k = next;
+    }
+    Some(a / b)
+}
+
+pub fn enhanced_karnik_mendel(points: &[f64], lower: &[f64], upper: &[f64]) -> (f64, f64) {
+    let (points, lower, upper) = sorted(points, lower, upper);
+    // the starting switch points are the ones proposed by Wu and Mendel
+    let left = enhanced_karnik_mendel_bound(&points, &upper, &lower, 2.4);
+    let right = enhanced_karnik_mendel_bound(&points, &lower, &upper, 1.7);
+    match (left, right) {
+        (Some(left), Some(right)) => (left, right),
+        _ => (
+            karnik_mendel_bound(&points, &upper, &lower),
+            karnik_mendel_bound(&points, &lower, &upper),
+        ),
+    }
+}
+
+pub fn nie_tan(points: &[f64], lower: &[f64], upper: &[f64]) -> (f64, f64) {
+    let weights: Vec<f64> = lower.iter().zip(upper).map(|(l, u)| l + u).collect();
+    let y = weighted_mean(points, &weights);
+    (y, y)
+}
+
+// m and n are usually both 0.5
+pub fn begian_melek_mendel(
+    points: &[f64],
+    lower: &[f64],
+    upper: &[f64],
+    m: f64,
+    n: f64,
+) -> (f64, f64) {
+    let y_upper = weighted_mean(points, upper);
+    let y_lower = if lower.iter().sum::<f64>() > 0.0 {
+        weighted_mean(points, lower)
+    } else {
+        y_upper
+    };
+    let y = m * y_lower + n * y_upper;
+    (y, y)
+}
+
+pub fn wu_mendel(points: &[f64], lower: &[f64], upper: &[f64]) -> (f64, f64) {
+    let (points, lower, upper) = sorted(points, lower, upper);
+    let sum_lower: f64 = lower.iter().sum();
+    let sum_upper: f64 = upper.iter().sum();
+    // the bounds are not defined without a lower firing set
+    if sum_lower <= 0.0 {
+        return karnik_mendel(&points, &lower, &upper);
+    }
+    let first = points[0];
+    let last = points[points.len() - 1];
+    let y_lower = weighted_mean(&points, &lower);
+    let y_upper = weighted_mean(&points, &upper);
+    let spread: f64 =
+        upper.iter().zip(&lower).map(|(u, l)| u - l).sum::<f64>() / (sum_upper * sum_lower);
+
+    let left_max = y_lower.min(y_upper);
+    let a: f64 = lower
+        .iter()
+        .zip(&points)
+        .map(|(w, x)| w * (x - first))
+        .sum();
+    let b: f64 = upper.iter().zip(&points).map(|(w, x)| w * (last - x)).sum();
+    let left_min = if a + b > 0.0 {
+        left_max - spread * a * b / (a + b)
+    } else {
+        left_max
+    };
+
+    let right_min = y_lower.max(y_upper);
+    let a: f64 = upper
+        .iter()
+        .zip(&points)
+        .map(|(w, x)| w * (x - first))
+        .sum();
+    let b: f64 = lower.iter().zip(&points).map(|(w, x)| w * (last - x)).sum();
+    let right_max = if a + b > 0.0 {
+        right_min + spread * a * b / (a + b)
+    } else {
+        right_min
+    };
+
+    ((left_min + left_max) / 2.0, (right_min + right_max) / 2.0)
+}
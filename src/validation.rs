@@ -0,0 +1,543 @@
+use std::fmt;
+
+use crate::defuzzifications::Defuzzifiers;
+use crate::error::{check, FuzzyError};
+use crate::implications::Reasoning;
+use crate::membership_functions::{GetDegree, Kind};
+use crate::rules::{OutputRelation, Relation, Rule, TSKRule};
+use crate::variables::{
+    InputVariable, IntervalType2OutputVariable, NamedTerms, OutputModes, OutputVariable,
+    TSKOutputVariable,
+};
+
+// number of points used to look for the top of an input membership function
+const SAMPLES: usize = 1001;
+const TOLERANCE: f64 = 1e-6;
+
+// what a system does with the terms of its consequents
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) enum Consequents {
+    // fuzzy sets, every relation works
+    Sets,
+    // inverted terms, only plain and complemented terms
+    Inverses,
+    // crisp functions, only plain terms
+    Functions,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Severity {
+    Error,
+    Warning,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Diagnostic {
+    Error(FuzzyError),
+    DuplicateRule {
+        rule: usize,
+        first: usize,
+    },
+    UnusedTerm {
+        variable: String,
+        term: String,
+    },
+    NeverReachesOne {
+        variable: String,
+        term: String,
+        max: f64,
+    },
+}
+
+impl Diagnostic {
+    pub fn get_severity(&self) -> Severity {
+        match self {
+            Self::Error(_) => Severity::Error,
+            _ => Severity::Warning,
+        }
+    }
+
+    pub fn is_error(&self) -> bool {
+        self.get_severity() == Severity::Error
+    }
+}
+
+impl fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Error(err) => write!(f, "error: {}", err),
+            Self::DuplicateRule { rule, first } => {
+                write!(f, "warning: Rule {} is the same as rule {}", rule, first)
+            }
+            Self::UnusedTerm { variable, term } => write!(
+                f,
+                "warning: Term `{}` of `{}` is not used by any rule",
+                term, variable
+            ),
+            Self::NeverReachesOne {
+                variable,
+                term,
+                max,
+            } => write!(
+                f,
+                "warning: Term `{}` of `{}` never reaches 1.0 inside its range (max {})",
+                term, variable, max
+            ),
+        }
+    }
+}
+
+// what the systems check in their rules, `Rule` and `TSKRule` keep their outputs differently
+pub(crate) trait CheckedRule {
+    fn check_rule<I: NamedTerms, O: NamedTerms>(
+        &self,
+        rule_index: usize,
+        inputs: &[I],
+        outputs: &[O],
+    ) -> Result<(), FuzzyError>;
+
+    fn check_consequents<O: NamedTerms>(
+        &self,
+        rule_index: usize,
+        input_size: usize,
+        outputs: &[O],
+        consequents: Consequents,
+    ) -> Result<(), FuzzyError>;
+
+    // (variable, term) of every term the rule uses, the outputs come after the inputs
+    fn used_terms(&self, input_size: usize) -> Vec<(usize, usize)>;
+
+    fn same_rule(&self, other: &Self, inputs: &[InputVariable]) -> bool;
+}
+
+impl CheckedRule for Rule {
+    fn check_rule<I: NamedTerms, O: NamedTerms>(
+        &self,
+        rule_index: usize,
+        inputs: &[I],
+        outputs: &[O],
+    ) -> Result<(), FuzzyError> {
+        check_rule(rule_index, self, inputs, outputs)
+    }
+
+    fn check_consequents<O: NamedTerms>(
+        &self,
+        rule_index: usize,
+        input_size: usize,
+        outputs: &[O],
+        consequents: Consequents,
+    ) -> Result<(), FuzzyError> {
+        check_consequents(rule_index, self, input_size, outputs, consequents)
+    }
+
+    fn used_terms(&self, input_size: usize) -> Vec<(usize, usize)> {
+        let consequents = self
+            .get_output_rules(input_size)
+            .iter()
+            .enumerate()
+            .map(|(output, relation)| (input_size + output, relation));
+        self.get_conditions(input_size)
+            .into_iter()
+            .chain(consequents)
+            .filter_map(|(variable, relation)| Some((variable, relation.get_term()?)))
+            .collect()
+    }
+
+    // expressions are compared by their text
+    fn same_rule(&self, other: &Self, inputs: &[InputVariable]) -> bool {
+        let antecedent = |rule: &Rule| rule.get_antecedent().map(|e| e.to_text(inputs));
+        self.get_rules() == other.get_rules()
+            && self.get_kind() == other.get_kind()
+            && antecedent(self) == antecedent(other)
+    }
+}
+
+// TSK consequents are functions, so NOT and hedges of the output terms do not work
+impl CheckedRule for TSKRule {
+    fn check_rule<I: NamedTerms, O: NamedTerms>(
+        &self,
+        rule_index: usize,
+        inputs: &[I],
+        outputs: &[O],
+    ) -> Result<(), FuzzyError> {
+        let relations = self.get_input_rules().len() + self.get_output_rules().len();
+        let mut expected = outputs.len();
+        if self.get_antecedent().is_none() {
+            expected += inputs.len();
+        }
+        if relations != expected {
+            return Err(FuzzyError::RuleArity {
+                rule: rule_index,
+                expected,
+                found: relations,
+            });
+        }
+        check_rule(rule_index, self.as_ref(), inputs, &[] as &[O])?;
+        for (relation, variable) in self.get_output_rules().iter().zip(outputs) {
+            match relation {
+                OutputRelation::Term(relation) => match relation.get_term() {
+                    Some(term) if term >= variable.term_count() => {
+                        return Err(FuzzyError::UnknownTerm {
+                            rule: rule_index,
+                            variable: variable.variable_name().to_string(),
+                            index: term,
+                        })
+                    }
+                    _ => (),
+                },
+                OutputRelation::Function(function) => match function.get_input_size() {
+                    Some(found) if found != inputs.len() => {
+                        return Err(FuzzyError::InvalidParameters(format!(
+                            "Rule {} gives `{}` a consequent for {} inputs but the system has {}",
+                            rule_index,
+                            variable.variable_name(),
+                            found,
+                            inputs.len()
+                        )))
+                    }
+                    _ => (),
+                },
+            }
+        }
+        Ok(())
+    }
+
+    fn check_consequents<O: NamedTerms>(
+        &self,
+        rule_index: usize,
+        _input_size: usize,
+        outputs: &[O],
+        _consequents: Consequents,
+    ) -> Result<(), FuzzyError> {
+        for (relation, variable) in self.get_output_rules().iter().zip(outputs) {
+            if let OutputRelation::Term(relation @ (Relation::Not(_) | Relation::Hedged(..))) =
+                relation
+            {
+                return Err(FuzzyError::UnsupportedRelation {
+                    rule: rule_index,
+                    variable: variable.variable_name().to_string(),
+                    relation: relation.clone(),
+                });
+            }
+        }
+        Ok(())
+    }
+
+    fn used_terms(&self, input_size: usize) -> Vec<(usize, usize)> {
+        let consequents = self
+            .get_output_rules()
+            .iter()
+            .enumerate()
+            .filter_map(|(output, relation)| Some((input_size + output, relation.get_term()?)));
+        self.get_conditions()
+            .into_iter()
+            .filter_map(|(variable, relation)| Some((variable, relation.get_term()?)))
+            .chain(consequents)
+            .collect()
+    }
+
+    fn same_rule(&self, other: &Self, inputs: &[InputVariable]) -> bool {
+        self.as_ref().same_rule(other.as_ref(), inputs)
+            && self.get_output_rules() == other.get_output_rules()
+    }
+}
+
+pub(crate) fn check_rule<I: NamedTerms, O: NamedTerms>(
+    rule_index: usize,
+    rule: &Rule,
+    inputs: &[I],
+    outputs: &[O],
+) -> Result<(), FuzzyError> {
+    // rules with an expression only keep the outputs in their relations
+    let relations = rule.get_rules();
+    let mut expected = outputs.len();
+    if rule.get_antecedent().is_none() {
+        expected += inputs.len();
+    }
+    if relations.len() != expected {
+        return Err(FuzzyError::RuleArity {
+            rule: rule_index,
+            expected,
+            found: relations.len(),
+        });
+    }
+    let mut conditions = Vec::new();
+    for (input, relation) in rule.get_conditions(inputs.len()) {
+        match inputs.get(input) {
+            Some(variable) => conditions.push((relation, variable as &dyn NamedTerms)),
+            None => {
+                return Err(FuzzyError::InvalidParameters(format!(
+                    "Rule {} uses input {} but there are only {} inputs",
+                    rule_index,
+                    input,
+                    inputs.len()
+                )))
+            }
+        }
+    }
+    let consequents = rule
+        .get_output_rules(inputs.len())
+        .iter()
+        .zip(outputs.iter().map(|v| v as &dyn NamedTerms));
+    for (relation, variable) in conditions.into_iter().chain(consequents) {
+        match relation.get_term() {
+            Some(term) if term >= variable.term_count() => {
+                return Err(FuzzyError::UnknownTerm {
+                    rule: rule_index,
+                    variable: variable.variable_name().to_string(),
+                    index: term,
+                })
+            }
+            _ => (),
+        }
+    }
+    Ok(())
+}
+
+pub(crate) fn check_consequents<O: NamedTerms>(
+    rule_index: usize,
+    rule: &Rule,
+    input_size: usize,
+    outputs: &[O],
+    consequents: Consequents,
+) -> Result<(), FuzzyError> {
+    let supported = |relation: &Relation| match relation {
+        Relation::Term(_) => true,
+        Relation::Not(_) => consequents != Consequents::Functions,
+        Relation::Any => consequents != Consequents::Inverses,
+        _ => consequents == Consequents::Sets,
+    };
+    match rule
+        .get_output_rules(input_size)
+        .iter()
+        .zip(outputs)
+        .find(|(relation, _)| !supported(relation))
+    {
+        Some((relation, variable)) => Err(FuzzyError::UnsupportedRelation {
+            rule: rule_index,
+            variable: variable.variable_name().to_string(),
+            relation: relation.clone(),
+        }),
+        None => Ok(()),
+    }
+}
+
+pub(crate) fn check_output_ranges(outputs: &[OutputVariable]) -> Result<(), FuzzyError> {
+    for output in outputs {
+        let expected = output.get_universe().len();
+        for mr in output.get_memberships() {
+            if mr.get_mu().len() != expected {
+                return Err(FuzzyError::RangeLength {
+                    variable: output.get_name().clone(),
+                    term: mr.get_name(),
+                    expected,
+                    found: mr.get_mu().len(),
+                });
+            }
+        }
+    }
+    Ok(())
+}
+
+pub(crate) fn check_consequent_inputs(
+    input_size: usize,
+    outputs: &[TSKOutputVariable],
+) -> Result<(), FuzzyError> {
+    for output in outputs {
+        for (i, mf) in output.get_memberships().iter().enumerate() {
+            match mf.get_input_size() {
+                Some(found) if found != input_size => {
+                    return Err(FuzzyError::ConsequentInputs {
+                        variable: output.get_name().clone(),
+                        term: output.membership_function_name(i as i32),
+                        expected: input_size,
+                        found,
+                    })
+                }
+                _ => (),
+            }
+        }
+    }
+    Ok(())
+}
+
+pub(crate) fn check_interval_output_ranges(
+    outputs: &[IntervalType2OutputVariable],
+) -> Result<(), FuzzyError> {
+    for output in outputs {
+        let expected = output.get_universe().len();
+        for mr in output.get_memberships() {
+            if mr.get_lower().len() != expected {
+                return Err(FuzzyError::RangeLength {
+                    variable: output.get_name().clone(),
+                    term: mr.get_name(),
+                    expected,
+                    found: mr.get_lower().len(),
+                });
+            }
+        }
+    }
+    Ok(())
+}
+
+// only Centroid and Bisection integrate the shapes, the others need the universe
+pub(crate) fn check_output_modes(
+    outputs: &[OutputVariable],
+    defuzzifier: &Defuzzifiers,
+) -> Result<(), FuzzyError> {
+    let integrated = matches!(
+        defuzzifier,
+        Defuzzifiers::Centroid | Defuzzifiers::Bisection
+    );
+    for output in outputs {
+        if output.get_mode() == OutputModes::Continuous && !integrated {
+            return Err(FuzzyError::InvalidParameters(format!(
+                "Output `{}` is continuous but {:?} only works on the universe, use Centroid or Bisection",
+                output.get_name(),
+                defuzzifier
+            )));
+        }
+    }
+    Ok(())
+}
+
+// the rule based defuzzifiers skip the aggregation, so they can't intersect implicative rules
+pub(crate) fn check_reasoning(
+    defuzzifier: &Defuzzifiers,
+    reasoning: Reasoning,
+) -> Result<(), FuzzyError> {
+    check(
+        !(defuzzifier.is_rule_based() && reasoning == Reasoning::Implicative),
+        "WeightedAverage, Height and CenterOfSums need conjunctive reasoning",
+    )
+}
+
+pub(crate) fn validate_system<O: NamedTerms, R: CheckedRule>(
+    inputs: &[InputVariable],
+    outputs: &[O],
+    rules: &[R],
+    consequents: Consequents,
+) -> Vec<Diagnostic> {
+    let mut diagnostics = Vec::new();
+    if inputs.is_empty() {
+        diagnostics.push(Diagnostic::Error(FuzzyError::NoInputs));
+    }
+    if outputs.is_empty() {
+        diagnostics.push(Diagnostic::Error(FuzzyError::NoOutputs));
+    }
+    if rules.is_empty() {
+        diagnostics.push(Diagnostic::Error(FuzzyError::EmptyRuleBase));
+    }
+
+    let mut used: Vec<Vec<bool>> = inputs
+        .iter()
+        .map(|v| v as &dyn NamedTerms)
+        .chain(outputs.iter().map(|v| v as &dyn NamedTerms))
+        .map(|v| vec![false; v.term_count()])
+        .collect();
+    for (i, rule) in rules.iter().enumerate() {
+        let result = rule
+            .check_rule(i, inputs, outputs)
+            .and_then(|_| rule.check_consequents(i, inputs.len(), outputs, consequents));
+        if let Err(err) = result {
+            diagnostics.push(Diagnostic::Error(err));
+            continue;
+        }
+        for (variable, term) in rule.used_terms(inputs.len()) {
+            used[variable][term] = true;
+        }
+        if let Some(first) = rules[..i]
+            .iter()
+            .position(|other| rule.same_rule(other, inputs))
+        {
+            diagnostics.push(Diagnostic::DuplicateRule { rule: i, first });
+        }
+    }
+
+    if !rules.is_empty() {
+        let variables = inputs
+            .iter()
+            .map(|v| v as &dyn NamedTerms)
+            .chain(outputs.iter().map(|v| v as &dyn NamedTerms));
+        for (i, (variable, terms)) in variables.zip(used).enumerate() {
+            // an output without rules has nothing to defuzzify
+            if i >= inputs.len() && !terms.iter().any(|used| *used) {
+                diagnostics.push(Diagnostic::Error(FuzzyError::UnusedOutput {
+                    variable: variable.variable_name().to_string(),
+                }));
+                continue;
+            }
+            let names = variable.term_names();
+            for (name, _) in names.into_iter().zip(terms).filter(|(_, used)| !used) {
+                diagnostics.push(Diagnostic::UnusedTerm {
+                    variable: variable.variable_name().to_string(),
+                    term: name,
+                });
+            }
+        }
+    }
+
+    for input in inputs {
+        let (start, stop) = *input.get_range();
+        for mf in input.get_memberships() {
+            let mut points: Vec<f64> = (0..SAMPLES)
+                .map(|i| start + (stop - start) * i as f64 / (SAMPLES - 1) as f64)
+                .collect();
+            points.extend(peak_candidates(mf.get_kind(), start, stop));
+            let max = points
+                .into_iter()
+                .map(|x| mf.get_degree(x))
+                .fold(0.0, f64::max);
+            if max < 1.0 - TOLERANCE {
+                diagnostics.push(Diagnostic::NeverReachesOne {
+                    variable: input.get_name().clone(),
+                    term: mf.get_name().clone(),
+                    max,
+                });
+            }
+        }
+    }
+    diagnostics
+}
+
+pub(crate) fn validate_output_ranges(outputs: &[OutputVariable]) -> Vec<Diagnostic> {
+    let mut diagnostics = Vec::new();
+    for output in outputs {
+        let (start, stop) = *output.get_range();
+        for mr in output.get_memberships() {
+            if mr.get_mu().len() != output.get_universe().len() {
+                diagnostics.push(Diagnostic::Error(FuzzyError::RangeLength {
+                    variable: output.get_name().clone(),
+                    term: mr.get_name(),
+                    expected: output.get_universe().len(),
+                    found: mr.get_mu().len(),
+                }));
+                continue;
+            }
+            let mut max = mr.get_mu().iter().cloned().fold(0.0, f64::max);
+            // the universe may step over the peak, so ask the shape itself when we know it
+            if let Some(kind) = mr.get_kind() {
+                max = peak_candidates(kind, start, stop)
+                    .into_iter()
+                    .map(|x| kind.get_degree(x))
+                    .fold(max, f64::max);
+            }
+            if max < 1.0 - TOLERANCE {
+                diagnostics.push(Diagnostic::NeverReachesOne {
+                    variable: output.get_name().clone(),
+                    term: mr.get_name(),
+                    max,
+                });
+            }
+        }
+    }
+    diagnostics
+}
+
+// the peaks of the built-in shapes sit on one of their parameters
+fn peak_candidates(kind: &Kind, start: f64, stop: f64) -> Vec<f64> {
+    kind.get_parameters()
+        .into_iter()
+        .filter(|x| (start..=stop).contains(x))
+        .collect()
+}
@@ -1,9 +1,77 @@
+use crate::defuzzifications::{Fallbacks, TSKDefuzzifiers};
+use crate::error::{check, FuzzyError};
 use crate::membership_functions::{
-    linear_membership, GetDegree, MembershipFunction, TSKMembershipFunction,
+    GetDegree, GetInterval, GetInverse, IntervalType2MembershipFunction, MembershipFunction,
+    TSKMembershipFunction, TsukamotoMembershipFunction,
 };
-use crate::membership_ranges::MembershipRange;
+use crate::membership_ranges::{IntervalType2MembershipRange, MembershipRange};
+
+pub trait NamedTerms {
+    fn variable_name(&self) -> &str;
+    fn term_names(&self) -> Vec<String>;
+
+    fn term_count(&self) -> usize {
+        self.term_names().len()
+    }
+}
+
+// Sampled outputs work on the points of their universe, continuous outputs integrate the
+// shapes of their terms. Only Centroid and Bisection can integrate, the systems return an
+// error for a continuous output with any other defuzzifier.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum OutputModes {
+    #[default]
+    Sampled,
+    Continuous,
+}
+
+// What an output gives when no rule fires. `middle` is the middle of the output range and
+// `last` the value of the output in the previous evaluation, if the caller kept it.
+#[derive(Debug, Clone, PartialEq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum NoActivationPolicies {
+    Value(f64),
+    HoldLast,
+    #[default]
+    MidRange,
+    Error,
+}
+
+// the fallback of a Mamdani system is the policy of the outputs that have none
+impl From<&Fallbacks> for NoActivationPolicies {
+    fn from(fallback: &Fallbacks) -> Self {
+        match fallback {
+            Fallbacks::MidRange => Self::MidRange,
+            Fallbacks::Value(value) => Self::Value(*value),
+        }
+    }
+}
+
+impl NoActivationPolicies {
+    pub fn resolve(&self, middle: f64, last: Option<f64>) -> Result<f64, FuzzyError> {
+        match self {
+            Self::Value(value) => Ok(*value),
+            // without a previous value there is nothing to hold
+            Self::HoldLast => Ok(last.unwrap_or(middle)),
+            Self::MidRange => Ok(middle),
+            Self::Error => Err(FuzzyError::ZeroFiringStrength),
+        }
+    }
+}
+
+fn universe(range: (f64, f64), n: i32) -> Vec<f64> {
+    let mut universe = Vec::new();
+    let (start, stop) = range;
+    let delta = (stop - start) / (n as f64);
+    for i in 0..n {
+        universe.push(start + delta * (i as f64))
+    }
+    universe
+}
 
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct InputVariable {
     name: String,
     range: (f64, f64),
@@ -35,37 +103,74 @@ impl InputVariable {
     pub fn get_range(&self) -> &(f64, f64) {
         &self.range
     }
+
+    pub fn get_memberships(&self) -> &Vec<MembershipFunction> {
+        &self.mfs
+    }
 }
 
 #[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct OutputVariable {
     name: String,
+    range: (f64, f64),
     mrs: Vec<MembershipRange>,
     universe: Vec<f64>,
+    // None uses the fallback of the system
+    #[cfg_attr(feature = "serde", serde(default))]
+    no_activation: Option<NoActivationPolicies>,
+    #[cfg_attr(feature = "serde", serde(default))]
+    mode: OutputModes,
 }
 
 impl OutputVariable {
     pub fn new(name: String, range: (f64, f64), n: i32) -> Self {
-        let mut universe = Vec::new();
-        let (start, stop) = range;
-        let delta = (stop - start) / (n as f64);
-        for i in 0..n {
-            universe.push(start + delta * (i as f64))
-        }
         Self {
             name,
+            range,
             mrs: Vec::new(),
-            universe,
+            universe: universe(range, n),
+            no_activation: None,
+            mode: OutputModes::Sampled,
         }
     }
     pub fn add_membership(&mut self, membership_rang: MembershipRange) {
         self.mrs.push(membership_rang)
     }
 
+    pub fn add_mf(&mut self, mf: &MembershipFunction) {
+        self.mrs.push(MembershipRange::from_mf(mf, &self.universe))
+    }
+
+    pub fn set_resolution(&mut self, n: i32) {
+        self.try_set_resolution(n)
+            .unwrap_or_else(|err| panic!("{}", err))
+    }
+
+    // every term is sampled again, so each of them needs its shape
+    pub fn try_set_resolution(&mut self, n: i32) -> Result<(), FuzzyError> {
+        check(n > 0, "n must be greater than 0")?;
+        if let Some(mr) = self.mrs.iter().find(|mr| mr.get_kind().is_none()) {
+            return Err(FuzzyError::MissingShape {
+                variable: self.name.clone(),
+                term: mr.get_name(),
+            });
+        }
+        self.universe = universe(self.range, n);
+        for mr in self.mrs.iter_mut() {
+            mr.resample(&self.universe);
+        }
+        Ok(())
+    }
+
     pub fn get_mu(&self, idx: usize) -> &Vec<f64> {
         self.mrs[idx].get_mu()
     }
 
+    pub fn membership_function_name(&self, idx: i32) -> String {
+        self.mrs[idx as usize].get_name()
+    }
+
     pub fn get_universe(&self) -> &Vec<f64> {
         &self.universe
     }
@@ -77,12 +182,190 @@ impl OutputVariable {
     pub fn get_universe_by_idx(&self, idx: usize) -> f64 {
         self.universe[idx].clone()
     }
+
+    pub fn get_range(&self) -> &(f64, f64) {
+        &self.range
+    }
+
+    pub fn get_memberships(&self) -> &Vec<MembershipRange> {
+        &self.mrs
+    }
+
+    pub fn get_no_activation_policy(&self) -> Option<&NoActivationPolicies> {
+        self.no_activation.as_ref()
+    }
+
+    pub fn set_no_activation_policy(&mut self, policy: NoActivationPolicies) {
+        self.no_activation = Some(policy);
+    }
+
+    pub fn get_mode(&self) -> OutputModes {
+        self.mode
+    }
+
+    // continuous outputs need terms with a shape, see MembershipRange::get_kind
+    pub fn set_mode(&mut self, mode: OutputModes) {
+        self.mode = mode;
+    }
+}
+
+#[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct TsukamotoOutputVariable {
+    name: String,
+    range: (f64, f64),
+    mfs: Vec<TsukamotoMembershipFunction>,
+    // None is MidRange
+    #[cfg_attr(feature = "serde", serde(default))]
+    no_activation: Option<NoActivationPolicies>,
+}
+
+impl TsukamotoOutputVariable {
+    pub fn new(name: String, range: (f64, f64)) -> Self {
+        Self {
+            name,
+            range,
+            mfs: Vec::new(),
+            no_activation: None,
+        }
+    }
+
+    pub fn add_membership(&mut self, membership: TsukamotoMembershipFunction) {
+        self.mfs.push(membership);
+    }
+
+    // the crisp value where term `idx` reaches `mu`, kept inside the range
+    pub fn get_inverse(&self, idx: usize, mu: f64) -> f64 {
+        let (start, stop) = self.range;
+        self.mfs[idx].get_inverse(mu).clamp(start, stop)
+    }
+
+    pub fn membership_function_name(&self, idx: i32) -> String {
+        self.mfs[idx as usize].get_name().clone()
+    }
+
+    pub fn get_name(&self) -> &String {
+        &self.name
+    }
+
+    pub fn get_range(&self) -> &(f64, f64) {
+        &self.range
+    }
+
+    pub fn get_memberships(&self) -> &Vec<TsukamotoMembershipFunction> {
+        &self.mfs
+    }
+
+    pub fn get_no_activation_policy(&self) -> Option<&NoActivationPolicies> {
+        self.no_activation.as_ref()
+    }
+
+    pub fn set_no_activation_policy(&mut self, policy: NoActivationPolicies) {
+        self.no_activation = Some(policy);
+    }
+}
+
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct IntervalType2InputVariable {
+    name: String,
+    range: (f64, f64),
+    mfs: Vec<IntervalType2MembershipFunction>,
+}
+
+impl IntervalType2InputVariable {
+    pub fn new(name: String, range: (f64, f64)) -> Self {
+        Self {
+            name,
+            range,
+            mfs: Vec::new(),
+        }
+    }
+    pub fn add_membership(&mut self, mf: IntervalType2MembershipFunction) {
+        self.mfs.push(mf);
+    }
+
+    pub fn fuzzify(&self, idx: usize, x: f64) -> (f64, f64) {
+        self.mfs[idx].get_interval(x)
+    }
+    pub fn membership_function_name(&self, idx: i32) -> String {
+        self.mfs[idx as usize].get_name().clone()
+    }
+    pub fn get_name(&self) -> &String {
+        &self.name
+    }
+
+    pub fn get_range(&self) -> &(f64, f64) {
+        &self.range
+    }
+
+    pub fn get_memberships(&self) -> &Vec<IntervalType2MembershipFunction> {
+        &self.mfs
+    }
 }
 
 #[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct IntervalType2OutputVariable {
+    name: String,
+    range: (f64, f64),
+    mrs: Vec<IntervalType2MembershipRange>,
+    universe: Vec<f64>,
+}
+
+impl IntervalType2OutputVariable {
+    pub fn new(name: String, range: (f64, f64), n: i32) -> Self {
+        Self {
+            name,
+            range,
+            mrs: Vec::new(),
+            universe: universe(range, n),
+        }
+    }
+    pub fn add_membership(&mut self, membership_range: IntervalType2MembershipRange) {
+        self.mrs.push(membership_range)
+    }
+
+    pub fn get_lower(&self, idx: usize) -> &Vec<f64> {
+        self.mrs[idx].get_lower()
+    }
+
+    pub fn get_upper(&self, idx: usize) -> &Vec<f64> {
+        self.mrs[idx].get_upper()
+    }
+
+    pub fn membership_function_name(&self, idx: i32) -> String {
+        self.mrs[idx as usize].get_name()
+    }
+
+    pub fn get_universe(&self) -> &Vec<f64> {
+        &self.universe
+    }
+
+    pub fn get_name(&self) -> &String {
+        &self.name
+    }
+
+    pub fn get_range(&self) -> &(f64, f64) {
+        &self.range
+    }
+
+    pub fn get_memberships(&self) -> &Vec<IntervalType2MembershipRange> {
+        &self.mrs
+    }
+}
+
+#[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct TSKOutputVariable {
     name: String,
     mfs: Vec<TSKMembershipFunction>,
+    // None is MidRange
+    #[cfg_attr(feature = "serde", serde(default))]
+    no_activation: Option<NoActivationPolicies>,
+    // None uses the defuzzification of the system
+    #[cfg_attr(feature = "serde", serde(default))]
+    defuzzification: Option<TSKDefuzzifiers>,
 }
 
 impl TSKOutputVariable {
@@ -90,30 +373,184 @@ impl TSKOutputVariable {
         Self {
             name,
             mfs: Vec::new(),
+            no_activation: None,
+            defuzzification: None,
         }
     }
 
     pub fn add_membership(&mut self, membership: TSKMembershipFunction) {
-        self.mfs.push(membership);
+        self.try_add_membership(membership)
+            .unwrap_or_else(|err| panic!("{}", err))
+    }
+
+    // every consequent of an output must be made for the same inputs
+    pub fn try_add_membership(
+        &mut self,
+        membership: TSKMembershipFunction,
+    ) -> Result<(), FuzzyError> {
+        if let TSKMembershipFunction::Affine(coefficients) = &membership {
+            check(
+                !coefficients.is_empty(),
+                "an affine consequent needs at least the constant",
+            )?;
+        }
+        match (self.get_input_size(), membership.get_input_size()) {
+            (Some(expected), Some(found)) if expected != found => {
+                Err(FuzzyError::ConsequentInputs {
+                    variable: self.name.clone(),
+                    term: self.membership_function_name(self.mfs.len() as i32),
+                    expected,
+                    found,
+                })
+            }
+            _ => {
+                self.mfs.push(membership);
+                Ok(())
+            }
+        }
     }
 
     pub fn add_constant_membership(&mut self, value: f64) {
-        self.mfs.push(TSKMembershipFunction::Constant(value));
+        self.add_membership(TSKMembershipFunction::Constant(value));
     }
 
     pub fn add_linear_membership(&mut self, coefficients: Vec<f64>) {
-        self.mfs.push(TSKMembershipFunction::Linear(coefficients));
+        self.add_membership(TSKMembershipFunction::Linear(coefficients));
+    }
+
+    // the coefficients of the inputs and the constant last
+    pub fn add_affine_membership(&mut self, coefficients: Vec<f64>) {
+        self.add_membership(TSKMembershipFunction::Affine(coefficients));
+    }
+
+    pub fn try_add_affine_membership(&mut self, coefficients: Vec<f64>) -> Result<(), FuzzyError> {
+        self.try_add_membership(TSKMembershipFunction::Affine(coefficients))
+    }
+
+    // the number of inputs the consequents are made for, None if they take any number
+    pub fn get_input_size(&self) -> Option<usize> {
+        self.mfs
+            .iter()
+            .find_map(TSKMembershipFunction::get_input_size)
     }
 
     pub fn get_mu(&self, idx: usize, input_vec: &Vec<f64>) -> f64 {
-        match &self.mfs[idx] {
-            TSKMembershipFunction::Constant(c) => *c,
-            TSKMembershipFunction::Linear(coeff) => linear_membership(&coeff, input_vec),
-            TSKMembershipFunction::Custom(fun) => fun(input_vec),
-        }
+        self.mfs[idx].get_mu(input_vec)
     }
 
     pub fn get_name(&self) -> &String {
         &self.name
     }
+
+    pub fn get_memberships(&self) -> &Vec<TSKMembershipFunction> {
+        &self.mfs
+    }
+
+    // TSK outputs have no range, the middle is taken between the consequents of the rules
+    pub fn get_no_activation_policy(&self) -> Option<&NoActivationPolicies> {
+        self.no_activation.as_ref()
+    }
+
+    pub fn set_no_activation_policy(&mut self, policy: NoActivationPolicies) {
+        self.no_activation = Some(policy);
+    }
+
+    pub fn get_defuzzification_method(&self) -> Option<&TSKDefuzzifiers> {
+        self.defuzzification.as_ref()
+    }
+
+    pub fn set_defuzzification(&mut self, defuzzification: TSKDefuzzifiers) {
+        self.defuzzification = Some(defuzzification);
+    }
+
+    pub fn membership_function_name(&self, idx: i32) -> String {
+        format!("{}{}", self.name, idx + 1)
+    }
+}
+
+impl NamedTerms for InputVariable {
+    fn variable_name(&self) -> &str {
+        &self.name
+    }
+
+    fn term_names(&self) -> Vec<String> {
+        self.mfs.iter().map(|mf| mf.get_name().clone()).collect()
+    }
+
+    fn term_count(&self) -> usize {
+        self.mfs.len()
+    }
+}
+
+impl NamedTerms for OutputVariable {
+    fn variable_name(&self) -> &str {
+        &self.name
+    }
+
+    fn term_names(&self) -> Vec<String> {
+        self.mrs.iter().map(|mr| mr.get_name()).collect()
+    }
+
+    fn term_count(&self) -> usize {
+        self.mrs.len()
+    }
+}
+
+// TSK consequents have no names, they are called `<output><n>` starting from 1
+impl NamedTerms for TSKOutputVariable {
+    fn variable_name(&self) -> &str {
+        &self.name
+    }
+
+    fn term_names(&self) -> Vec<String> {
+        (0..self.mfs.len())
+            .map(|idx| self.membership_function_name(idx as i32))
+            .collect()
+    }
+
+    fn term_count(&self) -> usize {
+        self.mfs.len()
+    }
+}
+
+impl NamedTerms for IntervalType2InputVariable {
+    fn variable_name(&self) -> &str {
+        &self.name
+    }
+
+    fn term_names(&self) -> Vec<String> {
+        self.mfs.iter().map(|mf| mf.get_name().clone()).collect()
+    }
+
+    fn term_count(&self) -> usize {
+        self.mfs.len()
+    }
+}
+
+impl NamedTerms for IntervalType2OutputVariable {
+    fn variable_name(&self) -> &str {
+        &self.name
+    }
+
+    fn term_names(&self) -> Vec<String> {
+        self.mrs.iter().map(|mr| mr.get_name()).collect()
+    }
+
+    fn term_count(&self) -> usize {
+        self.mrs.len()
+    }
+}
+
+impl NamedTerms for TsukamotoOutputVariable {
+    fn variable_name(&self) -> &str {
+        &self.name
+    }
+
+    fn term_names(&self) -> Vec<String> {
+        self.mfs.iter().map(|mf| mf.get_name().clone()).collect()
+    }
+
+    fn term_count(&self) -> usize {
+        self.mfs.len()
+    }
 }